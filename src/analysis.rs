@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+
+use crate::token::{Token, TOKEN_TYPE_WORD};
+use crate::{LexxError, Lexxer};
+
+/// WordFrequency tallies how many times each [TOKEN_TYPE_WORD] value is seen in a stream of
+/// [Token]s, ignoring every other token type. With `case_fold` set words are lowercased before
+/// they are counted so `The` and `the` are the same word.
+///
+/// # Example
+///
+/// ```rust
+/// use lexx::Lexx;
+/// use lexx::analysis::WordFrequency;
+/// use lexx::input::InputString;
+///
+/// let mut lexx = Lexx::<512>::from_spec(
+///     "word,whitespace,symbol",
+///     Box::new(InputString::new(String::from("The fox saw the other fox."))),
+/// ).unwrap();
+///
+/// let mut frequency = WordFrequency::new(true);
+/// frequency.consume(&mut lexx).unwrap();
+///
+/// assert_eq!(frequency.total(), 6);
+/// assert_eq!(
+///     frequency.sorted()[0..2],
+///     [(String::from("fox"), 2), (String::from("the"), 2)]
+/// );
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct WordFrequency {
+    /// The count for each word seen so far.
+    pub counts: HashMap<String, usize>,
+    /// If words are lowercased before they are counted.
+    pub case_fold: bool,
+}
+
+impl WordFrequency {
+    /// Build an empty word frequency
+    ///
+    /// # Arguments
+    ///
+    /// * `case_fold` - if words are lowercased before they are counted
+    ///
+    pub fn new(case_fold: bool) -> WordFrequency {
+        WordFrequency {
+            counts: HashMap::new(),
+            case_fold,
+        }
+    }
+
+    /// Counts `token` if it is a [TOKEN_TYPE_WORD].
+    pub fn add(&mut self, token: &Token) {
+        if token.token_type != TOKEN_TYPE_WORD {
+            return;
+        }
+        let word = if self.case_fold {
+            token.value.to_lowercase()
+        } else {
+            token.value.clone()
+        };
+        *self.counts.entry(word).or_insert(0) += 1;
+    }
+
+    /// Counts every [Token] from `lexx` until it runs out of input. Stops at and returns the
+    /// first error, anything counted before it is kept.
+    pub fn consume(&mut self, lexx: &mut dyn Lexxer) -> Result<(), LexxError> {
+        while let Some(token) = lexx.next_token()? {
+            self.add(&token);
+        }
+        Ok(())
+    }
+
+    /// The total number of words counted.
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// Returns each word with its count, the most frequent first. Words with the same count are
+    /// in alphabetical order.
+    pub fn sorted(&self) -> Vec<(String, usize)> {
+        let mut sorted: Vec<(String, usize)> =
+            self.counts.iter().map(|(w, c)| (w.clone(), *c)).collect();
+        sorted.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        sorted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::WordFrequency;
+    use crate::input::InputString;
+    use crate::Lexx;
+
+    #[test]
+    fn analysis_word_frequency() {
+        let src = "The quick fox and the lazy dog, the fox 42 times.";
+        let make = || {
+            Lexx::<512>::from_spec(
+                "word,whitespace,integer,symbol",
+                Box::new(InputString::new(String::from(src))),
+            )
+            .unwrap()
+        };
+
+        let mut frequency = WordFrequency::new(false);
+        frequency.consume(&mut make()).unwrap();
+        assert_eq!(frequency.total(), 10);
+        assert_eq!(frequency.counts.get("42"), None);
+        assert_eq!(
+            frequency.sorted()[0..3],
+            [
+                (String::from("fox"), 2),
+                (String::from("the"), 2),
+                (String::from("The"), 1)
+            ]
+        );
+
+        let mut frequency = WordFrequency::new(true);
+        frequency.consume(&mut make()).unwrap();
+        assert_eq!(frequency.total(), 10);
+        assert_eq!(frequency.counts.len(), 7);
+        assert_eq!(
+            frequency.sorted()[0..3],
+            [
+                (String::from("the"), 3),
+                (String::from("fox"), 2),
+                (String::from("and"), 1)
+            ]
+        );
+    }
+}
@@ -1,8 +1,11 @@
 use crate::LexxError;
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
 use std::error::Error;
 use std::fmt;
 use std::fmt::Debug;
 use std::io::Read;
+use std::rc::Rc;
 use std::str::{from_utf8, from_utf8_unchecked};
 
 /// maximum size for input strings for the [InputReader](InputReader)
@@ -64,8 +67,130 @@ impl From<LexxInputError> for LexxError {
 pub trait LexxInput: Debug {
     /// returns the next LexxInputResult
     fn next(&mut self) -> Result<Option<char>, LexxInputError>;
+    /// returns the total number of [char]s in the input if it is known up front, [None] for
+    /// streaming inputs. Defaults to [None].
+    fn source_len(&self) -> Option<usize> {
+        None
+    }
+    /// returns `false` if more [char]s may still arrive after [LexxInput::next] returned
+    /// [Ok(None)], see [AppendableInput]. Defaults to `true`.
+    fn is_closed(&self) -> bool {
+        true
+    }
+    /// returns how many [char]s have been read so far if the input can go back to this point
+    /// with [LexxInput::seek], [None] if it can't. Defaults to [None].
+    fn mark(&self) -> Option<usize> {
+        None
+    }
+    /// goes back to a point returned by [LexxInput::mark] so the [char]s after it are read
+    /// again. Defaults to an error.
+    fn seek(&mut self, _mark: usize) -> Result<(), LexxInputError> {
+        Err(LexxInputError::Error(String::from("this input can't seek")))
+    }
+}
+
+/// Reads the rest of `input` and returns how many lines it has, without running any
+/// [Matcher](crate::matcher::Matcher)s. Lines end the same way they do for the
+/// [WhitespaceMatcher](crate::matcher_whitespace::WhitespaceMatcher), at a `\n` or a `\r`, so
+/// `\r\n` is a single line break and a lone `\r` is one too. The count is the
+/// [Token::line](crate::token::Token::line) the last [char] would have, a final line break
+/// doesn't add an empty line and an empty input has no lines.
+///
+/// # Arguments
+///
+/// * `input` - The [LexxInput] to count, it is consumed
+///
+/// # Example
+///
+/// ```rust
+/// use lexx::input::{count_lines, InputString};
+///
+/// assert_eq!(count_lines(&mut InputString::new(String::from("one\r\ntwo\n\nfour\n"))), Ok(4));
+/// assert_eq!(count_lines(&mut InputString::new(String::new())), Ok(0));
+/// ```
+pub fn count_lines<I: LexxInput>(input: &mut I) -> Result<usize, LexxInputError> {
+    let mut lines = 0;
+    let mut line_start = true;
+    let mut after_cr = false;
+    while let Some(c) = input.next()? {
+        // the \n of a \r\n was already counted at the \r
+        if c == '\n' && after_cr {
+            after_cr = false;
+            continue;
+        }
+        if line_start {
+            lines += 1;
+        }
+        line_start = c == '\n' || c == '\r';
+        after_cr = c == '\r';
+    }
+    Ok(lines)
+}
+
+/// The [char]s that end a line, see [detect_line_ending].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum LineEnding {
+    /// `\n`, as on Unix, this is the default.
+    #[default]
+    Lf,
+    /// `\r\n`, as on Windows.
+    CrLf,
+    /// A lone `\r`, as on classic Mac OS.
+    Cr,
+}
+
+impl LineEnding {
+    /// The [char]s of this line ending.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+            LineEnding::Cr => "\r",
+        }
+    }
 }
 
+/// Reads the rest of `input` and returns the [LineEnding] most of its lines end with, for
+/// example so a formatter can write its output with the same line endings. If there is a tie
+/// [LineEnding::Lf] wins over [LineEnding::CrLf], which wins over [LineEnding::Cr], and an input
+/// with no line breaks at all is [LineEnding::Lf].
+///
+/// # Arguments
+///
+/// * `input` - The [LexxInput] to check, it is consumed
+///
+/// # Example
+///
+/// ```rust
+/// use lexx::input::{detect_line_ending, InputString, LineEnding};
+///
+/// let mut input = InputString::new(String::from("one\r\ntwo\nthree\r\n"));
+/// assert_eq!(detect_line_ending(&mut input), Ok(LineEnding::CrLf));
+/// assert_eq!(LineEnding::CrLf.as_str(), "\r\n");
+/// ```
+pub fn detect_line_ending<I: LexxInput>(input: &mut I) -> Result<LineEnding, LexxInputError> {
+    let (mut lf, mut crlf, mut cr) = (0, 0, 0);
+    let mut after_cr = false;
+    while let Some(c) = input.next()? {
+        match c {
+            '\n' if after_cr => crlf += 1,
+            '\n' => lf += 1,
+            _ if after_cr => cr += 1,
+            _ => {}
+        }
+        after_cr = c == '\r';
+    }
+    if after_cr {
+        cr += 1;
+    }
+    Ok(if lf >= crlf && lf >= cr {
+        LineEnding::Lf
+    } else if crlf >= cr {
+        LineEnding::CrLf
+    } else {
+        LineEnding::Cr
+    })
+}
 
 /// Implements [LexxInput](LexxInput) for a passed in [String].
 #[derive(Debug)]
@@ -103,6 +228,51 @@ impl InputString {
             chars,
         }
     }
+
+    /// Creates a new InputString of the [char]s from `start` up to, but not including, `end`,
+    /// for lexing just part of a larger input such as the lines an editor has changed. Both are
+    /// [char] indices, not byte offsets, and are clamped to the length of the string. Nothing
+    /// that has been read from this InputString is taken into account.
+    ///
+    /// The [Token]s of the slice start at line 1, column 1, use
+    /// [Lexxer::reset_position](crate::Lexxer::reset_position) to have them start where the
+    /// slice does in the whole input instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The index of the first [char] in the slice
+    /// * `end` - The index just past the last [char] in the slice
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lexx::{Lexx, Lexxer};
+    /// use lexx::input::InputString;
+    /// use lexx::matcher_word::WordMatcher;
+    ///
+    /// let input = InputString::new(String::from("The quick fox"));
+    /// let mut lexx = Lexx::<512>::new(
+    ///     Box::new(input.slice(4, 9)),
+    ///     vec![Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] })],
+    /// );
+    /// lexx.reset_position(1, 5);
+    ///
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "quick" && t.column == 5));
+    /// assert!(matches!(lexx.next_token(), Ok(None)));
+    /// ```
+    ///
+    /// [Token]: crate::token::Token
+    pub fn slice(&self, start: usize, end: usize) -> InputString {
+        let end = end.min(self.size);
+        let start = start.min(end);
+        let mut chars = Box::new(['x'; BUFFER_SIZE]);
+        chars[0..end - start].copy_from_slice(&self.chars[start..end]);
+        InputString {
+            index: 0,
+            size: end - start,
+            chars,
+        }
+    }
 }
 
 impl LexxInput for InputString {
@@ -115,9 +285,238 @@ impl LexxInput for InputString {
         }
         return Ok(None);
     }
+    /// Returns the number of [char]s in the string, after any truncation
+    fn source_len(&self) -> Option<usize> {
+        Some(self.size)
+    }
+    /// Returns the index of the next [char] in the string
+    fn mark(&self) -> Option<usize> {
+        Some(self.index)
+    }
+    /// Moves to the [char] at `mark`, which can't be past the end of the string
+    fn seek(&mut self, mark: usize) -> Result<(), LexxInputError> {
+        if mark > self.size {
+            return Err(LexxInputError::Error(format!(
+                "can't seek to {}, the string is {} chars",
+                mark, self.size
+            )));
+        }
+        self.index = mark;
+        Ok(())
+    }
 }
 
 
+/// Implements [LexxInput](LexxInput) for any [Iterator] of [char]s, pulling from it lazily so
+/// nothing is copied up front and there is no size limit like [InputString] has.
+///
+/// # Example
+///
+/// ```rust
+/// use lexx::{Lexx, Lexxer};
+/// use lexx::input::InputIter;
+/// use lexx::matcher_word::WordMatcher;
+///
+/// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+///     Box::new(InputIter::new("abc".chars())),
+///     vec![Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] })]
+/// ));
+///
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "abc"));
+/// assert!(matches!(lexx.next_token(), Ok(None)));
+/// ```
+#[derive(Debug)]
+pub struct InputIter<I>
+    where
+        I: Iterator<Item = char> + Debug,
+{
+    /// the source of the chars
+    iter: I,
+}
+
+impl<I> InputIter<I>
+    where
+        I: Iterator<Item = char> + Debug,
+{
+    /// creates a new InputIter
+    ///
+    /// # Arguments
+    ///
+    /// * `iter` - The [Iterator] the [char]s are taken from
+    ///
+    pub fn new(iter: I) -> Self {
+        InputIter { iter }
+    }
+}
+
+impl<I> LexxInput for InputIter<I>
+    where
+        I: Iterator<Item = char> + Debug,
+{
+    /// Returns the next [char] from the iterator, [Ok(None)] once it is exhausted
+    fn next(&mut self) -> Result<Option<char>, LexxInputError> {
+        Ok(self.iter.next())
+    }
+}
+
+/// Implements [LexxInput](LexxInput) for input that arrives in pieces, such as from a network
+/// connection. The [char]s are added with [AppendableInput::push_str] on a clone of the
+/// AppendableInput while [Lexx](crate::Lexx) owns the other, clones share the same buffer.
+///
+/// Until [AppendableInput::close] is called running out of [char]s doesn't end the input, instead
+/// [Lexxer::next_token](crate::Lexxer::next_token) returns [Ok(None)] with
+/// [Lexxer::is_incomplete](crate::Lexxer::is_incomplete) set and a partial match is kept to be
+/// tried again once more has been pushed.
+///
+/// # Example
+///
+/// ```rust
+/// use lexx::{Lexx, Lexxer};
+/// use lexx::input::AppendableInput;
+/// use lexx::matcher_integer::IntegerMatcher;
+///
+/// let input = AppendableInput::new();
+/// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+///     Box::new(input.clone()),
+///     vec![Box::new(IntegerMatcher { index: 0, precedence: 0, running: true, radix_prefixes: false })]
+/// ));
+///
+/// input.push_str("12");
+/// assert!(matches!(lexx.next_token(), Ok(None)));
+/// assert!(lexx.is_incomplete());
+///
+/// input.push_str("34");
+/// input.close();
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "1234"));
+/// assert!(matches!(lexx.next_token(), Ok(None)));
+/// assert!(!lexx.is_incomplete());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AppendableInput {
+    /// the chars pushed but not yet read
+    chars: Rc<RefCell<VecDeque<char>>>,
+    /// if no more chars will be pushed
+    closed: Rc<Cell<bool>>,
+}
+
+impl AppendableInput {
+    /// creates a new, empty, AppendableInput
+    pub fn new() -> Self {
+        AppendableInput::default()
+    }
+
+    /// Adds `text` to the end of the input.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The [char]s to add
+    ///
+    pub fn push_str(&self, text: &str) {
+        self.chars.borrow_mut().extend(text.chars());
+    }
+
+    /// Marks the end of the input, once the [char]s already pushed are read the input ends as
+    /// any other would.
+    pub fn close(&self) {
+        self.closed.set(true);
+    }
+}
+
+impl LexxInput for AppendableInput {
+    /// Returns the next pushed [char], [Ok(None)] if there are none right now
+    fn next(&mut self) -> Result<Option<char>, LexxInputError> {
+        Ok(self.chars.borrow_mut().pop_front())
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed.get()
+    }
+}
+
+/// Implements [LexxInput](LexxInput) for a memory mapped file, decoding the UTF-8 straight from
+/// the mapped bytes as it goes so the file is never copied into memory. Any bytes that aren't
+/// valid UTF-8 are skipped.
+///
+/// Only available with the `mmap` feature.
+///
+/// # Example
+///
+/// ```rust
+/// use std::fs::File;
+/// use lexx::{Lexx, Lexxer};
+/// use lexx::input::InputMmap;
+/// use lexx::matcher_symbol::SymbolMatcher;
+/// use lexx::matcher_whitespace::WhitespaceMatcher;
+/// use lexx::matcher_word::WordMatcher;
+///
+/// let input = InputMmap::new(&File::open("utf-8-sampler.txt").unwrap()).unwrap();
+/// let lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+///     Box::new(input),
+///     vec![
+///         Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] }),
+///         Box::new(WhitespaceMatcher::with_predicate(char::is_whitespace, 0)),
+///         Box::new(SymbolMatcher::with_max_len(0, 0)),
+///     ]
+/// ));
+/// ```
+#[cfg(feature = "mmap")]
+#[derive(Debug)]
+pub struct InputMmap {
+    /// the mapped file
+    mmap: memmap2::Mmap,
+    /// byte offset of the next char
+    index: usize,
+}
+
+#[cfg(feature = "mmap")]
+impl InputMmap {
+    /// creates a new InputMmap by mapping `file` into memory
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - The file to map, it must stay unchanged while it is being lexed. Changing a
+    ///   file while it is mapped is undefined behavior, see [memmap2::Mmap::map].
+    ///
+    pub fn new(file: &std::fs::File) -> Result<Self, LexxInputError> {
+        // Safety: the caller is required not to modify the file while it is mapped
+        let mmap = unsafe { memmap2::Mmap::map(file) }
+            .map_err(|e| LexxInputError::Error(format!("could not map file: {}", e)))?;
+        Ok(InputMmap { mmap, index: 0 })
+    }
+}
+
+#[cfg(feature = "mmap")]
+impl LexxInput for InputMmap {
+    /// decodes the next char from the mapped bytes
+    fn next(&mut self) -> Result<Option<char>, LexxInputError> {
+        while self.index < self.mmap.len() {
+            let width = match self.mmap[self.index] {
+                0x00..=0x7F => 1,
+                0xC0..=0xDF => 2,
+                0xE0..=0xEF => 3,
+                0xF0..=0xF7 => 4,
+                _ => 0,
+            };
+            if let Some(Ok(s)) = self
+                .mmap
+                .get(self.index..self.index + width)
+                .filter(|_| width > 0)
+                .map(from_utf8)
+            {
+                self.index += width;
+                return Ok(s.chars().next());
+            }
+            // not valid UTF-8, skip the byte
+            self.index += 1;
+        }
+        Ok(None)
+    }
+    /// Returns [None], counting the chars would mean decoding the whole file
+    fn source_len(&self) -> Option<usize> {
+        None
+    }
+}
+
 /// Implements [LexxInput](LexxInput) for the [Read](Read) trait.
 /// It uses a paged buffer to load the file. [BUFFER_SIZE] sets the size of the buffer used.
 /// The stream needs to be UTF8.
@@ -142,6 +541,8 @@ pub struct InputReader<R>
     buffer: Box<[u8; BUFFER_SIZE]>,
     /// char buffer the byte buffer is translated into
     text: Box<[char; BUFFER_SIZE]>,
+    /// if a leading UTF-8 BOM should still be discarded, only checked on the first load
+    strip_bom: bool,
 }
 
 impl<R> InputReader<R>
@@ -150,6 +551,31 @@ impl<R> InputReader<R>
 {
     /// creates a new InputReader
     pub fn new(input: R) -> Self {
+        InputReader::new_with_options(input, false)
+    }
+
+    /// creates a new InputReader with options
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The [Read] to get the UTF8 stream from
+    /// * `strip_bom` - If `true` a UTF-8 byte order mark (`EF BB BF`) at the very start of the
+    ///   stream is discarded instead of being returned as a `'\u{FEFF}'` [char].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use lexx::input::InputReader;
+    /// use crate::lexx::input::LexxInput;
+    ///
+    /// let mut lexx_input = InputReader::new_with_options(Cursor::new(b"\xEF\xBB\xBFab".to_vec()), true);
+    ///
+    /// assert!(matches!(lexx_input.next(), Ok(Some(c)) if c == 'a'));
+    /// assert!(matches!(lexx_input.next(), Ok(Some(c)) if c == 'b'));
+    /// assert!(matches!(lexx_input.next(), Ok(None)));
+    /// ```
+    pub fn new_with_options(input: R, strip_bom: bool) -> Self {
         let buffer = Box::new([0; BUFFER_SIZE]);
         let text = Box::new(['x'; BUFFER_SIZE]);
 
@@ -161,6 +587,7 @@ impl<R> InputReader<R>
             reader: input,
             buffer,
             text,
+            strip_bom,
         }
     }
 }
@@ -225,6 +652,12 @@ impl<R> LexxInput for InputReader<R>
             self.text[self.size] = c;
             self.size += 1;
         }
+        if self.strip_bom {
+            self.strip_bom = false;
+            if self.text[0] == '\u{FEFF}' {
+                return self.next();
+            }
+        }
         return Ok(Some(self.text[self.index-1]));
     }
 }
@@ -234,10 +667,14 @@ impl<R> LexxInput for InputReader<R>
 mod tests {
     use std::collections::HashMap;
     use std::fs::File;
-    use std::time::Instant;
+    use std::io::{Cursor, Read};
+    use std::collections::hash_map::RandomState;
+    use std::hash::BuildHasher;
+    use std::time::{Duration, Instant};
 
     use crate::{Lexx, Lexxer, LexxError};
-    use crate::input::InputReader;
+    use crate::input::{count_lines, detect_line_ending, AppendableInput, LineEnding, InputIter, InputReader, InputString, LexxInput};
+    use crate::matcher::{Ctx, Matcher, MatcherResult};
     use crate::matcher_integer::IntegerMatcher;
     use crate::matcher_float::FloatMatcher;
     use crate::matcher_symbol::SymbolMatcher;
@@ -297,7 +734,7 @@ mod tests {
                     LexxError::TokenNotFound(_) => {
                         assert!(false, "Should not have failed finding a token file");
                     }
-                    LexxError::Error(_) => {
+                    LexxError::Error(_) | LexxError::BufferOverflow(_) => {
                         assert!(false, "Should not have failed parsing file");
                     }
                 },
@@ -317,6 +754,51 @@ mod tests {
         println!("Time elapsed is: {:?}", duration);
     }
 
+    #[test]
+    fn input_reader_strips_bom() {
+        let bytes = b"\xEF\xBB\xBFThe".to_vec();
+
+        let mut input = InputReader::new_with_options(Cursor::new(bytes.clone()), true);
+        assert_eq!(input.next(), Ok(Some('T')));
+        assert_eq!(input.next(), Ok(Some('h')));
+        assert_eq!(input.next(), Ok(Some('e')));
+        assert_eq!(input.next(), Ok(None));
+
+        // off by default
+        let mut input = InputReader::new(Cursor::new(bytes));
+        assert_eq!(input.next(), Ok(Some('\u{FEFF}')));
+        assert_eq!(input.next(), Ok(Some('T')));
+
+        // a BOM that isn't at the start is left alone
+        let mut input = InputReader::new_with_options(Cursor::new(b"a\xEF\xBB\xBF".to_vec()), true);
+        assert_eq!(input.next(), Ok(Some('a')));
+        assert_eq!(input.next(), Ok(Some('\u{FEFF}')));
+
+        // only a BOM
+        let mut input = InputReader::new_with_options(Cursor::new(b"\xEF\xBB\xBF".to_vec()), true);
+        assert_eq!(input.next(), Ok(None));
+    }
+
+    #[test]
+    fn input_iter_drives_lexx() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputIter::new("abc 12".chars())),
+            vec![
+                Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] }),
+                Box::new(IntegerMatcher { index: 0, precedence: 0, running: true, radix_prefixes: false }),
+                Box::new(WhitespaceMatcher { index: 0, column: 0, line: 0, precedence: 0, running: true, predicate: char::is_whitespace, line_continuation: None, mixed_indent: false }),
+            ],
+        );
+
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "abc" && t.token_type == TOKEN_TYPE_WORD));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_WHITESPACE));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "12" && t.token_type == TOKEN_TYPE_INTEGER && t.column == 5));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+
+        let mut input = InputIter::new("".chars());
+        assert_eq!(input.next(), Ok(None));
+    }
+
     #[test]
     fn lexx_parse_utf_file() {
         let file = File::open("utf-8-sampler.txt").unwrap();
@@ -331,7 +813,8 @@ mod tests {
             len: 0,
             line: 0,
             column: 0,
-            precedence: 0
+            precedence: 0,
+            ..Default::default()
         };
 
         for token in lexx {
@@ -346,40 +829,415 @@ mod tests {
         assert_eq!(String::from("▁▂▃▄▅▆▇█"), final_token.value);
     }
 
-    fn make_test_lexx(input_file: InputReader<File>) -> Box<Lexx<512>> {
-        Box::new(Lexx::<512>::new(
-            Box::new(input_file),
+    /// A one-shot [Read] that hands out at most a couple of bytes per call, like a slow pipe.
+    #[derive(Debug)]
+    struct TrickleReader {
+        data: Vec<u8>,
+        pos: usize,
+    }
+
+    impl Read for TrickleReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = buf.len().min(2).min(self.data.len() - self.pos);
+            buf[..n].copy_from_slice(&self.data[self.pos..self.pos + n]);
+            self.pos += n;
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn lexx_look_ahead_input_reader_loses_nothing() {
+        let text = "let total = 12 + 345;\nprint total";
+        let input = InputReader::new(TrickleReader { data: text.as_bytes().to_vec(), pos: 0 });
+        let mut lexx = Lexx::<512>::new(
+            Box::new(input),
             vec![
-                Box::new(IntegerMatcher {
-                    index: 0,
-                    precedence: 0,
-                    running: true,
-                }),
-                Box::new(FloatMatcher {
-                    index: 0,
-                    precedence: 0,
-                    dot: false,
-                    float: false,
-                    running: true,
-                }),
-                Box::new(WhitespaceMatcher {
-                    index: 0,
-                    column: 0,
-                    line: 0,
-                    precedence: 0,
-                    running: true,
-                }),
-                Box::new(WordMatcher {
-                    index: 0,
-                    precedence: 0,
-                    running: true,
-                }),
-                Box::new(SymbolMatcher {
-                    index: 0,
-                    precedence: 0,
-                    running: true,
-                }),
+                Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] }),
+                Box::new(IntegerMatcher { index: 0, precedence: 0, running: true, radix_prefixes: false }),
+                Box::new(SymbolMatcher { index: 0, precedence: 0, running: true, max_len: 0 }),
+                Box::new(WhitespaceMatcher { index: 0, column: 0, line: 0, precedence: 0, running: true, predicate: char::is_whitespace, line_continuation: None, mixed_indent: false }),
             ],
-        ))
+        );
+
+        let mut rebuilt = String::new();
+        loop {
+            let ahead = lexx.look_ahead();
+            let next = lexx.next_token();
+            assert_eq!(ahead, next);
+            match next {
+                Ok(Some(t)) => rebuilt.push_str(&t.value),
+                Ok(None) => break,
+                Err(e) => panic!("{}", e),
+            }
+        }
+        assert_eq!(text, rebuilt);
     }
-}
\ No newline at end of file
+
+    /// Matches every [char] as its own [Token], reading and writing the `ctx` for each one.
+    #[derive(Debug)]
+    struct CtxCountingMatcher {
+        running: bool,
+    }
+
+    impl<S: BuildHasher> Matcher<S> for CtxCountingMatcher {
+        fn reset(&mut self, ctx: &mut Box<Ctx<S>>) {
+            self.running = true;
+            ctx.entry("tokens".to_string()).or_insert(0);
+        }
+
+        fn find_match(&mut self, oc: Option<char>, value: &[char], ctx: &mut Box<Ctx<S>>) -> MatcherResult {
+            match oc {
+                Some(c) if value.len() == 1 => {
+                    if c.is_alphabetic() {
+                        *ctx.entry("letters".to_string()).or_insert(0) += 1;
+                    }
+                    MatcherResult::Running()
+                }
+                _ if value.is_empty() => {
+                    self.running = false;
+                    MatcherResult::Failed()
+                }
+                _ => {
+                    self.running = false;
+                    *ctx.entry("tokens".to_string()).or_insert(0) += 1;
+                    MatcherResult::Matched(Token {
+                        value: value[0].to_string(),
+                        token_type: TOKEN_TYPE_SYMBOL,
+                        len: 1,
+                        line: 0,
+                        column: 1,
+                        ..Default::default()
+                    })
+                }
+            }
+        }
+
+        fn is_running(&self) -> bool {
+            self.running
+        }
+
+        fn precedence(&self) -> u8 {
+            0
+        }
+    }
+
+    /// Lexes the Varney file one [char] at a time with a `ctx` using the `hasher`, returning
+    /// the [Token] count, the `ctx` letter count and how long it took.
+    fn lexx_with_ctx<S: BuildHasher>(hasher: S) -> (usize, i32, Duration) {
+        let start = Instant::now();
+
+        let file = File::open("Varney-the-Vampire.txt").unwrap();
+        let mut lexx = Lexx::<512, S>::with_hasher(
+            Box::new(InputReader::new(file)),
+            vec![Box::new(CtxCountingMatcher { running: true })],
+            hasher,
+        );
+
+        let mut total = 0;
+        while let Ok(Some(_)) = lexx.next_token() {
+            total += 1;
+        }
+
+        let duration = start.elapsed();
+        (total, lexx.ctx.get("letters").copied().unwrap_or(0), duration)
+    }
+
+    #[test]
+    fn lexx_parse_large_file_with_ctx() {
+        let (total, letters, duration) = lexx_with_ctx(RandomState::new());
+        assert_eq!(1841065, total);
+        assert_eq!(1407126, letters);
+        println!("Time elapsed with ctx is: {:?}", duration);
+    }
+
+    /// Compares the default hasher with the [FastHasher](crate::matcher::FastHasher) on the same
+    /// input, run with `--release --nocapture` to see the times.
+    #[cfg(feature = "fast-hash")]
+    #[test]
+    fn lexx_parse_large_file_with_fast_ctx() {
+        use crate::matcher::FastHasher;
+
+        let (total, letters, default_duration) = lexx_with_ctx(RandomState::new());
+        let (fast_total, fast_letters, fast_duration) = lexx_with_ctx(FastHasher::default());
+        assert_eq!(total, fast_total);
+        assert_eq!(letters, fast_letters);
+        println!(
+            "Time elapsed with the default hasher is: {:?}, with FastHasher: {:?} ({:.2}x)",
+            default_duration,
+            fast_duration,
+            default_duration.as_secs_f64() / fast_duration.as_secs_f64()
+        );
+    }
+
+    /// Passes everything on to the wrapped [Matcher] but can claim it's stateful, so the same
+    /// matchers can be timed with [Matcher::reset] and with [Matcher::reset_light].
+    #[derive(Debug)]
+    struct FullResetMatcher {
+        matcher: Box<dyn Matcher>,
+        full_reset: bool,
+    }
+
+    impl Matcher for FullResetMatcher {
+        fn reset(&mut self, ctx: &mut Box<Ctx>) {
+            self.matcher.reset(ctx);
+        }
+
+        fn is_stateful(&self) -> bool {
+            self.full_reset || self.matcher.is_stateful()
+        }
+
+        fn reset_light(&mut self) {
+            self.matcher.reset_light();
+        }
+
+        fn find_match(&mut self, oc: Option<char>, value: &[char], ctx: &mut Box<Ctx>) -> MatcherResult {
+            self.matcher.find_match(oc, value, ctx)
+        }
+
+        fn is_running(&self) -> bool {
+            self.matcher.is_running()
+        }
+
+        fn precedence(&self) -> u8 {
+            self.matcher.precedence()
+        }
+    }
+
+    /// Lexes the Varney file with the stateless matchers reset fully or not, returning the
+    /// [Token] count and how long it took.
+    fn lexx_with_resets(full_reset: bool) -> (usize, Duration) {
+        let start = Instant::now();
+
+        let file = File::open("Varney-the-Vampire.txt").unwrap();
+        let mut lexx = make_test_lexx(InputReader::new(file));
+        lexx.matchers = lexx
+            .matchers
+            .drain(..)
+            .map(|matcher| -> Box<dyn Matcher> { Box::new(FullResetMatcher { matcher, full_reset }) })
+            .collect();
+
+        let mut total = 0;
+        while let Ok(Some(_)) = lexx.next_token() {
+            total += 1;
+        }
+
+        (total, start.elapsed())
+    }
+
+    /// Compares [Matcher::reset] with [Matcher::reset_light] for the stateless matchers, run with
+    /// `--release --nocapture` to see the times.
+    #[test]
+    fn lexx_parse_large_file_reset_light() {
+        let (total, full_duration) = lexx_with_resets(true);
+        let (light_total, light_duration) = lexx_with_resets(false);
+        assert_eq!(743524, total);
+        assert_eq!(total, light_total);
+        println!(
+            "Time elapsed with reset is: {:?}, with reset_light: {:?} ({:.2}x)",
+            full_duration,
+            light_duration,
+            full_duration.as_secs_f64() / light_duration.as_secs_f64()
+        );
+    }
+
+    /// The built in matchers work with any hasher.
+    #[cfg(feature = "fast-hash")]
+    #[test]
+    fn lexx_parse_large_file_fast_hash() {
+        use crate::matcher::FastHasher;
+
+        let file = File::open("Varney-the-Vampire.txt").unwrap();
+        let mut fast = Lexx::<512, FastHasher>::with_hasher(
+            Box::new(InputReader::new(file)),
+            make_test_matchers(),
+            FastHasher::default(),
+        );
+        let file = File::open("Varney-the-Vampire.txt").unwrap();
+        let mut lexx = make_test_lexx(InputReader::new(file));
+
+        let mut total = 0;
+        loop {
+            let token = fast.next_token();
+            assert_eq!(token, lexx.next_token());
+            match token {
+                Ok(Some(_)) => total += 1,
+                _ => break,
+            }
+        }
+        assert_eq!(743524, total);
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn input_mmap_matches_input_reader() {
+        use crate::input::InputMmap;
+        use std::io::Write;
+
+        let path = std::env::temp_dir().join(format!("lexx_mmap_test_{}.txt", std::process::id()));
+        let mut text = String::new();
+        for i in 0..200 {
+            text.push_str(&format!("line {} has ünïcode, 3.14 and ▁▂▃▄ ok\n", i));
+        }
+        File::create(&path).unwrap().write_all(text.as_bytes()).unwrap();
+
+        let from_reader: Vec<Token> = make_test_lexx(InputReader::new(File::open(&path).unwrap())).collect();
+        let from_mmap: Vec<Token> = make_test_lexx(InputMmap::new(&File::open(&path).unwrap()).unwrap()).collect();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(from_reader.len() > 2000);
+        assert_eq!(from_reader, from_mmap);
+
+        // invalid bytes are skipped
+        File::create(&path).unwrap().write_all(b"a\xFFb\xE2\x96c").unwrap();
+        let mut input = InputMmap::new(&File::open(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(input.next(), Ok(Some('a')));
+        assert_eq!(input.next(), Ok(Some('b')));
+        assert_eq!(input.next(), Ok(Some('c')));
+        assert_eq!(input.next(), Ok(None));
+
+        // an empty file
+        let path = std::env::temp_dir().join(format!("lexx_mmap_empty_{}.txt", std::process::id()));
+        File::create(&path).unwrap();
+        let mut input = InputMmap::new(&File::open(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(input.next(), Ok(None));
+    }
+
+    #[test]
+    fn lexx_appendable_input_waits_for_more() {
+        let input = AppendableInput::new();
+        let mut lexx = make_test_lexx(input.clone());
+
+        assert!(matches!(lexx.next_token(), Ok(None)));
+        assert!(lexx.is_incomplete());
+
+        input.push_str("12");
+        assert!(matches!(lexx.next_token(), Ok(None)));
+        assert!(lexx.is_incomplete());
+        input.push_str("34");
+        assert!(matches!(lexx.next_token(), Ok(None)));
+        input.push_str(" ab");
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "1234" && t.token_type == TOKEN_TYPE_INTEGER && t.column == 1));
+        assert!(!lexx.is_incomplete());
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_WHITESPACE && t.column == 5));
+        // "ab" could still grow into a longer word
+        assert!(matches!(lexx.next_token(), Ok(None)));
+        assert!(lexx.is_incomplete());
+
+        input.push_str("c");
+        input.close();
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "abc" && t.token_type == TOKEN_TYPE_WORD && t.column == 6));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+        assert!(!lexx.is_incomplete());
+    }
+
+    #[test]
+    fn lexx_count_lines() {
+        let text = "fn main() {\r\n\tlet x = 1;\n\n  x\r}\n// end";
+        assert_eq!(count_lines(&mut InputString::new(String::from(text))), Ok(6));
+
+        // it agrees with the line of the last token
+        let mut lexx = make_test_lexx(InputString::new(String::from(text)));
+        let mut last_line = 0;
+        while let Ok(Some(t)) = lexx.next_token() {
+            last_line = t.line;
+        }
+        assert_eq!(last_line, 6);
+
+        assert_eq!(count_lines(&mut InputString::new(String::from("\n\n"))), Ok(2));
+        assert_eq!(count_lines(&mut InputString::new(String::from("one"))), Ok(1));
+        assert_eq!(count_lines(&mut InputReader::new(Cursor::new(text.as_bytes()))), Ok(6));
+        assert_eq!(count_lines(&mut InputString::new(String::from("a\rb\r\n"))), Ok(2));
+    }
+
+    #[test]
+    fn input_detect_line_ending() {
+        let detect = |text: &str| detect_line_ending(&mut InputString::new(String::from(text)));
+
+        assert_eq!(detect("a\r\nb\nc\r\nd\r\n\ne"), Ok(LineEnding::CrLf));
+        assert_eq!(detect("a\nb\r\nc\n"), Ok(LineEnding::Lf));
+        // a \r at the very end is a line ending too
+        assert_eq!(detect("a\rb\r\r\nc\r"), Ok(LineEnding::Cr));
+        // ties and no line breaks at all are Lf
+        assert_eq!(detect("a\nb\r\n"), Ok(LineEnding::Lf));
+        assert_eq!(detect("a\r\nb\r"), Ok(LineEnding::CrLf));
+        assert_eq!(detect("abc"), Ok(LineEnding::Lf));
+        assert_eq!(
+            detect_line_ending(&mut InputReader::new(Cursor::new("x\r\ny\r\n".as_bytes()))),
+            Ok(LineEnding::CrLf)
+        );
+    }
+
+    #[test]
+    fn input_string_slice() {
+        let input = InputString::new(String::from("let\nx = 42 + y;"));
+        let mut lexx = make_test_lexx(input.slice(5, 10));
+        lexx.reset_position(2, 2);
+
+        let tokens: Vec<(String, usize, usize)> = lexx.map(|t| (t.value, t.line, t.column)).collect();
+        assert_eq!(
+            tokens,
+            vec![
+                (String::from(" "), 2, 2),
+                (String::from("="), 2, 3),
+                (String::from(" "), 2, 4),
+                (String::from("42"), 2, 5),
+            ]
+        );
+
+        // without an origin the slice starts at line 1, column 1
+        let mut lexx = make_test_lexx(input.slice(4, 5));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "x" && t.line == 1 && t.column == 1));
+
+        assert_eq!(input.slice(10, 100).source_len(), Some(5));
+        assert_eq!(input.slice(8, 3).source_len(), Some(0));
+    }
+
+    fn make_test_lexx(input_file: impl LexxInput + 'static) -> Box<Lexx<512>> {
+        Box::new(Lexx::<512>::new(Box::new(input_file), make_test_matchers()))
+    }
+
+    fn make_test_matchers<S: BuildHasher>() -> Vec<Box<dyn Matcher<S>>> {
+        vec![
+            Box::new(IntegerMatcher {
+                index: 0,
+                precedence: 0,
+                running: true,
+                radix_prefixes: false,
+            }),
+            Box::new(FloatMatcher {
+                index: 0,
+                precedence: 0,
+                dot: false,
+                float: false,
+                running: true,
+                lenient_trailing_dot: false,
+                exponent: false,
+                integer_mantissa: false,
+            }),
+            Box::new(WhitespaceMatcher {
+                index: 0,
+                column: 0,
+                line: 0,
+                precedence: 0,
+                running: true,
+                predicate: char::is_whitespace,
+                line_continuation: None,
+                mixed_indent: false,
+            }),
+            Box::new(WordMatcher {
+                index: 0,
+                precedence: 0,
+                running: true,
+                trailing: &[],
+            }),
+            Box::new(SymbolMatcher {
+                index: 0,
+                precedence: 0,
+                running: true,
+                max_len: 0,
+            }),
+        ]
+    }
+}
@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+
+/// StringInterner gives each distinct string it sees a small id, the same string always gets
+/// the same id. Set one with [Lexxer::set_interner](crate::Lexxer::set_interner) and the
+/// [Token]s of the chosen types carry the id of their `value` in
+/// [Token::interned](crate::token::Token::interned), so a parser can store and compare ids
+/// instead of [String]s. Each string is only stored once however often it is seen.
+///
+/// # Example
+///
+/// ```rust
+/// use lexx::interner::StringInterner;
+///
+/// let mut interner = StringInterner::new();
+/// let foo = interner.intern("foo");
+/// assert_eq!(interner.intern("bar"), 1);
+/// assert_eq!(interner.intern("foo"), foo);
+/// assert_eq!(interner.resolve(foo), Some("foo"));
+/// assert_eq!(interner.len(), 2);
+/// ```
+///
+/// [Token]: crate::token::Token
+#[derive(Clone, Debug, Default)]
+pub struct StringInterner {
+    /// The id of each string interned so far.
+    ids: HashMap<String, usize>,
+    /// The strings interned so far, by id.
+    strings: Vec<String>,
+}
+
+impl StringInterner {
+    /// Build an empty string interner
+    pub fn new() -> StringInterner {
+        StringInterner {
+            ids: HashMap::new(),
+            strings: vec![],
+        }
+    }
+
+    /// Returns the id of `value`, giving it the next free id, starting at 0, the first time it is
+    /// seen.
+    pub fn intern(&mut self, value: &str) -> usize {
+        if let Some(id) = self.ids.get(value) {
+            return *id;
+        }
+        let id = self.strings.len();
+        self.strings.push(value.to_string());
+        self.ids.insert(value.to_string(), id);
+        id
+    }
+
+    /// Returns the string with the `id`, or [None] if no string has that id.
+    pub fn resolve(&self, id: usize) -> Option<&str> {
+        self.strings.get(id).map(|s| s.as_str())
+    }
+
+    /// How many distinct strings have been interned.
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// If nothing has been interned yet.
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::input::InputString;
+    use crate::interner::StringInterner;
+    use crate::token::{Token, TOKEN_TYPE_WORD};
+    use crate::{Lexx, Lexxer};
+
+    #[test]
+    fn interner_shares_ids() {
+        let interner = Rc::new(RefCell::new(StringInterner::new()));
+        let mut lexx = Lexx::<512>::from_spec(
+            "word,whitespace,integer,symbol",
+            Box::new(InputString::new(String::from("foo = bar + foo; 1 + 1"))),
+        )
+        .unwrap();
+        lexx.set_interner(Rc::clone(&interner), &[TOKEN_TYPE_WORD]);
+
+        let tokens: Vec<Token> = lexx.by_ref().filter(|t| t.value.trim() != "").collect();
+        assert_eq!(tokens[0].value, "foo");
+        assert_eq!(tokens[4].value, "foo");
+        assert_eq!(tokens[0].interned, Some(0));
+        assert_eq!(tokens[4].interned, tokens[0].interned);
+        assert_eq!(tokens[2].interned, Some(1));
+        // only words are interned
+        assert_eq!(tokens[1].interned, None);
+        assert_eq!(tokens[6].value, "1");
+        assert_eq!(tokens[6].interned, None);
+
+        let interner = interner.borrow();
+        assert_eq!(interner.len(), 2);
+        assert_eq!(interner.resolve(1), Some("bar"));
+        assert_eq!(interner.resolve(2), None);
+    }
+}
@@ -3,14 +3,14 @@
 //! Sample output for the string "This is  \n1.0 thing."
 //! ```
 //! use lexx::token::Token;
-//! Token{ token_type: 4, value: "This".to_string(), line: 1, column: 1, len: 4, precedence: 0};
-//! Token{ token_type: 3, value: " ".to_string(), line: 1, column: 5, len: 1, precedence: 0};
-//! Token{ token_type: 4, value: "is".to_string(), line: 1, column: 6, len: 2, precedence: 0};
-//! Token{ token_type: 3, value: "  \n".to_string(), line: 1, column: 8, len: 3, precedence: 0};
-//! Token{ token_type: 2, value: "1.0".to_string(), line: 2, column: 1, len: 3, precedence: 0};
-//! Token{ token_type: 3, value: " ".to_string(), line: 2, column: 4, len: 1, precedence: 0};
-//! Token{ token_type: 4, value: "thing".to_string(), line: 2, column: 5, len: 5, precedence: 0};
-//! Token{ token_type: 5, value: ".".to_string(), line: 2, column: 10, len: 1, precedence: 0};
+//! Token{ token_type: 4, value: "This".to_string(), line: 1, column: 1, len: 4, precedence: 0, prev_gap: 0, label: None, start_byte: 0, end_byte: 4, leading_trivia: String::new(), normalized: None, interned: None};
+//! Token{ token_type: 3, value: " ".to_string(), line: 1, column: 5, len: 1, precedence: 0, prev_gap: 0, label: None, start_byte: 4, end_byte: 5, leading_trivia: String::new(), normalized: None, interned: None};
+//! Token{ token_type: 4, value: "is".to_string(), line: 1, column: 6, len: 2, precedence: 0, prev_gap: 1, label: None, start_byte: 5, end_byte: 7, leading_trivia: String::new(), normalized: None, interned: None};
+//! Token{ token_type: 3, value: "  \n".to_string(), line: 1, column: 8, len: 3, precedence: 0, prev_gap: 0, label: None, start_byte: 7, end_byte: 10, leading_trivia: String::new(), normalized: None, interned: None};
+//! Token{ token_type: 2, value: "1.0".to_string(), line: 2, column: 1, len: 3, precedence: 0, prev_gap: 3, label: None, start_byte: 10, end_byte: 13, leading_trivia: String::new(), normalized: None, interned: None};
+//! Token{ token_type: 3, value: " ".to_string(), line: 2, column: 4, len: 1, precedence: 0, prev_gap: 0, label: None, start_byte: 13, end_byte: 14, leading_trivia: String::new(), normalized: None, interned: None};
+//! Token{ token_type: 4, value: "thing".to_string(), line: 2, column: 5, len: 5, precedence: 0, prev_gap: 1, label: None, start_byte: 14, end_byte: 19, leading_trivia: String::new(), normalized: None, interned: None};
+//! Token{ token_type: 5, value: ".".to_string(), line: 2, column: 10, len: 1, precedence: 0, prev_gap: 0, label: None, start_byte: 19, end_byte: 20, leading_trivia: String::new(), normalized: None, interned: None};
 //! ```
 //! Lexx uses a [LexxInput](LexxInput) to provide chars that are fed to
 //! [Matcher](Matcher) instances until the longest match is found, if any. The
@@ -18,11 +18,15 @@
 //! [Token](Token) includes a type and the string matched as well as the
 //! line and column where the match was made. A custom [LexxInput](LexxInput)
 //! can be passed to Lexx but the library comes with implementations for
-//! [String](input::InputString) and
-//! [Reader](input::InputReader) types.
+//! [String](input::InputString), [Reader](input::InputReader) and
+//! [char Iterator](input::InputIter) types, as well as an
+//! [appendable input](input::AppendableInput) for text that arrives in pieces.
 //!
 //! Lexx implements [Iterator] so it can be use with `for each`.
 //!
+//! [LexxerExt](LexxerExt) adds adaptors such as `windows3`, `iter_results` and `map_tokens` to
+//! every Lexxer.
+//!
 //! Custom [Matcher](Matcher)s can also be made though Lexx comes with:
 //! - [WordMatcher](matcher_word::WordMatcher) matches alphabetic characters such as `ABCdef` and `word`
 //! - [IntegerMatcher](matcher_integer::IntegerMatcher) matches integers such as `3` or `14537`
@@ -38,6 +42,39 @@
 //! `new` or `specific`, it differs from the [ExactMatcher](matcher_exact::ExactMatcher) in that it
 //! will not mach substrings, such as the `new` in `renewable` or `newfangled`.
 //! - [WhitespaceMatcher](matcher_whitespace::WhitespaceMatcher) matches whitespace such as `  ` or `\t\r\n`
+//! - [RepeatCharMatcher](matcher_repeat_char::RepeatCharMatcher) matches runs of a single character
+//!   such as the `====` of a Markdown header.
+//! - [EmailMatcher](matcher_email::EmailMatcher) matches email addresses such as `user@example.com`
+//! - [CharMapMatcher](matcher_char_map::CharMapMatcher) matches single chars such as `(` or `,`, each with
+//!   its own token type
+//! - [NewlineMatcher](matcher_newline::NewlineMatcher) matches line endings, telling blank lines apart
+//! - [PrefixedMatcher](matcher_prefixed::PrefixedMatcher) matches a prefix char followed by what another
+//!   matcher matches, such as `@name` or `#tag`
+//! - [QuantityMatcher](matcher_quantity::QuantityMatcher) matches numbers with units such as `5ms` or `10kg`
+//! - [GroupedIntegerMatcher](matcher_grouped_integer::GroupedIntegerMatcher) matches integers grouped by
+//!   spaces such as `1 234 567`
+//! - [DigitGroupMatcher](matcher_digit_group::DigitGroupMatcher) matches phone and card numbers such as
+//!   `123-456-7890`
+//! - [TagMatcher](matcher_tag::TagMatcher) matches XML and HTML tags such as `<div>` or `<br/>`
+//! - [UrlMatcher](matcher_url::UrlMatcher) matches URLs such as `https://example.com/a%20b`
+//! - [AltMatcher](matcher_alt::AltMatcher) matches the longest match of several other matchers,
+//!   with a single precedence for them all
+//! - [StateMachineMatcher](matcher_state_machine::StateMachineMatcher) matches whatever a state
+//!   machine of [char] class transitions accepts, for custom token shapes
+//! - [OperatorMatcher](matcher_operator::OperatorMatcher) matches the longest operator from a table such
+//!   as `<<=`, each with its own token type, and any other symbol as a single char
+//! - [DateTimeMatcher](matcher_datetime::DateTimeMatcher) matches ISO-8601 dates and times such as `2024-01-15`
+//!   or `2024-01-15T10:30:00Z`
+//! - [CommentMatcher](matcher_comment::CommentMatcher) matches comments that run to the end of the line
+//!   such as `// a comment`
+//! - [BlockCommentMatcher](matcher_block_comment::BlockCommentMatcher) matches comments between delimiters
+//!   such as `/* a comment */`, optionally nested
+//! - [StringLiteralMatcher](matcher_string::StringLiteralMatcher) matches quoted strings with `\` escapes such
+//!   as `"a \"quoted\" string"`, or without escapes such as `'a string'`
+//! - [IdentifierMatcher](matcher_identifier::IdentifierMatcher) matches programming identifiers
+//!   such as `foo_bar2`
+//! - [SuffixedMatcher](matcher_suffixed::SuffixedMatcher) matches what another matcher matches
+//!   followed by an optional suffix, such as `100u8` or `3.14f32`
 //!
 //! [Matcher](Matcher)s can be given a precedence that can make a matcher return it's
 //! results even if another matcher has a longer match. For example, both the [WordMatcher](matcher_word::WordMatcher)
@@ -55,14 +92,34 @@
 //! given a higher precedence in which case it would get to return `new` and the next match would
 //! start at `fangled`.
 //!
+//! This longest match rule can be swapped for an ordered choice with [Lexxer::set_match_strategy],
+//! where the first [Matcher](Matcher) to complete a match wins however long the others could go.
+//!
 //! To successfully parse an entire stream [Lexx] must have a matcher with which to tokenize every
 //! encountered collection of characters. If a match fails [Lexx] will return Err
 //! [TokenNotFound](LexxError::TokenNotFound) with the text that could not be matched.
 //!
-//! # Panics
+//! # Buffer Size
 //!
 //! For speed Lexx does not dynamically allocate buffer space, in `Lexx<CAP>` CAP is the maximum
-//! possible token size, if that size is exceeded a panic will be thrown.
+//! possible token size, if that size is exceeded [Lexxer::next_token] returns Err
+//! [BufferOverflow](LexxError::BufferOverflow) with the line the oversized token started on. If a
+//! shorter [Token] had already matched it is returned instead and there is no error. Otherwise the
+//! whole oversized token is dropped and lexing goes on from the [char] after it, none of it comes
+//! back as [Token]s. Finding where it ends means holding all of it, so only that one run takes
+//! more memory than CAP.
+//!
+//! # Features
+//!
+//! * `fast-hash` - Adds [FastHasher](matcher::FastHasher), the faster `FxHasher`, for the
+//!   [Lexx::ctx] map of a [Lexx] made with [Lexx::with_hasher].
+//! * `token-dump` - Adds [encode_tokens](token::encode_tokens) and
+//!   [decode_tokens](token::decode_tokens) for caching [Token]s in a compact binary format.
+//! * `mmap` - Adds [InputMmap](input::InputMmap) for lexing memory mapped files.
+//! * `ansi` - Adds [format_stream_colored](token::format_stream_colored) for printing [Token]s
+//!   in color on a terminal.
+//! * `golden` - Adds [format_golden](token::format_golden) and
+//!   [assert_golden](token::assert_golden) for testing [Token]s against a golden file.
 //!
 //! # Example
 //!
@@ -80,9 +137,9 @@
 //! let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
 //!   Box::new(lexx_input),
 //!   vec![
-//!     Box::new(WordMatcher{ index: 0, precedence: 0, running: true }),
-//!     Box::new(WhitespaceMatcher { index: 0, column: 0,line: 0,precedence: 0, running: true }),
-//!     Box::new(SymbolMatcher { index:0, precedence: 0, running: true }),
+//!     Box::new(WordMatcher{ index: 0, precedence: 0, running: true, trailing: &[] }),
+//!     Box::new(WhitespaceMatcher { index: 0, column: 0,line: 0,precedence: 0, running: true, predicate: char::is_whitespace, line_continuation: None, mixed_indent: false }),
+//!     Box::new(SymbolMatcher { index:0, precedence: 0, running: true, max_len: 0 }),
 //!     // with a precedence of 1 this will match "quick" instead of the word matcher
 //!     // We can change the TOKEN_TYPE value returned if we want to have more than one
 //!     // ExactMatcher that return different token types.
@@ -125,6 +182,10 @@ pub mod input;
 pub mod matcher;
 /// The results of a match
 pub mod token;
+/// Helpers for analysing the [Token](token::Token)s lexx produces
+pub mod analysis;
+/// The [StringInterner](interner::StringInterner) for sharing [Token](token::Token) values
+pub mod interner;
 /// [RollingCharBuffer](RollingCharBuffer) is a fast, fixed size
 /// [char] buffer that can be used as a LIFO or FIFO stack.
 pub mod rolling_char_buffer;
@@ -136,6 +197,44 @@ pub mod matcher_float;
 pub mod matcher_integer;
 /// The [KeywordMatcher](matcher_keyword::KeywordMatcher)
 pub mod matcher_keyword;
+/// The [RepeatCharMatcher](matcher_repeat_char::RepeatCharMatcher)
+pub mod matcher_repeat_char;
+/// The [EmailMatcher](matcher_email::EmailMatcher)
+pub mod matcher_email;
+/// The [CharMapMatcher](matcher_char_map::CharMapMatcher)
+pub mod matcher_char_map;
+/// The [NewlineMatcher](matcher_newline::NewlineMatcher)
+pub mod matcher_newline;
+/// The [PrefixedMatcher](matcher_prefixed::PrefixedMatcher)
+pub mod matcher_prefixed;
+/// The [QuantityMatcher](matcher_quantity::QuantityMatcher)
+pub mod matcher_quantity;
+/// The [GroupedIntegerMatcher](matcher_grouped_integer::GroupedIntegerMatcher)
+pub mod matcher_grouped_integer;
+/// The [DigitGroupMatcher](matcher_digit_group::DigitGroupMatcher)
+pub mod matcher_digit_group;
+/// The [TagMatcher](matcher_tag::TagMatcher)
+pub mod matcher_tag;
+/// The [UrlMatcher](matcher_url::UrlMatcher)
+pub mod matcher_url;
+/// The [AltMatcher](matcher_alt::AltMatcher)
+pub mod matcher_alt;
+/// The [StateMachineMatcher](matcher_state_machine::StateMachineMatcher)
+pub mod matcher_state_machine;
+/// The [OperatorMatcher](matcher_operator::OperatorMatcher)
+pub mod matcher_operator;
+/// The [DateTimeMatcher](matcher_datetime::DateTimeMatcher)
+pub mod matcher_datetime;
+/// The [CommentMatcher](matcher_comment::CommentMatcher)
+pub mod matcher_comment;
+/// The [BlockCommentMatcher](matcher_block_comment::BlockCommentMatcher)
+pub mod matcher_block_comment;
+/// The [StringLiteralMatcher](matcher_string::StringLiteralMatcher)
+pub mod matcher_string;
+/// The [IdentifierMatcher](matcher_identifier::IdentifierMatcher)
+pub mod matcher_identifier;
+/// The [SuffixedMatcher](matcher_suffixed::SuffixedMatcher)
+pub mod matcher_suffixed;
 /// The [SymbolMatcher](matcher_symbol::SymbolMatcher)
 pub mod matcher_symbol;
 /// The [WhitespaceMatcher](matcher_whitespace::WhitespaceMatcher)
@@ -143,16 +242,30 @@ pub mod matcher_whitespace;
 /// The [WordMatcher](matcher_word::WordMatcher)
 pub mod matcher_word;
 
-use arrayvec::ArrayVec;
-use std::collections::HashMap;
+use std::cell::RefCell;
 use std::error::Error;
+use std::collections::hash_map::RandomState;
 use std::fmt;
+use std::hash::BuildHasher;
+use std::marker::PhantomData;
+use std::rc::Rc;
+use std::sync::mpsc::Sender;
+use std::thread;
+use std::thread::JoinHandle;
 
-use crate::input::LexxInput;
-use crate::matcher::Matcher;
+use crate::input::{InputIter, InputReader, LexxInput};
+use crate::interner::StringInterner;
+use crate::matcher::{Ctx, Matcher};
+use crate::matcher_email::EmailMatcher;
+use crate::matcher_float::FloatMatcher;
+use crate::matcher_integer::IntegerMatcher;
+use crate::matcher_newline::NewlineMatcher;
+use crate::matcher_symbol::SymbolMatcher;
+use crate::matcher_whitespace::WhitespaceMatcher;
+use crate::matcher_word::WordMatcher;
 use crate::matcher::MatcherResult::{Failed, Matched, Running};
 use crate::rolling_char_buffer::{RollingCharBuffer, RollingCharBufferError};
-use token::Token;
+use token::{Token, TOKEN_TYPE_WHITESPACE};
 
 /// Errors Lexx can return
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -160,7 +273,9 @@ pub enum LexxError {
     /// no matcher matched the current character(s)
     TokenNotFound(String),
     /// some other error
-    Error(String)
+    Error(String),
+    /// a match needed more than `CAP` chars, with the line the match started on
+    BufferOverflow(usize),
 }
 
 impl fmt::Display for LexxError {
@@ -172,6 +287,9 @@ impl fmt::Display for LexxError {
             LexxError::Error(ref s) => {
                 write!(f, "an error occurred: {:?}", s)
             }
+            LexxError::BufferOverflow(line) => {
+                write!(f, "ran out of buffer space for a token at line {}", line)
+            }
         }
     }
 }
@@ -182,34 +300,155 @@ impl Error for LexxError {
         match *self {
             LexxError::TokenNotFound(..) => "no token could be found",
             LexxError::Error(..) => "an error occurred",
+            LexxError::BufferOverflow(..) => "ran out of buffer space",
         }
     }
 }
 
+/// How [Lexx] picks between the [Matcher]s that match at the same position, see
+/// [Lexxer::set_match_strategy].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum MatchStrategy {
+    /// The longest match wins unless a shorter one has a higher precedence, this is the default.
+    #[default]
+    LongestMatch,
+    /// The first match to be completed wins, no matter how long the others could go. When more
+    /// than one [Matcher] completes at the same time the first in the matcher list wins.
+    FirstMatch,
+}
+
+/// The key in [Lexx::ctx] a [Matcher] sets to pop matcher sets off the mode stack once the
+/// [Token] it matched is returned, the value is how many to pop, see [Lexxer::push_mode].
+pub const LEXX_POP_MODE: &str = "lexx_pop_mode";
+
+/// A saved position in the input that [Lexx] can go back to, see [Lexxer::checkpoint].
+#[derive(Debug, Clone)]
+pub struct Checkpoint {
+    /// Where the [LexxInput] was, see [LexxInput::mark].
+    mark: Option<usize>,
+    /// The [char]s read from the [LexxInput] but not yet used.
+    cache: Vec<char>,
+    /// How many inputs were still chained, to tell if the input changed.
+    chained: usize,
+    line: usize,
+    column: usize,
+    byte_offset: usize,
+    prev_gap: usize,
+    lexx_result: Option<Result<Option<Token>, LexxError>>,
+    pending_trivia: Vec<Token>,
+    incomplete: bool,
+    at_start: bool,
+}
+
 /// The lexer itself. Implements [Lexxer](Lexxer) so you can use `Box<dyn Lexxer>` and don't
 /// have to define the `CAP` in var declarations.
 #[derive(Debug)]
-pub struct Lexx<const CAP: usize> {
+pub struct Lexx<const CAP: usize, S = RandomState> {
     /// The array of matcher used to generate tokens
-    matchers: Vec<Box<dyn Matcher>>,
+    matchers: Vec<Box<dyn Matcher<S>>>,
+    /// The labels given to the matchers, by index, see [Lexxer::push_matcher].
+    labels: Vec<Option<&'static str>>,
+    /// Which matchers are used, by index, see [Lexxer::set_active].
+    active: Vec<bool>,
     /// The input the matchers will be run against
     input: Box<dyn LexxInput>,
     /// When more chars are pulled from the input than the matchers use the
     /// excess is stored in this buffer. In this way the Input doesn't need to be re-indexed.
     /// This is also used by the Rewind feature.
     cache: Box<RollingCharBuffer<CAP>>,
-    /// While the match is being made the chars are stored in this buffer.
-    value: Box<ArrayVec<char, CAP>>,
+    /// While the match is being made the chars are stored in this buffer. It holds up to `CAP`
+    /// chars plus the one after them, which a token of exactly `CAP` needs to end its match. The
+    /// space is allocated once and it never grows.
+    value: Vec<char>,
     /// If [Lexx::look_ahead] is called the results are also stored here.
     pub lexx_result: Option<Result<Option<Token>, LexxError>>,
     /// While matches are being made the most recent acceptable token is stored here.
     pub found_token: Option<Token>,
+    /// How many modes to pop once [Lexx::found_token] is returned, the [LEXX_POP_MODE] its
+    /// [Matcher] set.
+    found_pop: i32,
     /// The current line in the input.
     pub line: usize,
     /// The current column in the input.
     pub column: usize,
     /// A general use hashmap that can be used by custom matchers for context sharing.
-    pub ctx: Box<HashMap<String, i32>>,
+    pub ctx: Box<Ctx<S>>,
+    /// The length of the previous [Token] if it was whitespace, used for [Token::prev_gap].
+    prev_gap: usize,
+    /// The current byte offset in the input, used for [Token::start_byte] and [Token::end_byte].
+    byte_offset: usize,
+    /// When set every match is recorded here while tokenizing, see [Lexx::find_ambiguities].
+    matches_seen: Option<Vec<(usize, Token)>>,
+    /// How many rewinds are allowed without progress, see [Lexxer::set_rewind_budget].
+    rewind_budget: Option<usize>,
+    /// The rewinds since the input last moved past `furthest_byte`.
+    rewinds: usize,
+    /// The furthest byte offset any [Token] has ended at.
+    furthest_byte: usize,
+    /// If the last [Lexxer::next_token] ran out of input that isn't closed, see
+    /// [Lexxer::is_incomplete].
+    incomplete: bool,
+    /// Every match made while finding the last [Token], see [Lexxer::last_competition].
+    #[cfg(debug_assertions)]
+    competition: Vec<(u16, usize, u8)>,
+    /// The token types attached as trivia, see [Lexxer::set_attach_trivia].
+    trivia_types: Vec<u16>,
+    /// Trivia [Token]s waiting for the next significant [Token].
+    pending_trivia: Vec<Token>,
+    /// Sets [Token::normalized], see [Lexxer::set_normalize].
+    normalizer: Option<Normalizer>,
+    /// How competing matches are picked, see [Lexxer::set_match_strategy].
+    match_strategy: MatchStrategy,
+    /// The inputs to read once the current one ends and if each restarts the position, see
+    /// [Lexxer::chain_input].
+    chained: Vec<(Box<dyn LexxInput>, bool)>,
+    /// If a `#!` first line is skipped, see [Lexxer::set_skip_shebang].
+    skip_shebang: bool,
+    /// If nothing has been read from the input yet.
+    at_start: bool,
+    /// The most [Matcher::step_cost] a single [Token] may use, see [Lexxer::set_step_budget].
+    step_budget: Option<u64>,
+    /// The token type for [char]s no [Matcher] matches, see [Lexxer::set_default_unmatched_type].
+    default_unmatched_type: Option<u16>,
+    /// Sets [Token::interned], see [Lexxer::set_interner].
+    interner: Option<Rc<RefCell<StringInterner>>>,
+    /// The token types that are interned.
+    interned_types: Vec<u16>,
+    /// Decides if a [Token] may be directly followed by a [char], see
+    /// [Lexxer::set_adjacency_rule].
+    adjacency_rule: Option<AdjacencyRule>,
+    /// The matcher sets under the one in use, see [Lexxer::push_mode].
+    modes: Vec<Mode<S>>,
+}
+
+/// A matcher set waiting under the one in use, see [Lexxer::push_mode].
+#[derive(Debug)]
+struct Mode<S> {
+    matchers: Vec<Box<dyn Matcher<S>>>,
+    labels: Vec<Option<&'static str>>,
+    active: Vec<bool>,
+}
+
+/// Wraps the normalizer given to [Lexxer::set_normalize] so [Lexx] can still be [Debug].
+struct Normalizer(Box<dyn Fn(&str) -> String>);
+
+impl fmt::Debug for Normalizer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Normalizer")
+    }
+}
+
+/// Returns `true` if a [Token] may be directly followed by a [char], see
+/// [Lexxer::set_adjacency_rule].
+pub type Adjacency = dyn Fn(&Token, char) -> bool;
+
+/// Wraps the rule given to [Lexxer::set_adjacency_rule] so [Lexx] can still be [Debug].
+struct AdjacencyRule(Box<Adjacency>);
+
+impl fmt::Debug for AdjacencyRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "AdjacencyRule")
+    }
 }
 
 impl<const CAP: usize> Lexx<CAP> {
@@ -227,44 +466,649 @@ impl<const CAP: usize> Lexx<CAP> {
     /// See [lexx](crate)
     ///
     pub fn new(input: Box<dyn LexxInput>, matchers: Vec<Box<dyn Matcher>>) -> Self {
+        Lexx::with_hasher(input, matchers, RandomState::new())
+    }
+
+    /// Creates a new Lexx like [Lexx::new] but first [validates](Matcher::validate) each of the
+    /// `matchers`, returning [LexxError::Error] describing the first misconfigured one.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - An instance of [LexxInput] that provides
+    ///   the char stream that will be lexed.
+    /// * `matchers` - a [vec] of [Matcher]s that will be used to
+    ///   generate Tokens.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lexx::{Lexx, LexxError};
+    /// use lexx::input::InputString;
+    /// use lexx::matcher_exact::ExactMatcher;
+    /// use lexx::matcher_word::WordMatcher;
+    /// use lexx::token::TOKEN_TYPE_EXACT;
+    ///
+    /// let lexx = Lexx::<512>::new_checked(
+    ///     Box::new(InputString::new(String::from("The fox"))),
+    ///     vec![
+    ///         Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] }),
+    ///         Box::new(ExactMatcher::build_exact_matcher(vec![], TOKEN_TYPE_EXACT, 1)),
+    ///     ],
+    /// );
+    ///
+    /// assert!(matches!(lexx, Err(LexxError::Error(e)) if e.contains("matcher 1")));
+    /// ```
+    ///
+    pub fn new_checked(
+        input: Box<dyn LexxInput>,
+        matchers: Vec<Box<dyn Matcher>>,
+    ) -> Result<Self, LexxError> {
+        for (i, m) in matchers.iter().enumerate() {
+            if let Err(e) = m.validate() {
+                return Err(LexxError::Error(format!("matcher {} is invalid: {}", i, e)));
+            }
+        }
+        Ok(Lexx::new(input, matchers))
+    }
+
+    /// Creates a new Lexx like [Lexx::new] that reads from stdin, for command line tools that
+    /// have their input piped in. The stdin lock is held for as long as the Lexx is.
+    ///
+    /// # Arguments
+    ///
+    /// * `matchers` - a [vec] of [Matcher]s that will be used to
+    ///   generate Tokens.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use lexx::Lexx;
+    /// use lexx::matcher_whitespace::WhitespaceMatcher;
+    /// use lexx::matcher_word::WordMatcher;
+    ///
+    /// let lexx = Lexx::<512>::from_stdin(vec![
+    ///     Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] }),
+    ///     Box::new(WhitespaceMatcher::with_predicate(char::is_whitespace, 0)),
+    /// ]);
+    /// for token in lexx {
+    ///     println!("{}", token.value);
+    /// }
+    /// ```
+    ///
+    /// Which is the same as wrapping the stdin lock in an [InputReader](input::InputReader)
+    /// yourself, as is done here with a [Cursor](std::io::Cursor) standing in for stdin.
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use lexx::{Lexx, Lexxer};
+    /// use lexx::input::InputReader;
+    /// use lexx::matcher_whitespace::WhitespaceMatcher;
+    /// use lexx::matcher_word::WordMatcher;
+    ///
+    /// let piped = Cursor::new(b"The quick fox\n".to_vec());
+    /// let mut lexx = Lexx::<512>::new(
+    ///     Box::new(InputReader::new(piped)),
+    ///     vec![
+    ///         Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] }),
+    ///         Box::new(WhitespaceMatcher::with_predicate(char::is_whitespace, 0)),
+    ///     ],
+    /// );
+    ///
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "The"));
+    /// assert_eq!(lexx.count(), 5);
+    /// ```
+    ///
+    pub fn from_stdin(matchers: Vec<Box<dyn Matcher>>) -> Self {
+        Lexx::new(
+            Box::new(InputReader::new(std::io::stdin().lock())),
+            matchers,
+        )
+    }
+
+    /// Creates a new Lexx from a comma separated list of built in matcher names, each made with
+    /// its default settings and a precedence of 0, for data driven setups. The names are
+    /// `word`, `whitespace`, `integer`, `float`, `symbol`, `email` and `newline`, matchers that
+    /// need more configuration, like the [ExactMatcher](matcher_exact::ExactMatcher), can be
+    /// added afterwards with [Lexxer::push_matcher].
+    ///
+    /// Returns [LexxError::Error] for an unknown name or an empty `spec`.
+    ///
+    /// # Arguments
+    ///
+    /// * `spec` - The matcher names, such as `"word,whitespace,integer"`.
+    /// * `input` - An instance of [LexxInput] that provides
+    ///   the char stream that will be lexed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lexx::{Lexx, Lexxer};
+    /// use lexx::input::InputString;
+    /// use lexx::token::{TOKEN_TYPE_INTEGER, TOKEN_TYPE_WORD};
+    ///
+    /// let mut lexx = Lexx::<512>::from_spec(
+    ///     "word, whitespace, integer",
+    ///     Box::new(InputString::new(String::from("abc 123"))),
+    /// ).unwrap();
+    ///
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "abc" && t.token_type == TOKEN_TYPE_WORD));
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == " "));
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "123" && t.token_type == TOKEN_TYPE_INTEGER));
+    ///
+    /// assert!(Lexx::<512>::from_spec("word,verb", Box::new(InputString::new(String::new()))).is_err());
+    /// ```
+    pub fn from_spec(spec: &str, input: Box<dyn LexxInput>) -> Result<Self, LexxError> {
+        let mut matchers: Vec<Box<dyn Matcher>> = vec![];
+        for name in spec.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            let matcher: Box<dyn Matcher> = match name {
+                "word" => Box::new(WordMatcher::default()),
+                "whitespace" => Box::new(WhitespaceMatcher::default()),
+                "integer" => Box::new(IntegerMatcher::default()),
+                "float" => Box::new(FloatMatcher::default()),
+                "symbol" => Box::new(SymbolMatcher::default()),
+                "email" => Box::new(EmailMatcher::default()),
+                "newline" => Box::new(NewlineMatcher::default()),
+                _ => return Err(LexxError::Error(format!("unknown matcher '{}'", name))),
+            };
+            matchers.push(matcher);
+        }
+        if matchers.is_empty() {
+            return Err(LexxError::Error("no matchers in spec".to_string()));
+        }
+        Ok(Lexx::new(input, matchers))
+    }
+
+    /// Lexes the values of `tokens` again with a different set of [Matcher]s, for example after
+    /// a macro expansion has built a new sequence of [Token]s. The values are joined together,
+    /// with nothing added between them, and run through a new [Lexx] with the `matchers`. The
+    /// positions of the new [Token]s are in that joined string, starting at line 1, column 1 and
+    /// byte 0, NOT in the input the `tokens` came from.
+    ///
+    /// # Arguments
+    ///
+    /// * `tokens` - The [Token]s whose values are lexed.
+    /// * `matchers` - The [Matcher]s to lex them with.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lexx::Lexx;
+    /// use lexx::token::{Token, TOKEN_TYPE_FLOAT, TOKEN_TYPE_INTEGER};
+    /// use lexx::input::InputString;
+    /// use lexx::matcher_float::FloatMatcher;
+    /// use lexx::matcher_integer::IntegerMatcher;
+    /// use lexx::matcher_symbol::SymbolMatcher;
+    ///
+    /// // "1", "." and "5" as separate tokens
+    /// let tokens: Vec<Token> = Lexx::<512>::new(
+    ///     Box::new(InputString::new(String::from("1.5"))),
+    ///     vec![Box::new(IntegerMatcher::default()), Box::new(SymbolMatcher::default())],
+    /// )
+    /// .collect();
+    /// assert_eq!(tokens.len(), 3);
+    ///
+    /// let relexed = Lexx::<512>::relex(&tokens, vec![Box::new(FloatMatcher::default())]).unwrap();
+    /// assert_eq!(relexed.len(), 1);
+    /// assert!(relexed[0].value == "1.5" && relexed[0].token_type == TOKEN_TYPE_FLOAT);
+    /// ```
+    pub fn relex(
+        tokens: &[Token],
+        matchers: Vec<Box<dyn Matcher>>,
+    ) -> Result<Vec<Token>, LexxError> {
+        let chars: Vec<char> = tokens.iter().flat_map(|t| t.value.chars()).collect();
+        let mut lexx = Lexx::<CAP>::new(Box::new(InputIter::new(chars.into_iter())), matchers);
+        let mut relexed = vec![];
+        while let Some(token) = lexx.next_token()? {
+            relexed.push(token);
+        }
+        Ok(relexed)
+    }
+}
+
+impl<const CAP: usize, S: BuildHasher> Lexx<CAP, S> {
+    /// Creates a new Lexx like [Lexx::new] whose [Lexx::ctx] uses the `hasher` instead of the
+    /// default one, for [Matcher]s that use the `ctx` for every [char], see [Matcher]. With the
+    /// `fast-hash` feature [FastHasher](matcher::FastHasher) can be used.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - An instance of [LexxInput] that provides
+    ///   the char stream that will be lexed.
+    /// * `matchers` - a [vec] of [Matcher]s that will be used to
+    ///   generate Tokens.
+    /// * `hasher` - Builds the hasher for [Lexx::ctx].
+    ///
+    pub fn with_hasher(
+        input: Box<dyn LexxInput>,
+        matchers: Vec<Box<dyn Matcher<S>>>,
+        hasher: S,
+    ) -> Self {
         let cache = Box::new(RollingCharBuffer::<CAP>::new());
+        let labels = vec![None; matchers.len()];
+        let active = vec![true; matchers.len()];
         Lexx {
             matchers,
+            labels,
+            active,
             input,
             cache,
-            value: Box::new(ArrayVec::<char, CAP>::new()),
+            value: Vec::with_capacity(CAP + 1),
             lexx_result: None,
             found_token: None,
+            found_pop: 0,
             line: 1,
             column: 1,
-            ctx: Box::new(HashMap::new()),
+            ctx: Box::new(Ctx::with_hasher(hasher)),
+            prev_gap: 0,
+            byte_offset: 0,
+            matches_seen: None,
+            rewind_budget: None,
+            rewinds: 0,
+            furthest_byte: 0,
+            incomplete: false,
+            #[cfg(debug_assertions)]
+            competition: vec![],
+            trivia_types: vec![],
+            pending_trivia: vec![],
+            normalizer: None,
+            match_strategy: MatchStrategy::LongestMatch,
+            chained: vec![],
+            skip_shebang: false,
+            at_start: true,
+            step_budget: None,
+            default_unmatched_type: None,
+            interner: None,
+            interned_types: vec![],
+            adjacency_rule: None,
+            modes: vec![],
+        }
+    }
+
+    /// Tokenizes `sample` and reports every time two [Matcher]s matched the exact same text at
+    /// the same position with the same precedence, in which case the later [Matcher] always wins.
+    /// This is usually an accident, such as the same keyword given to two matchers. Tokenizing
+    /// stops at the first error.
+    ///
+    /// This replaces the current input with `sample` using [Lexxer::set_input].
+    ///
+    /// Returns the indices of both [Matcher]s and the text they both matched, each combination is
+    /// only reported once.
+    ///
+    /// # Arguments
+    ///
+    /// * `sample` - Text that exercises the [Matcher]s.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lexx::Lexx;
+    /// use lexx::input::InputString;
+    /// use lexx::matcher_exact::ExactMatcher;
+    /// use lexx::matcher_whitespace::WhitespaceMatcher;
+    ///
+    /// let mut lexx = Lexx::<512>::new(
+    ///     Box::new(InputString::new(String::new())),
+    ///     vec![
+    ///         Box::new(WhitespaceMatcher::with_predicate(char::is_whitespace, 0)),
+    ///         Box::new(ExactMatcher::build_exact_matcher(vec!["if", "else"], 10, 0)),
+    ///         Box::new(ExactMatcher::build_exact_matcher(vec!["else", "while"], 11, 0)),
+    ///     ],
+    /// );
+    ///
+    /// assert_eq!(lexx.find_ambiguities("if else while"), vec![(1, 2, String::from("else"))]);
+    /// ```
+    pub fn find_ambiguities(&mut self, sample: &str) -> Vec<(usize, usize, String)> {
+        let chars: Vec<char> = sample.chars().collect();
+        self.set_input(Box::new(InputIter::new(chars.into_iter())));
+        self.matches_seen = Some(vec![]);
+        let mut ambiguities: Vec<(usize, usize, String)> = vec![];
+        loop {
+            let done = !matches!(self.get_token(), Ok(Some(_)));
+            let seen = self.matches_seen.replace(vec![]).unwrap_or_default();
+            for (n, (i, a)) in seen.iter().enumerate() {
+                for (j, b) in seen[n + 1..].iter() {
+                    let ambiguity = (*i, *j, a.value.clone());
+                    if i != j
+                        && a.len == b.len
+                        && a.precedence == b.precedence
+                        && !ambiguities.contains(&ambiguity)
+                    {
+                        ambiguities.push(ambiguity);
+                    }
+                }
+            }
+            if done {
+                break;
+            }
+        }
+        self.matches_seen = None;
+        ambiguities
+    }
+
+    /// Finds the next [Token] like [Lexxer::next_token] but instead of returning it calls `f`
+    /// with the matched [char]s, straight from the internal buffer, and returns what `f` returns.
+    /// For hot paths that only glance at each [Token] this skips everything [Lexx] adds to a
+    /// [Token] after it is matched, its [Token::normalized], [Token::interned] and
+    /// [Token::leading_trivia] values are never built. The [Matcher]s still make their own
+    /// [Token]s while matching.
+    ///
+    /// Trivia given to [Lexxer::set_attach_trivia] is skipped, `f` is only called for the other
+    /// [Token]s, and any trivia at the end of the input is dropped as there's nothing to attach it
+    /// to. A [Token] already found with [Lexxer::look_ahead] has been built, its [char]s are
+    /// copied out of its value.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - Called with the [char]s of the matched [Token].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lexx::Lexx;
+    /// use lexx::input::InputString;
+    /// use lexx::matcher_word::WordMatcher;
+    ///
+    /// let mut lexx = Lexx::<512>::new(
+    ///     Box::new(InputString::new(String::from("fox"))),
+    ///     vec![Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] })],
+    /// );
+    ///
+    /// assert_eq!(lexx.next_token_with(|chars| chars.len()), Ok(Some(3)));
+    /// assert_eq!(lexx.next_token_with(|chars| chars.len()), Ok(None));
+    /// ```
+    pub fn next_token_with<R>(
+        &mut self,
+        f: impl FnOnce(&[char]) -> R,
+    ) -> Result<Option<R>, LexxError> {
+        if let Some(lr) = self.lexx_result.take() {
+            return Ok(lr?.map(|token| f(&token.value.chars().collect::<Vec<char>>())));
+        }
+        let token = loop {
+            match self.match_token()? {
+                Some(token) if self.trivia_types.contains(&token.token_type) => {}
+                token => break token,
+            }
+        };
+        self.pending_trivia.clear();
+        Ok(token.map(|token| f(&self.value[0..token.len])))
+    }
+
+    /// Gives back the input, for handing what is left of it to something else once the
+    /// [Token]s that are wanted have been read. Like [Lexxer::take_input] any [char]s that were
+    /// read from the input but not used yet are lost, as are any inputs queued with
+    /// [Lexxer::chain_input].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lexx::{Lexx, Lexxer};
+    /// use lexx::input::InputString;
+    /// use lexx::matcher_whitespace::WhitespaceMatcher;
+    /// use lexx::matcher_word::WordMatcher;
+    ///
+    /// let mut lexx = Lexx::<512>::new(
+    ///     Box::new(InputString::new(String::from("The fox"))),
+    ///     vec![
+    ///         Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] }),
+    ///         Box::new(WhitespaceMatcher::with_predicate(char::is_whitespace, 0)),
+    ///     ],
+    /// );
+    ///
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "The"));
+    /// let mut input = lexx.into_input();
+    /// // the " " that ended "The" was read, so it is lost
+    /// assert_eq!(input.next(), Ok(Some('f')));
+    /// ```
+    pub fn into_input(self) -> Box<dyn LexxInput> {
+        self.input
+    }
+
+    /// Gets the next [Token] with [Lexxer::set_attach_trivia] applied.
+    fn get_significant_token(&mut self) -> Result<Option<Token>, LexxError> {
+        if self.trivia_types.is_empty() {
+            return self.get_token();
+        }
+        loop {
+            match self.get_token()? {
+                Some(token) if self.trivia_types.contains(&token.token_type) => {
+                    self.pending_trivia.push(token);
+                }
+                Some(mut token) => {
+                    token.leading_trivia = self.pending_trivia.drain(..).map(|t| t.value).collect();
+                    return Ok(Some(token));
+                }
+                // trivia at the end of the input has nothing to attach to
+                None if !self.incomplete && !self.pending_trivia.is_empty() => {
+                    return Ok(Some(self.pending_trivia.remove(0)));
+                }
+                None => return Ok(None),
+            }
+        }
+    }
+
+    /// Reads the next [char] from the input, moving on to the next chained input when it ends.
+    fn next_input_char(&mut self) -> Result<Option<char>, LexxError> {
+        loop {
+            let c = self.input.next()?;
+            if c.is_some() || !self.input.is_closed() || self.chained.is_empty() {
+                return Ok(c);
+            }
+            // a restart has to end the current match first, the switch is made on the next call
+            if self.chained[0].1 && !self.value.is_empty() {
+                return Ok(None);
+            }
+            let (input, restart) = self.chained.remove(0);
+            self.input = input;
+            if restart {
+                self.line = 1;
+                self.column = 1;
+                self.byte_offset = 0;
+                self.furthest_byte = 0;
+                self.rewinds = 0;
+            }
+        }
+    }
+
+    /// Skips the first line of the input if it starts with `#!`, see [Lexxer::set_skip_shebang].
+    /// Returns `false` if more input is needed to tell.
+    fn skip_shebang_line(&mut self) -> Result<bool, LexxError> {
+        let mut read: Vec<char> = vec![];
+        loop {
+            let c = if self.cache.is_empty() {
+                self.next_input_char()?
+            } else {
+                Some(self.cache.read().unwrap())
+            };
+            let Some(c) = c else {
+                if !self.input.is_closed() {
+                    // can't tell yet, try again when there's more input
+                    if !read.is_empty() && self.cache.prepend(&read).is_err() {
+                        return Err(self.buffer_overflow());
+                    }
+                    return Ok(false);
+                }
+                break;
+            };
+            read.push(c);
+            if (read.len() <= 2 && c != ['#', '!'][read.len() - 1]) || c == '\n' {
+                break;
+            }
+        }
+        self.at_start = false;
+        if !read.starts_with(&['#', '!']) {
+            if !read.is_empty() && self.cache.prepend(&read).is_err() {
+                return Err(self.buffer_overflow());
+            }
+            return Ok(true);
+        }
+        self.byte_offset += read.iter().map(|c| c.len_utf8()).sum::<usize>();
+        self.furthest_byte = self.byte_offset;
+        if read.last() == Some(&'\n') {
+            self.line = self.line.saturating_add(1);
+            self.column = 1;
+        } else {
+            self.column = self.column.saturating_add(read.len());
+        }
+        Ok(true)
+    }
+
+    /// The error for a match that needs more than `CAP` [char]s. The [char]s read for it are
+    /// dropped and the position moves past them so lexing can go on after them.
+    fn buffer_overflow(&mut self) -> LexxError {
+        let e = LexxError::BufferOverflow(self.line);
+        self.found_token = None;
+        for c in self.value.drain(..) {
+            self.byte_offset += c.len_utf8();
+            if c == '\n' {
+                self.line = self.line.saturating_add(1);
+                self.column = 1;
+            } else {
+                self.column = self.column.saturating_add(1);
+            }
+        }
+        self.furthest_byte = self.furthest_byte.max(self.byte_offset);
+        e
+    }
+
+    /// The error for a run that went past `CAP` [char]s with nothing shorter matched. The
+    /// [Matcher]s have been run to its end, so all of it is dropped, either the longest [Token]
+    /// they found or everything read if they found none, and any [char]s read past it are put
+    /// back in the cache.
+    fn skip_overflow(&mut self) -> LexxError {
+        let len = self
+            .found_token
+            .take()
+            .map_or(self.value.len(), |t| t.len.min(self.value.len()));
+        let mut rest = self.value.split_off(len);
+        let e = self.buffer_overflow();
+        if !rest.is_empty() && self.cache.prepend(&rest).is_err() {
+            // too much was read past the run to keep, it goes with it
+            self.value.append(&mut rest);
+            self.buffer_overflow();
+        }
+        // the run may have grown the value past its usual size
+        self.value.shrink_to(CAP + 1);
+        e
+    }
+
+    /// Returns the next [char] without using it up, it is left in the cache.
+    fn peek_char(&mut self) -> Result<Option<char>, LexxError> {
+        let c = if self.cache.is_empty() {
+            self.next_input_char()?
+        } else {
+            Some(self.cache.read().unwrap())
+        };
+        if let Some(c) = c {
+            // the value is the Token that was just returned, it's already been moved past
+            if self.cache.prefix(c).is_err() {
+                return Err(LexxError::BufferOverflow(self.line));
+            }
         }
+        Ok(c)
     }
 
+    /// A single [char] [Token] for a [char] no [Matcher] matched, see
+    /// [Lexxer::set_default_unmatched_type].
+    fn unmatched_token(c: char, token_type: u16) -> Token {
+        let (line, column) = if c == '\n' { (1, 1) } else { (0, 1) };
+        Token {
+            value: c.to_string(),
+            token_type,
+            len: 1,
+            line,
+            column,
+            precedence: 0,
+            ..Default::default()
+        }
+    }
+
+    /// Gets the next [Token] with its [Token::normalized] and [Token::interned] values set.
     fn get_token(&mut self) -> Result<Option<Token>, LexxError> {
+        let Some(mut token) = self.match_token()? else {
+            return Ok(None);
+        };
+        token.normalized = self.normalizer.as_ref().map(|n| (n.0)(&token.value));
+        if let Some(interner) = self.interner.as_ref() {
+            if self.interned_types.contains(&token.token_type) {
+                token.interned = Some(interner.borrow_mut().intern(&token.value));
+            }
+        }
+        Ok(Some(token))
+    }
+
+    /// Finds the next [Token], its [char]s are left at the start of `value`.
+    fn match_token(&mut self) -> Result<Option<Token>, LexxError> {
+        if let Some(budget) = self.rewind_budget {
+            if self.rewinds > budget {
+                return Err(LexxError::Error(format!(
+                    "rewound {} times without progress, the budget is {}",
+                    self.rewinds, budget
+                )));
+            }
+        }
         let mut precedence = 0;
+        let mut steps: u64 = 0;
+        let mut overflowed = false;
+        self.incomplete = false;
+        #[cfg(debug_assertions)]
+        self.competition.clear();
         self.value.clear();
-        for m in self.matchers.as_mut_slice() {
-            m.reset(&mut self.ctx);
+        if self.at_start {
+            if self.skip_shebang {
+                if !self.skip_shebang_line()? {
+                    self.incomplete = true;
+                    return Ok(None);
+                }
+            } else {
+                self.at_start = false;
+            }
+        }
+        for (i, m) in self.matchers.iter_mut().enumerate() {
+            if !self.active[i] {
+                continue;
+            }
+            if m.is_stateful() {
+                m.reset(&mut self.ctx);
+            } else {
+                m.reset_light();
+            }
         }
         if self.found_token.is_some() {
             self.found_token = None;
         }
+        self.found_pop = 0;
+        // a pop set by a match that wasn't returned, or before an error, is forgotten
+        if !self.ctx.is_empty() {
+            self.ctx.remove(LEXX_POP_MODE);
+        }
         loop {
             let c = if self.cache.is_empty() {
-                self.input.next()?
+                self.next_input_char()?
             } else {
                 Some(self.cache.read().unwrap())
             };
+            if c.is_none() && !self.input.is_closed() {
+                // more input may still come, so keep what's been read for the next try
+                if !self.value.is_empty()
+                    && self.cache.prepend(&self.value[0..self.value.len()]).is_err()
+                {
+                    return Err(self.buffer_overflow());
+                }
+                self.found_token = None;
+                self.incomplete = true;
+                return Ok(None);
+            }
             let mut found_token: Option<Token> = None;
+            let mut found_pop = 0;
             let mut running = false;
 
-            if c.is_some() {
-                self.value.push(c.unwrap());
+            if let Some(c) = c {
+                self.value.push(c);
             }
 
-            for m in self.matchers.as_mut_slice() {
-                if m.is_running() {
+            for (i, m) in self.matchers.iter_mut().enumerate() {
+                if self.active[i] && m.is_running() {
+                    steps += m.step_cost() as u64;
                     let int_result =
                         m.find_match(c, &self.value[0..self.value.len()], &mut self.ctx);
                     match int_result {
@@ -272,14 +1116,37 @@ impl<const CAP: usize> Lexx<CAP> {
                             running = true;
                         }
                         Matched(token) => {
+                            // the pop goes with this match only, not whichever one is returned
+                            let pop = if self.ctx.is_empty() {
+                                None
+                            } else {
+                                self.ctx.remove(LEXX_POP_MODE)
+                            };
+                            let Some(mut token) = m.on_match(token) else {
+                                continue;
+                            };
+                            token.label = self.labels[i];
+                            if let Some(seen) = self.matches_seen.as_mut() {
+                                seen.push((i, token.clone()));
+                            }
+                            #[cfg(debug_assertions)]
+                            self.competition.push((
+                                token.token_type,
+                                token.len,
+                                token.precedence,
+                            ));
                             if found_token.is_some() {
-                                if precedence <= token.precedence {
+                                if self.match_strategy == MatchStrategy::LongestMatch
+                                    && precedence <= token.precedence
+                                {
                                     precedence = token.precedence;
                                     found_token = Some(token);
+                                    found_pop = pop.unwrap_or(0);
                                 }
                             } else {
                                 precedence = token.precedence;
-                                found_token = Some(token)
+                                found_token = Some(token);
+                                found_pop = pop.unwrap_or(0);
                             }
                         }
                         Failed() => {}
@@ -287,6 +1154,22 @@ impl<const CAP: usize> Lexx<CAP> {
                 }
             }
 
+            if let Some(budget) = self.step_budget {
+                if steps > budget {
+                    // put back what was read so the same Token can be tried again
+                    if !self.value.is_empty()
+                        && self.cache.prepend(&self.value[0..self.value.len()]).is_err()
+                    {
+                        return Err(self.buffer_overflow());
+                    }
+                    self.found_token = None;
+                    return Err(LexxError::Error(format!(
+                        "matching at {}, {} took {} steps, the budget is {}",
+                        self.line, self.column, steps, budget
+                    )));
+                }
+            }
+
             if found_token.is_some() {
                 let t = found_token;
                 if self.found_token.is_some() {
@@ -294,40 +1177,117 @@ impl<const CAP: usize> Lexx<CAP> {
                         <= t.as_ref().unwrap().precedence
                     {
                         self.found_token = t;
+                        self.found_pop = found_pop;
                     }
                 } else {
                     self.found_token = t;
+                    self.found_pop = found_pop;
                 }
             }
 
-            if !running {
+            if running && !overflowed && self.value.len() > CAP {
+                if self.found_token.as_ref().is_some_and(|t| t.len <= CAP) {
+                    // the longer match can't fit, the one already found is used instead
+                    running = false;
+                } else {
+                    // nothing fits, the run is followed to its end so none of it comes back
+                    overflowed = true;
+                }
+            }
+
+            if !running
+                || (self.match_strategy == MatchStrategy::FirstMatch
+                    && self.found_token.is_some())
+            {
+                if overflowed {
+                    return Err(self.skip_overflow());
+                }
+                if self.found_token.is_none() && !self.value.is_empty() {
+                    if let Some(token_type) = self.default_unmatched_type {
+                        self.found_token = Some(Lexx::<CAP>::unmatched_token(
+                            self.value[0],
+                            token_type,
+                        ));
+                    }
+                }
                 return if self.found_token.is_some() {
-                    let mut token = self.found_token.as_ref().unwrap().clone();
-                    self.found_token = None;
-                    if self.value.len() > token.len {
-                        if let Err(e) = self.cache.prepend(&self.value[token.len..self.value.len()])
-                        {
-                            panic!("Ran out of buffer space: {}", e)
-                        };
+                    let mut token = self.found_token.take().unwrap();
+                    if token.len > CAP {
+                        return Err(self.buffer_overflow());
+                    }
+                    if self.value.len() > token.len
+                        && self.cache.prepend(&self.value[token.len..self.value.len()]).is_err()
+                    {
+                        return Err(self.buffer_overflow());
+                    }
+                    // a zero length token doesn't move the input on, so the same match would be
+                    // made again and again, this is a bug in the Matcher that made it
+                    #[cfg(debug_assertions)]
+                    if token.len == 0 {
+                        return Err(LexxError::Error(format!(
+                            "a Matcher made a zero length token at {}, {}, it would stall the input: {:?}",
+                            self.line, self.column, token
+                        )));
                     }
                     let l = self.line;
                     let c = self.column;
+                    // positions stop at usize::MAX rather than wrapping around on huge inputs
                     if token.line > 0 {
-                        self.line += token.line;
+                        self.line = self.line.saturating_add(token.line);
                         self.column = token.column;
                     } else {
-                        self.column += token.column;
+                        self.column = self.column.saturating_add(token.column);
                     }
                     token.line = l;
                     token.column = c;
-                    Ok(Some(token))
-                } else {
-                    if c.is_none() {
-                        return Ok(None);
+                    debug_assert!(
+                        token.line >= 1 && token.column >= 1,
+                        "token {:?} has a zero line or column, they are 1 based",
+                        token
+                    );
+                    token.start_byte = self.byte_offset;
+                    token.end_byte = self.byte_offset
+                        + self.value
+                            .iter()
+                            .take(token.len)
+                            .map(|c| c.len_utf8())
+                            .sum::<usize>();
+                    self.byte_offset = token.end_byte;
+                    if self.byte_offset > self.furthest_byte {
+                        self.furthest_byte = self.byte_offset;
+                        self.rewinds = 0;
                     }
-                    Err(LexxError::TokenNotFound(format!(
-                        "Could not resolve token at {}, {}: '{:?}'.",
-                        &self.line, &self.column, c
+                    token.prev_gap = self.prev_gap;
+                    self.prev_gap = if token.token_type == TOKEN_TYPE_WHITESPACE {
+                        token.len
+                    } else {
+                        0
+                    };
+                    if self.adjacency_rule.is_some() {
+                        if let Some(next) = self.peek_char()? {
+                            if !(self.adjacency_rule.as_ref().unwrap().0)(&token, next) {
+                                return Err(LexxError::Error(format!(
+                                    "{:?} can't be followed by {:?} at {}, {}",
+                                    token.value, next, self.line, self.column
+                                )));
+                            }
+                        }
+                    }
+                    for _ in 0..std::mem::take(&mut self.found_pop) {
+                        self.pop_mode();
+                    }
+                    Ok(Some(token))
+                } else {
+                    // chars left over at the end of the input that nothing matched, such as an
+                    // unclosed comment, are an error rather than silently dropped
+                    if c.is_none() && self.value.is_empty() {
+                        return Ok(None);
+                    }
+                    Err(LexxError::TokenNotFound(format!(
+                        "Could not resolve token at {}, {}: '{:?}'.",
+                        &self.line,
+                        &self.column,
+                        c.or(self.value.first().copied())
                     )))
                 };
             }
@@ -337,7 +1297,7 @@ impl<const CAP: usize> Lexx<CAP> {
         } // loop
     }
 }
-impl<const CAP: usize> Lexxer for Lexx<CAP> {
+impl<const CAP: usize, S: BuildHasher> Lexxer<S> for Lexx<CAP, S> {
     ///
     /// Returns the next [Result<Option<Token>, LexxError>](Result).
     ///
@@ -353,7 +1313,7 @@ impl<const CAP: usize> Lexxer for Lexx<CAP> {
             self.lexx_result = None;
             return lr;
         }
-        return self.get_token();
+        return self.get_significant_token();
     }
 
     ///
@@ -377,9 +1337,9 @@ impl<const CAP: usize> Lexxer for Lexx<CAP> {
     /// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
     /// Box::new(lexx_input),
     /// vec![
-    ///     Box::new(WordMatcher{ index: 0, precedence: 0, running: true }),
-    ///     Box::new(WhitespaceMatcher { index: 0, column: 0,line: 0,precedence: 0, running: true}),
-    ///     Box::new(SymbolMatcher { index:0, precedence: 0, running: true }),
+    ///     Box::new(WordMatcher{ index: 0, precedence: 0, running: true, trailing: &[] }),
+    ///     Box::new(WhitespaceMatcher { index: 0, column: 0,line: 0,precedence: 0, running: true, predicate: char::is_whitespace, line_continuation: None, mixed_indent: false}),
+    ///     Box::new(SymbolMatcher { index:0, precedence: 0, running: true, max_len: 0 }),
     ///     // with a precedence of 1 this will match "quick" instead of the word matcher
     ///     // We can change the TOKEN_TYPE value returned if we want to have more than one
     ///     // ExactMatcher that return different token types.
@@ -400,11 +1360,32 @@ impl<const CAP: usize> Lexxer for Lexx<CAP> {
         if self.lexx_result.is_some() {
             self.lexx_result.clone().unwrap()
         } else {
-            self.lexx_result = Some(self.get_token());
+            self.lexx_result = Some(self.get_significant_token());
             self.lexx_result.clone().unwrap()
         }
     }
 
+    ///
+    /// Returns how many [char]s have been read from the [LexxInput] but not yet returned by
+    /// [Lexxer::next_token], that is the `len` of a [Token] held by [Lexxer::look_ahead] plus
+    /// anything in the cache.
+    ///
+    fn lookahead_buffered_len(&self) -> usize {
+        let peeked = match &self.lexx_result {
+            Some(Ok(Some(token))) => token.len,
+            _ => 0,
+        };
+        peeked + self.cache.len()
+    }
+
+    ///
+    /// Returns the total number of [char]s in the input if the [LexxInput] knows it, see
+    /// [LexxInput::source_len].
+    ///
+    fn source_len(&self) -> Option<usize> {
+        self.input.source_len()
+    }
+
     ///
     /// Stuffs the token back into the stream to be re-tokenized
     /// (not really, but that's the effect it has). The line and column
@@ -415,13 +1396,22 @@ impl<const CAP: usize> Lexxer for Lexx<CAP> {
     /// as the cache buffer doesn't overflow. Be careful with line and column values
     /// if you want to mess with the order though.
     ///
+    /// If the cache doesn't have room for the `value` a
+    /// [BufferFullError](RollingCharBufferError::BufferFullError) is returned and nothing is
+    /// changed, the line and column stay where they were.
+    ///
     /// * `token` - The Token who's `value` will be pushed into the cache to be re-tokenized
     ///
     fn rewind(&mut self, token: Token) -> Result<usize, RollingCharBufferError> {
+        // only move once the value is back in the cache so a failed rewind changes nothing
+        let remaining = self
+            .cache
+            .prepend(&token.value.chars().collect::<Vec<char>>())?;
         self.line = token.line;
         self.column = token.column;
-        self.cache
-            .prepend(&token.value.chars().collect::<Vec<char>>())
+        self.byte_offset = token.start_byte;
+        self.rewinds += 1;
+        Ok(remaining)
     }
 
     ///
@@ -437,17 +1427,332 @@ impl<const CAP: usize> Lexxer for Lexx<CAP> {
         self.column = 1;
         self.cache.clear();
         self.lexx_result = None;
+        self.prev_gap = 0;
+        self.byte_offset = 0;
+        self.rewinds = 0;
+        self.furthest_byte = 0;
+        self.incomplete = false;
+        self.pending_trivia.clear();
+        self.chained.clear();
+        self.at_start = true;
+    }
+
+    ///
+    /// Queues `input` to be read once the current input ends, see [Lexxer::chain_input].
+    ///
+    /// * `input` - The [LexxInput] to read next.
+    /// * `restart` - If positions start over at line 1, column 1 and byte 0 for `input`.
+    ///
+    fn chain_input(&mut self, input: Box<dyn LexxInput>, restart: bool) {
+        self.chained.push((input, restart));
+    }
+
+    ///
+    /// Takes the input, leaving an empty one in its place, see [Lexxer::take_input].
+    ///
+    fn take_input(&mut self) -> Box<dyn LexxInput> {
+        let empty = || Box::new(InputIter::new(std::iter::empty()));
+        let input = std::mem::replace(&mut self.input, empty());
+        self.set_input(empty());
+        input
+    }
+
+    ///
+    /// Sets the line and column the next [Token] starts at, see [Lexxer::reset_position].
+    ///
+    /// * `line` - The line, starting at 1.
+    /// * `column` - The column, starting at 1.
+    ///
+    fn reset_position(&mut self, line: usize, column: usize) {
+        assert!(
+            line >= 1 && column >= 1,
+            "line and column start at 1, got {}, {}",
+            line,
+            column
+        );
+        self.line = line;
+        self.column = column;
+    }
+
+    ///
+    /// Attaches [Token]s of the `token_types` to the following [Token] as its
+    /// [Token::leading_trivia] instead of returning them, see [Lexxer::set_attach_trivia].
+    ///
+    /// * `token_types` - The token types that are trivia, empty to turn this off.
+    ///
+    fn set_attach_trivia(&mut self, token_types: &[u16]) {
+        self.trivia_types = token_types.to_vec();
+    }
+
+    ///
+    /// Returns the next [Token] with the trivia before it as a separate [String], see
+    /// [Lexxer::next_with_gap].
+    ///
+    fn next_with_gap(&mut self) -> Result<Option<(String, Token)>, LexxError> {
+        Ok(self
+            .next_token()?
+            .map(|mut token| (std::mem::take(&mut token.leading_trivia), token)))
+    }
+
+    ///
+    /// Sets the function used to fill in [Token::normalized], see [Lexxer::set_normalize].
+    ///
+    /// * `normalizer` - Rewrites a [Token::value].
+    ///
+    fn set_normalize(&mut self, normalizer: Box<dyn Fn(&str) -> String>) {
+        self.normalizer = Some(Normalizer(normalizer));
+    }
+
+    ///
+    /// Sets the [StringInterner] used to fill in [Token::interned], see [Lexxer::set_interner].
+    ///
+    /// * `interner` - The [StringInterner] to share.
+    /// * `token_types` - The token types to intern.
+    ///
+    fn set_interner(&mut self, interner: Rc<RefCell<StringInterner>>, token_types: &[u16]) {
+        self.interner = Some(interner);
+        self.interned_types = token_types.to_vec();
+    }
+
+    ///
+    /// Sets the rule for which [char]s may directly follow a [Token], see
+    /// [Lexxer::set_adjacency_rule].
+    ///
+    /// * `rule` - Returns `true` if the [Token] may be followed by the [char].
+    ///
+    fn set_adjacency_rule(&mut self, rule: Box<Adjacency>) {
+        self.adjacency_rule = Some(AdjacencyRule(rule));
+    }
+
+    ///
+    /// Sets how competing matches are picked, see [Lexxer::set_match_strategy].
+    ///
+    /// * `strategy` - The [MatchStrategy] to use.
+    ///
+    fn set_match_strategy(&mut self, strategy: MatchStrategy) {
+        self.match_strategy = strategy;
+    }
+
+    ///
+    /// Sets if a `#!` first line is skipped, see [Lexxer::set_skip_shebang].
+    ///
+    /// * `skip` - If the line is skipped.
+    ///
+    fn set_skip_shebang(&mut self, skip: bool) {
+        self.skip_shebang = skip;
+    }
+
+    ///
+    /// Sets the most work finding a single [Token] may take, see [Lexxer::set_step_budget].
+    ///
+    /// * `budget` - The most steps per [Token], or [None] for no limit.
+    ///
+    fn set_step_budget(&mut self, budget: Option<u64>) {
+        self.step_budget = budget;
+    }
+
+    ///
+    /// Sets the token type for [char]s no [Matcher] matches, see
+    /// [Lexxer::set_default_unmatched_type].
+    ///
+    /// * `token_type` - The token type, or [None] to return an error instead.
+    ///
+    fn set_default_unmatched_type(&mut self, token_type: Option<u16>) {
+        self.default_unmatched_type = token_type;
+    }
+
+    ///
+    /// Saves the current position, see [Lexxer::checkpoint].
+    ///
+    fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            mark: self.input.mark(),
+            cache: self.cache.to_vec(),
+            chained: self.chained.len(),
+            line: self.line,
+            column: self.column,
+            byte_offset: self.byte_offset,
+            prev_gap: self.prev_gap,
+            lexx_result: self.lexx_result.clone(),
+            pending_trivia: self.pending_trivia.clone(),
+            incomplete: self.incomplete,
+            at_start: self.at_start,
+        }
+    }
+
+    ///
+    /// Goes back to a saved position, see [Lexxer::restore].
+    ///
+    /// * `checkpoint` - The position from [Lexxer::checkpoint].
+    ///
+    fn restore(&mut self, checkpoint: Checkpoint) -> Result<(), LexxError> {
+        let Some(mark) = checkpoint.mark else {
+            return Err(LexxError::Error(String::from(
+                "the input can't go back to a checkpoint",
+            )));
+        };
+        if checkpoint.chained != self.chained.len() {
+            return Err(LexxError::Error(String::from(
+                "the input has changed since the checkpoint",
+            )));
+        }
+        self.input.seek(mark)?;
+        self.cache.clear();
+        if !checkpoint.cache.is_empty() {
+            if let Err(e) = self.cache.extend(&checkpoint.cache) {
+                return Err(LexxError::Error(format!(
+                    "can't restore the checkpoint cache: {:?}",
+                    e
+                )));
+            }
+        }
+        self.line = checkpoint.line;
+        self.column = checkpoint.column;
+        self.byte_offset = checkpoint.byte_offset;
+        self.prev_gap = checkpoint.prev_gap;
+        self.lexx_result = checkpoint.lexx_result;
+        self.pending_trivia = checkpoint.pending_trivia;
+        self.incomplete = checkpoint.incomplete;
+        self.at_start = checkpoint.at_start;
+        Ok(())
+    }
+
+    ///
+    /// Returns `true` if the last [Lexxer::next_token] returned [Ok(None)] because the input ran
+    /// out before it was closed, rather than at the end of the input.
+    ///
+    fn is_incomplete(&self) -> bool {
+        self.incomplete
+    }
+
+    ///
+    /// Returns how many of the active [Matcher]s are still running, see
+    /// [Lexxer::running_matcher_count].
+    ///
+    fn running_matcher_count(&self) -> usize {
+        self.matchers
+            .iter()
+            .enumerate()
+            .filter(|(i, m)| self.active[*i] && m.is_running())
+            .count()
+    }
+
+    ///
+    /// Returns the `(token_type, len, precedence)` of every match made while finding the most
+    /// recent [Token], empty in a release build.
+    ///
+    fn last_competition(&self) -> Vec<(u16, usize, u8)> {
+        #[cfg(debug_assertions)]
+        return self.competition.clone();
+        #[cfg(not(debug_assertions))]
+        vec![]
+    }
+
+    ///
+    /// Limits how many times [Lexxer::rewind] can be called without the [Lexxer] getting any
+    /// further into the input than it has been before, see [Lexxer::set_rewind_budget].
+    ///
+    /// * `budget` - The number of rewinds allowed, or [None] for no limit.
+    ///
+    fn set_rewind_budget(&mut self, budget: Option<usize>) {
+        self.rewind_budget = budget;
+        self.rewinds = 0;
+    }
+
+    ///
+    /// Adds a [Matcher] to the end of the matchers used to generate [Token]s.
+    ///
+    /// * `matcher` - The [Matcher] to add.
+    /// * `label` - An optional label that will be set as the [Token::label] of every [Token] this
+    ///   [Matcher] produces, for example to pick a syntax highlighting style.
+    ///
+    fn push_matcher(&mut self, matcher: Box<dyn Matcher<S>>, label: Option<&'static str>) {
+        self.matchers.push(matcher);
+        self.labels.push(label);
+        self.active.push(true);
+    }
+
+    ///
+    /// Chooses which [Matcher]s are used to generate [Token]s, all others are skipped until they
+    /// are made active again. Matchers are active when they are added.
+    ///
+    /// * `indices` - The indices of the [Matcher]s to use, in the order they were added.
+    ///
+    fn set_active(&mut self, indices: &[usize]) {
+        self.active.iter_mut().for_each(|a| *a = false);
+        for i in indices {
+            self.active[*i] = true;
+        }
+    }
+
+    ///
+    /// Describes every [Matcher], one per line, see [Lexxer::describe_config].
+    ///
+    fn describe_config(&self) -> String {
+        let mut config = String::new();
+        for (i, m) in self.matchers.iter().enumerate() {
+            config.push_str(&format!("{}: {}", i, m.describe()));
+            if let Some(label) = self.labels[i] {
+                config.push_str(&format!(" label {:?}", label));
+            }
+            if !self.active[i] {
+                config.push_str(" inactive");
+            }
+            config.push('\n');
+        }
+        config
+    }
+
+    ///
+    /// Tokenizes the remaining input without keeping the [Token]s, see [Lexxer::validate].
+    ///
+    fn validate(&mut self) -> Result<(usize, usize, usize), LexxError> {
+        let mut count = 0;
+        while self.next_token()?.is_some() {
+            count += 1;
+        }
+        Ok((count, self.line, self.column))
+    }
+
+    ///
+    /// Switches to a new set of [Matcher]s, keeping the current set to go back to, see
+    /// [Lexxer::push_mode].
+    ///
+    /// * `matchers` - The [Matcher]s to use until [Lexxer::pop_mode] is called.
+    ///
+    fn push_mode(&mut self, matchers: Vec<Box<dyn Matcher<S>>>) {
+        let labels = vec![None; matchers.len()];
+        let active = vec![true; matchers.len()];
+        self.modes.push(Mode {
+            matchers: std::mem::replace(&mut self.matchers, matchers),
+            labels: std::mem::replace(&mut self.labels, labels),
+            active: std::mem::replace(&mut self.active, active),
+        });
+    }
+
+    ///
+    /// Goes back to the [Matcher]s used before the last [Lexxer::push_mode], see
+    /// [Lexxer::pop_mode].
+    ///
+    fn pop_mode(&mut self) -> Option<Vec<Box<dyn Matcher<S>>>> {
+        let mode = self.modes.pop()?;
+        self.labels = mode.labels;
+        self.active = mode.active;
+        Some(std::mem::replace(&mut self.matchers, mode.matchers))
     }
 }
 
 /// A trait for [Lexx], so you can use `Box<dyn Lexxer>` and don't have to define the
 /// `CAP` in var declarations.
-pub trait Lexxer {
+pub trait Lexxer<S = RandomState> {
     ///
     /// Returns the next [Result<Option<Token>, LexxError>].
     ///
     /// The [Option] will be `None` if there is no remaining input (EOF)
     ///
+    /// In debug builds a [LexxError::Error] is returned if a [Matcher] makes a [Token] with a
+    /// `len` of 0, as that would never move on through the input.
+    ///
     /// # Examples
     ///
     /// See [lexx](crate)
@@ -463,6 +1768,12 @@ pub trait Lexxer {
     /// * `EndOfInput` - No more chars in the given [LexxInput](LexxInput).
     /// * `Failed` - Something went wrong or no match could be made.
     ///
+    /// Looking ahead reads from the [LexxInput](LexxInput) just like [Lexxer::next_token] does, any
+    /// [char]s read past the end of the looked ahead [Token] are kept in the cache, so nothing is
+    /// lost even when the input can't be re-read, such as an [InputReader](input::InputReader) over
+    /// a pipe. However [Lexxer::set_input] discards both the looked ahead result and the cache, so
+    /// anything already read from the old input is dropped.
+    ///
     /// # Examples
     ///
     /// ```rust
@@ -478,8 +1789,8 @@ pub trait Lexxer {
     /// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
     /// Box::new(lexx_input),
     /// vec![
-    ///     Box::new(WordMatcher{ index: 0, precedence: 0, running: true }),
-    ///     Box::new(WhitespaceMatcher { index: 0, column: 0,line: 0,precedence: 0, running: true}),
+    ///     Box::new(WordMatcher{ index: 0, precedence: 0, running: true, trailing: &[] }),
+    ///     Box::new(WhitespaceMatcher { index: 0, column: 0,line: 0,precedence: 0, running: true, predicate: char::is_whitespace, line_continuation: None, mixed_indent: false}),
     /// ]
     /// ));
     ///
@@ -497,6 +1808,22 @@ pub trait Lexxer {
     ///
     fn look_ahead(&mut self) -> Result<Option<Token>, LexxError>;
 
+    ///
+    /// Returns how many [char]s have been read from the [LexxInput] but not yet returned by
+    /// [Lexxer::next_token]. This is the `len` of the [Token] held by [Lexxer::look_ahead], if
+    /// any, plus the [char]s the [Matcher]s read past the end of a [Token] or that were put back
+    /// with [Lexxer::rewind]. Useful for reconciling the [LexxInput]'s position with the
+    /// [Token]s returned so far.
+    ///
+    fn lookahead_buffered_len(&self) -> usize;
+
+    ///
+    /// Returns the total number of [char]s in the input when the [LexxInput] knows it up front,
+    /// such as an [InputString](input::InputString), or [None] for streaming inputs. Along with
+    /// [Token::end_byte] or a count of the [Token] lengths this can drive a progress bar.
+    ///
+    fn source_len(&self) -> Option<usize>;
+
     ///
     /// Stuffs the token back into the stream to be re-tokenized
     /// (not really, but that's the effect this has). The line and column
@@ -507,6 +1834,10 @@ pub trait Lexxer {
     /// as the cache buffer doesn't overflow. Be careful with line and column values
     /// if you want to mess with the order though.
     ///
+    /// If the cache doesn't have room for the `value` a
+    /// [BufferFullError](RollingCharBufferError::BufferFullError) is returned and nothing is
+    /// changed, the line and column stay where they were.
+    ///
     /// * `token` - The Token who's `value` will be pushed into the cache to be re-tokenized
     ///
     fn rewind(&mut self, token: Token) -> Result<usize, RollingCharBufferError>;
@@ -519,81 +1850,2787 @@ pub trait Lexxer {
     /// * `input` - An instance of [LexxInput](LexxInput) that provides the char stream that will be lexed.
     ///
     fn set_input(&mut self, input: Box<dyn LexxInput>);
-}
 
-impl Iterator for dyn Lexxer {
-    type Item = Token;
+    ///
+    /// Queues `input` to be read once the current input, and any input chained before it, runs
+    /// out, as if they were one input spliced together. [Lexxer::set_input] drops anything
+    /// still queued.
+    ///
+    /// When `restart` is `false` the positions carry on from the end of the previous input and
+    /// a [Token] may run from one input into the next. When it is `true` the line, column and
+    /// byte offset start over at line 1, column 1 and byte 0, as they would for a new file, and
+    /// whatever was being matched at the end of the previous input is finished first, so no
+    /// [Token] spans the two.
+    ///
+    /// An input that isn't closed, such as an [AppendableInput](input::AppendableInput) that may
+    /// still get more, is waited on rather than moved past.
+    ///
+    /// * `input` - The [LexxInput] to read next.
+    /// * `restart` - If positions start over for `input`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lexx::{Lexx, Lexxer};
+    /// use lexx::input::InputString;
+    /// use lexx::matcher_whitespace::WhitespaceMatcher;
+    /// use lexx::matcher_word::WordMatcher;
+    ///
+    /// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+    ///     Box::new(InputString::new(String::from("The\n"))),
+    ///     vec![
+    ///         Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] }),
+    ///         Box::new(WhitespaceMatcher::with_predicate(char::is_whitespace, 0)),
+    ///     ],
+    /// ));
+    /// lexx.chain_input(Box::new(InputString::new(String::from("fox"))), false);
+    ///
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "The"));
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "\n"));
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "fox" && t.line == 2 && t.start_byte == 4));
+    /// assert!(matches!(lexx.next_token(), Ok(None)));
+    /// ```
+    ///
+    fn chain_input(&mut self, input: Box<dyn LexxInput>, restart: bool);
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.next_token()
-            .unwrap_or_else(|e| panic!("{}", e.to_string()))
-    }
-}
+    ///
+    /// Takes the input back out, for handing what is left of it to something else, and leaves
+    /// the [Lexxer] as if [Lexxer::set_input] had been called with an empty input. Any [char]s
+    /// already read from the input, such as the ones after the last [Token] that were looked at
+    /// to end it or a [Token] that was looked ahead to, are lost, as are any inputs queued with
+    /// [Lexxer::chain_input]. [Lexx::into_input] does the same when the [Lexx] isn't needed
+    /// any more.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lexx::{Lexx, Lexxer};
+    /// use lexx::input::InputString;
+    /// use lexx::matcher_whitespace::WhitespaceMatcher;
+    /// use lexx::matcher_word::WordMatcher;
+    ///
+    /// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+    ///     Box::new(InputString::new(String::from("The fox"))),
+    ///     vec![
+    ///         Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] }),
+    ///         Box::new(WhitespaceMatcher::with_predicate(char::is_whitespace, 0)),
+    ///     ],
+    /// ));
+    ///
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "The"));
+    /// let mut input = lexx.take_input();
+    /// assert_eq!(input.next(), Ok(Some('f')));
+    /// assert!(matches!(lexx.next_token(), Ok(None)));
+    /// ```
+    ///
+    fn take_input(&mut self) -> Box<dyn LexxInput>;
 
-impl<const CAP: usize> Iterator for Lexx<CAP> {
-    type Item = Token;
+    ///
+    /// Sets the line and column the next [Token] starts at, positions after it follow on from
+    /// there. Nothing else changes, the input, the cache and [Token::start_byte] are left as they
+    /// are. This is a low level escape hatch for fixing up positions after changing what's read
+    /// some other way, [Lexxer::rewind] takes care of this itself. A [Token] already found by
+    /// [Lexxer::look_ahead] keeps the position it had.
+    ///
+    /// * `line` - The line, starting at 1. Panics if it is 0.
+    /// * `column` - The column, starting at 1. Panics if it is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lexx::{Lexx, Lexxer};
+    /// use lexx::input::InputString;
+    /// use lexx::matcher_word::WordMatcher;
+    ///
+    /// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+    ///     Box::new(InputString::new(String::from("fox"))),
+    ///     vec![Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] })],
+    /// ));
+    /// lexx.reset_position(10, 5);
+    ///
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "fox" && t.line == 10 && t.column == 5));
+    /// ```
+    ///
+    fn reset_position(&mut self, line: usize, column: usize);
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.next_token()
-            .unwrap_or_else(|e| panic!("{}", e.to_string()))
-    }
-}
+    ///
+    /// Stops returning [Token]s of the `token_types`, such as [TOKEN_TYPE_WHITESPACE], and
+    /// instead attaches their values to the next [Token] as its [Token::leading_trivia], much like
+    /// the trivia of Roslyn. Together the `leading_trivia` and `value` of each [Token] still add up
+    /// to the whole input, except that trivia at the very end, with no [Token] after it to attach
+    /// to, is returned as is. The positions of a [Token] are its own, not those of its trivia.
+    ///
+    /// * `token_types` - The token types that are trivia, empty to turn this off.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lexx::{Lexx, Lexxer};
+    /// use lexx::input::InputString;
+    /// use lexx::matcher_whitespace::WhitespaceMatcher;
+    /// use lexx::matcher_word::WordMatcher;
+    /// use lexx::token::TOKEN_TYPE_WHITESPACE;
+    ///
+    /// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+    ///     Box::new(InputString::new(String::from("The  fox "))),
+    ///     vec![
+    ///         Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] }),
+    ///         Box::new(WhitespaceMatcher { index: 0, column: 0, line: 0, precedence: 0, running: true, predicate: char::is_whitespace, line_continuation: None, mixed_indent: false }),
+    ///     ],
+    /// ));
+    /// lexx.set_attach_trivia(&[TOKEN_TYPE_WHITESPACE]);
+    ///
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "The" && t.leading_trivia.is_empty()));
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "fox" && t.leading_trivia == "  " && t.column == 6));
+    /// // nothing comes after the last space
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == " " && t.token_type == TOKEN_TYPE_WHITESPACE));
+    /// assert!(matches!(lexx.next_token(), Ok(None)));
+    /// ```
+    ///
+    fn set_attach_trivia(&mut self, token_types: &[u16]);
 
-#[cfg(test)]
-mod tests {
-    use crate::matcher_exact::ExactMatcher;
-    use crate::matcher_whitespace::WhitespaceMatcher;
-    use crate::matcher_word::WordMatcher;
-    use crate::{Lexx, Lexxer, Token};
-    use crate::input::InputString;
-    use crate::token::{TOKEN_TYPE_EXACT, TOKEN_TYPE_WHITESPACE};
+    ///
+    /// Returns the next [Token] like [Lexxer::next_token] together with the text of the trivia
+    /// that came since the last [Token], for example to keep or drop it in a minifier. The trivia
+    /// are the token types given to [Lexxer::set_attach_trivia], the text is taken out of the
+    /// [Token::leading_trivia] so the returned [Token] always has an empty `leading_trivia`. With
+    /// no trivia types set the gap is always empty.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lexx::{Lexx, Lexxer};
+    /// use lexx::input::InputString;
+    /// use lexx::matcher_whitespace::WhitespaceMatcher;
+    /// use lexx::matcher_word::WordMatcher;
+    /// use lexx::token::TOKEN_TYPE_WHITESPACE;
+    ///
+    /// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+    ///     Box::new(InputString::new(String::from("The \n fox"))),
+    ///     vec![
+    ///         Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] }),
+    ///         Box::new(WhitespaceMatcher::with_predicate(char::is_whitespace, 0)),
+    ///     ],
+    /// ));
+    /// lexx.set_attach_trivia(&[TOKEN_TYPE_WHITESPACE]);
+    ///
+    /// assert!(matches!(lexx.next_with_gap(), Ok(Some((g, t))) if g.is_empty() && t.value == "The"));
+    /// assert!(matches!(lexx.next_with_gap(), Ok(Some((g, t))) if g == " \n " && t.value == "fox" && t.leading_trivia.is_empty()));
+    /// assert!(matches!(lexx.next_with_gap(), Ok(None)));
+    /// ```
+    ///
+    fn next_with_gap(&mut self) -> Result<Option<(String, Token)>, LexxError>;
 
-    #[test]
-    fn lexx_test_precedence() {
+    ///
+    /// Runs `normalizer` on the `value` of every [Token] from now on and stores the result in its
+    /// [Token::normalized], leaving the `value` as it was in the input. Useful for case
+    /// insensitive handling of identifiers later on.
+    ///
+    /// * `normalizer` - Rewrites a [Token::value], for example `Box::new(|v| v.to_lowercase())`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lexx::{Lexx, Lexxer};
+    /// use lexx::input::InputString;
+    /// use lexx::matcher_word::WordMatcher;
+    ///
+    /// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+    ///     Box::new(InputString::new(String::from("FOO"))),
+    ///     vec![Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] })],
+    /// ));
+    /// lexx.set_normalize(Box::new(|v| v.to_lowercase()));
+    ///
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "FOO" && t.normalized == Some(String::from("foo"))));
+    /// ```
+    ///
+    fn set_normalize(&mut self, normalizer: Box<dyn Fn(&str) -> String>);
+
+    ///
+    /// Interns the `value` of every [Token] of the `token_types` from now on in `interner` and
+    /// stores its id in [Token::interned]. The same value always gets the same id, so a parser
+    /// can keep ids instead of [String]s for, say, identifiers that are used over and over. The
+    /// `interner` is shared so it can be read while lexing goes on, or used by more than one
+    /// [Lexx].
+    ///
+    /// * `interner` - The [StringInterner] to share.
+    /// * `token_types` - The token types to intern, such as [TOKEN_TYPE_WORD](token::TOKEN_TYPE_WORD).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::cell::RefCell;
+    /// use std::rc::Rc;
+    /// use lexx::{Lexx, Lexxer};
+    /// use lexx::input::InputString;
+    /// use lexx::interner::StringInterner;
+    /// use lexx::token::TOKEN_TYPE_WORD;
+    ///
+    /// let interner = Rc::new(RefCell::new(StringInterner::new()));
+    /// let mut lexx = Lexx::<512>::from_spec(
+    ///     "word,whitespace",
+    ///     Box::new(InputString::new(String::from("foo bar foo"))),
+    /// ).unwrap();
+    /// lexx.set_interner(Rc::clone(&interner), &[TOKEN_TYPE_WORD]);
+    ///
+    /// let ids: Vec<Option<usize>> = lexx.map(|t| t.interned).collect();
+    /// assert_eq!(ids, vec![Some(0), None, Some(1), None, Some(0)]);
+    /// assert_eq!(interner.borrow().resolve(1), Some("bar"));
+    /// ```
+    ///
+    fn set_interner(&mut self, interner: Rc<RefCell<StringInterner>>, token_types: &[u16]);
+
+    ///
+    /// Checks every [Token] from now on against the [char] directly after it, if `rule` returns
+    /// `false` a [LexxError::Error] is returned instead of the [Token]. This catches things the
+    /// [Matcher]s split up without complaint, such as `123abc` lexed as an integer and a word
+    /// when a number can't run straight into letters. The [char] is only looked at, the next
+    /// [Lexxer::next_token] carries on from it. The last [Token] in the input isn't checked.
+    ///
+    /// * `rule` - Returns `true` if the [Token] may be followed by the [char].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lexx::{Lexx, LexxError, Lexxer};
+    /// use lexx::input::InputString;
+    /// use lexx::token::TOKEN_TYPE_INTEGER;
+    ///
+    /// let mut lexx = Lexx::<512>::from_spec(
+    ///     "word,whitespace,integer",
+    ///     Box::new(InputString::new(String::from("12 34ab"))),
+    /// ).unwrap();
+    /// lexx.set_adjacency_rule(Box::new(|t, c| t.token_type != TOKEN_TYPE_INTEGER || !c.is_alphabetic()));
+    ///
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "12"));
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == " "));
+    /// assert!(matches!(lexx.next_token(), Err(LexxError::Error(e)) if e == "\"34\" can't be followed by 'a' at 1, 6"));
+    /// ```
+    ///
+    fn set_adjacency_rule(&mut self, rule: Box<Adjacency>);
+
+    ///
+    /// Sets how [Lexx] picks between [Matcher]s that match at the same position. The default
+    /// [MatchStrategy::LongestMatch] lets every [Matcher] run as far as it can and keeps the
+    /// longest match, or the one with the highest precedence. [MatchStrategy::FirstMatch] stops
+    /// at the first completed match instead, like the ordered choice of a PEG grammar, when more
+    /// than one completes at once the first in the matcher list wins and precedence is ignored.
+    ///
+    /// A [Matcher] completes a match when it sees the [char] after it, so the first match is
+    /// usually the shortest.
+    ///
+    /// * `strategy` - The [MatchStrategy] to use.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lexx::{Lexx, Lexxer, MatchStrategy};
+    /// use lexx::input::InputString;
+    /// use lexx::matcher_exact::ExactMatcher;
+    /// use lexx::matcher_word::WordMatcher;
+    /// use lexx::token::{TOKEN_TYPE_EXACT, TOKEN_TYPE_WORD};
+    ///
+    /// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+    ///     Box::new(InputString::new(String::from("foxes"))),
+    ///     vec![
+    ///         Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] }),
+    ///         Box::new(ExactMatcher::build_exact_matcher(vec!["fox"], TOKEN_TYPE_EXACT, 0)),
+    ///     ],
+    /// ));
+    /// lexx.set_match_strategy(MatchStrategy::FirstMatch);
+    ///
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "fox" && t.token_type == TOKEN_TYPE_EXACT));
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "es" && t.token_type == TOKEN_TYPE_WORD));
+    /// ```
+    ///
+    fn set_match_strategy(&mut self, strategy: MatchStrategy);
+
+    ///
+    /// Skips the first line of the input, up to and including its line ending, if it starts with
+    /// `#!`, such as the `#!/usr/bin/env python` of a script. No [Token] is made for it, the first
+    /// [Token] is on line 2 with its positions counted from the start of the input. An input that
+    /// doesn't start with `#!` is lexed as is. This only has an effect before the first [Token]
+    /// of an input is read and is kept for new inputs given to [Lexxer::set_input].
+    ///
+    /// * `skip` - If the line is skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lexx::{Lexx, Lexxer};
+    /// use lexx::input::InputString;
+    /// use lexx::matcher_whitespace::WhitespaceMatcher;
+    /// use lexx::matcher_word::WordMatcher;
+    ///
+    /// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+    ///     Box::new(InputString::new(String::from("#!/bin/sh\necho"))),
+    ///     vec![
+    ///         Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] }),
+    ///         Box::new(WhitespaceMatcher::with_predicate(char::is_whitespace, 0)),
+    ///     ],
+    /// ));
+    /// lexx.set_skip_shebang(true);
+    ///
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "echo" && t.line == 2 && t.column == 1 && t.start_byte == 10));
+    /// assert!(matches!(lexx.next_token(), Ok(None)));
+    /// ```
+    ///
+    fn set_skip_shebang(&mut self, skip: bool);
+
+    ///
+    /// Limits how much work finding a single [Token] may take, to keep one expensive [Matcher]
+    /// from holding things up. Every call to a [Matcher::find_match] adds its
+    /// [Matcher::step_cost] to a count that starts over for each [Token], when the count goes
+    /// over the `budget` [Lexxer::next_token] returns a [LexxError::Error].
+    ///
+    /// The error can be recovered from, the [char]s read for the [Token] are kept and read again
+    /// on the next call. Unless something changes, such as a bigger budget or the expensive
+    /// [Matcher] turned off with [Lexxer::set_active], the same error will be returned again.
+    ///
+    /// * `budget` - The most steps per [Token], or [None] for no limit, the default.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lexx::{Lexx, Lexxer, LexxError};
+    /// use lexx::input::InputString;
+    /// use lexx::matcher_word::WordMatcher;
+    ///
+    /// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+    ///     Box::new(InputString::new(String::from("fox"))),
+    ///     vec![Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] })],
+    /// ));
+    /// // "fox" and the end of the input take a step each
+    /// lexx.set_step_budget(Some(3));
+    /// assert!(matches!(lexx.next_token(), Err(LexxError::Error(_))));
+    ///
+    /// lexx.set_step_budget(Some(4));
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "fox"));
+    /// ```
+    ///
+    fn set_step_budget(&mut self, budget: Option<u64>);
+
+    ///
+    /// When set a [char] that no [Matcher] matches is returned as a single [char] [Token] of
+    /// `token_type` instead of a [LexxError::TokenNotFound], as if there were a catch-all
+    /// [Matcher] with the lowest precedence. Only the first unmatched [char] is returned, the
+    /// [Matcher]s start again at the [char] after it.
+    ///
+    /// * `token_type` - The token type, or [None] to return an error, the default.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lexx::{Lexx, Lexxer};
+    /// use lexx::input::InputString;
+    /// use lexx::matcher_word::WordMatcher;
+    /// use lexx::token::{TOKEN_TYPE_SYMBOL, TOKEN_TYPE_WORD};
+    ///
+    /// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+    ///     Box::new(InputString::new(String::from("a%b"))),
+    ///     vec![Box::new(WordMatcher::default())],
+    /// ));
+    /// lexx.set_default_unmatched_type(Some(TOKEN_TYPE_SYMBOL));
+    ///
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "a" && t.token_type == TOKEN_TYPE_WORD));
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "%" && t.token_type == TOKEN_TYPE_SYMBOL && t.column == 2));
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "b" && t.token_type == TOKEN_TYPE_WORD));
+    /// assert!(matches!(lexx.next_token(), Ok(None)));
+    /// ```
+    ///
+    fn set_default_unmatched_type(&mut self, token_type: Option<u16>);
+
+    ///
+    /// Saves the current position so [Lexxer::restore] can come back to it, for example before
+    /// trying a speculative parse. This is much lighter than cloning a [Lexx], only the position
+    /// and the [char]s read ahead into the cache are saved, the [Matcher]s are not.
+    ///
+    /// Only inputs that can go back, such as [InputString](input::InputString), can be restored,
+    /// see [LexxInput::mark].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lexx::Lexx;
+    /// use lexx::Lexxer;
+    /// use lexx::input::InputString;
+    ///
+    /// let mut lexx = Lexx::<512>::from_spec(
+    ///     "word,whitespace",
+    ///     Box::new(InputString::new(String::from("maybe this"))),
+    /// ).unwrap();
+    ///
+    /// let checkpoint = lexx.checkpoint();
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "maybe"));
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == " "));
+    ///
+    /// // not what was wanted, go back and try again
+    /// lexx.restore(checkpoint).unwrap();
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "maybe" && t.column == 1));
+    /// ```
+    ///
+    fn checkpoint(&self) -> Checkpoint;
+
+    ///
+    /// Goes back to a position saved with [Lexxer::checkpoint], the [Token]s after it will be
+    /// returned again. The [Matcher]s are not restored, a stateful [Matcher] that remembers
+    /// earlier [Token]s may not match the same way the second time.
+    ///
+    /// Returns a [LexxError::Error] if the [LexxInput] can't go back, see [LexxInput::mark], or
+    /// if the input has changed since the [Checkpoint] was made with [Lexxer::set_input] or
+    /// [Lexxer::chain_input].
+    ///
+    /// * `checkpoint` - The position from [Lexxer::checkpoint].
+    ///
+    fn restore(&mut self, checkpoint: Checkpoint) -> Result<(), LexxError>;
+
+    ///
+    /// Guards against a parser that rewinds and re-reads the same [Token]s forever. Each
+    /// [Lexxer::rewind] is counted and the count starts over whenever a [Token] ends further into
+    /// the input than any [Token] before it. Once the count goes over the `budget` the following
+    /// [Lexxer::next_token] returns a [LexxError::Error] instead of a [Token]. There is no limit by
+    /// default. Setting the budget, or a new input, starts the count over.
+    ///
+    /// * `budget` - The number of rewinds allowed without progress, or [None] for no limit.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lexx::{Lexx, LexxError, Lexxer};
+    /// use lexx::input::InputString;
+    /// use lexx::matcher_word::WordMatcher;
+    ///
+    /// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+    ///     Box::new(InputString::new(String::from("loop"))),
+    ///     vec![Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] })],
+    /// ));
+    /// lexx.set_rewind_budget(Some(1));
+    ///
+    /// let token = lexx.next_token().unwrap().unwrap();
+    /// lexx.rewind(token).unwrap();
+    /// let token = lexx.next_token().unwrap().unwrap();
+    /// lexx.rewind(token).unwrap();
+    /// assert!(matches!(lexx.next_token(), Err(LexxError::Error(_))));
+    /// ```
+    ///
+    fn set_rewind_budget(&mut self, budget: Option<usize>);
+
+    ///
+    /// Returns `true` if the last [Lexxer::next_token] or [Lexxer::look_ahead] returned [Ok(None)]
+    /// only because the [LexxInput] has run out for now, it isn't
+    /// [closed](LexxInput::is_closed), rather than because it has ended. Any partial [Token] is
+    /// kept and matched again, with whatever comes next, on the following call. See
+    /// [AppendableInput](input::AppendableInput).
+    ///
+    fn is_incomplete(&self) -> bool;
+
+    ///
+    /// Returns how many of the active [Matcher]s are still running, that is how many were still
+    /// candidates for a longer match when the last [Lexxer::next_token] stopped. Once a [Token] is
+    /// found this is usually 0 as every [Matcher] has to finish first, but with
+    /// [MatchStrategy::FirstMatch] it is the number that were cut short, and when
+    /// [Lexxer::is_incomplete] it is the number still waiting on more input.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lexx::{Lexx, Lexxer};
+    /// use lexx::input::InputString;
+    /// use lexx::matcher_word::WordMatcher;
+    ///
+    /// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+    ///     Box::new(InputString::new(String::from("fox"))),
+    ///     vec![Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] })],
+    /// ));
+    ///
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "fox"));
+    /// assert_eq!(lexx.running_matcher_count(), 0);
+    /// ```
+    ///
+    fn running_matcher_count(&self) -> usize;
+
+    ///
+    /// Returns the `(token_type, len, precedence)` of every candidate the [Matcher]s came up with
+    /// while finding the most recent [Token], in the order they were made, for working out why a
+    /// precedence setup picks the [Token]s it does. Candidates that were shorter than the winner
+    /// are included. Note that [Lexxer::look_ahead] finds a [Token] too, so after looking ahead
+    /// this describes the looked ahead [Token].
+    ///
+    /// The candidates are only recorded in debug builds, so matching doesn't pay for them in a
+    /// release build, there this is always empty. The method is there in both so code calling it
+    /// builds either way.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lexx::{Lexx, Lexxer};
+    /// use lexx::input::InputString;
+    /// use lexx::matcher_exact::ExactMatcher;
+    /// use lexx::matcher_word::WordMatcher;
+    /// use lexx::token::{TOKEN_TYPE_EXACT, TOKEN_TYPE_WORD};
+    ///
+    /// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+    ///     Box::new(InputString::new(String::from("fox"))),
+    ///     vec![
+    ///         Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] }),
+    ///         Box::new(ExactMatcher::build_exact_matcher(vec!["fox"], TOKEN_TYPE_EXACT, 1)),
+    ///     ],
+    /// ));
+    ///
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_EXACT));
+    /// // empty if lexx itself was built for release
+    /// let competition = lexx.last_competition();
+    /// assert!(competition.is_empty() || competition == vec![(TOKEN_TYPE_WORD, 3, 0), (TOKEN_TYPE_EXACT, 3, 1)]);
+    /// ```
+    ///
+    fn last_competition(&self) -> Vec<(u16, usize, u8)>;
+
+    ///
+    /// Adds a [Matcher] to the end of the matchers used to generate [Token]s.
+    ///
+    /// * `matcher` - The [Matcher] to add.
+    /// * `label` - An optional label that will be set as the [Token::label] of every [Token] this
+    ///   [Matcher] produces, for example to pick a syntax highlighting style.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lexx::{Lexx, Lexxer};
+    /// use lexx::input::InputString;
+    /// use lexx::matcher_whitespace::WhitespaceMatcher;
+    /// use lexx::matcher_word::WordMatcher;
+    ///
+    /// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+    ///     Box::new(InputString::new(String::from("The fox"))),
+    ///     vec![Box::new(WhitespaceMatcher { index: 0, column: 0, line: 0, precedence: 0, running: true, predicate: char::is_whitespace, line_continuation: None, mixed_indent: false })],
+    /// ));
+    /// lexx.push_matcher(Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] }), Some("ident"));
+    ///
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "The" && t.label == Some("ident")));
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == " " && t.label == None));
+    /// ```
+    ///
+    fn push_matcher(&mut self, matcher: Box<dyn Matcher<S>>, label: Option<&'static str>);
+
+    ///
+    /// Chooses which [Matcher]s are used to generate [Token]s. Inactive [Matcher]s stay in the
+    /// [Lexxer] but are neither reset nor run, which is cheaper than removing them and adding them
+    /// back later. All [Matcher]s are active when they are added.
+    ///
+    /// * `indices` - The indices of the [Matcher]s to use, in the order they were added. Panics if
+    ///   an index is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lexx::{Lexx, LexxError, Lexxer};
+    /// use lexx::input::InputString;
+    /// use lexx::matcher_integer::IntegerMatcher;
+    /// use lexx::matcher_whitespace::WhitespaceMatcher;
+    /// use lexx::matcher_word::WordMatcher;
+    /// use lexx::token::{TOKEN_TYPE_WHITESPACE, TOKEN_TYPE_WORD};
+    ///
+    /// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+    ///     Box::new(InputString::new(String::from("abc 123"))),
+    ///     vec![
+    ///         Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] }),
+    ///         Box::new(IntegerMatcher { index: 0, precedence: 0, running: true, radix_prefixes: false }),
+    ///         Box::new(WhitespaceMatcher { index: 0, column: 0, line: 0, precedence: 0, running: true, predicate: char::is_whitespace, line_continuation: None, mixed_indent: false }),
+    ///     ],
+    /// ));
+    ///
+    /// lexx.set_active(&[0, 2]);
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "abc" && t.token_type == TOKEN_TYPE_WORD));
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_WHITESPACE));
+    /// // the IntegerMatcher isn't active
+    /// assert!(matches!(lexx.next_token(), Err(LexxError::TokenNotFound(_))));
+    /// ```
+    ///
+    fn set_active(&mut self, indices: &[usize]);
+
+    ///
+    /// Describes the [Matcher]s with [Matcher::describe], one per line in the order they were
+    /// added, with their labels from [Lexxer::push_matcher] and whether they are turned off with
+    /// [Lexxer::set_active]. Handy for attaching to a bug report.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lexx::{Lexx, Lexxer};
+    /// use lexx::input::InputString;
+    /// use lexx::matcher_word::WordMatcher;
+    /// use lexx::matcher_exact::ExactMatcher;
+    /// use lexx::token::TOKEN_TYPE_EXACT;
+    ///
+    /// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+    ///     Box::new(InputString::new(String::from("for"))),
+    ///     vec![Box::new(WordMatcher::default())],
+    /// ));
+    /// lexx.push_matcher(Box::new(ExactMatcher::build_exact_matcher(vec!["for"], TOKEN_TYPE_EXACT, 1)), Some("loop"));
+    /// lexx.set_active(&[1]);
+    ///
+    /// assert_eq!(
+    ///     lexx.describe_config(),
+    ///     "0: WordMatcher { precedence: 0, trailing: [] } inactive\n\
+    ///      1: ExactMatcher { precedence: 1, token_type: 6, targets: [\"for\"] } label \"loop\"\n"
+    /// );
+    /// ```
+    ///
+    fn describe_config(&self) -> String;
+
+    ///
+    /// Tokenizes the remaining input and throws the [Token]s away, for checking that an input
+    /// tokenizes at all without collecting it into a [Vec]. Returns how many [Token]s there were
+    /// and the line and column just past the end of the input, or the first error.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lexx::{Lexx, Lexxer, LexxError};
+    /// use lexx::input::InputString;
+    ///
+    /// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::from_spec(
+    ///     "word,whitespace",
+    ///     Box::new(InputString::new(String::from("The quick\nfox"))),
+    /// ).unwrap());
+    /// assert_eq!(lexx.validate(), Ok((5, 2, 4)));
+    ///
+    /// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::from_spec(
+    ///     "word,whitespace",
+    ///     Box::new(InputString::new(String::from("The 42"))),
+    /// ).unwrap());
+    /// assert!(matches!(lexx.validate(), Err(LexxError::TokenNotFound(_))));
+    /// ```
+    ///
+    fn validate(&mut self) -> Result<(usize, usize, usize), LexxError>;
+
+    ///
+    /// Switches to a new set of [Matcher]s and keeps the current set on a stack, for inputs that
+    /// hold other languages such as a template with code in it, or SQL inside a string. The new
+    /// [Matcher]s are used from the next [Token] on until [Lexxer::pop_mode] goes back to the set
+    /// before them, with its labels and active [Matcher]s as they were. A [Token] already read by
+    /// [Lexxer::look_ahead] was made by the [Matcher]s before the switch.
+    ///
+    /// A [Matcher] can end its mode itself by setting [LEXX_POP_MODE] in the `ctx` to how many
+    /// sets to pop when it returns the [Token] that ends the mode, they are popped once that
+    /// [Token] is returned. It is read as each match is made, so it only counts if that match is
+    /// the [Token] returned, a pop set by a match that loses to a longer one is dropped.
+    ///
+    /// * `matchers` - The [Matcher]s to use until [Lexxer::pop_mode] is called.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lexx::{Lexx, Lexxer};
+    /// use lexx::input::InputString;
+    /// use lexx::matcher_exact::ExactMatcher;
+    /// use lexx::matcher_whitespace::WhitespaceMatcher;
+    /// use lexx::matcher_word::WordMatcher;
+    /// use lexx::token::{TOKEN_TYPE_KEYWORD, TOKEN_TYPE_WORD};
+    ///
+    /// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+    ///     Box::new(InputString::new(String::from("if if"))),
+    ///     vec![
+    ///         Box::new(WordMatcher::default()),
+    ///         Box::new(WhitespaceMatcher::default()),
+    ///         Box::new(ExactMatcher::build_exact_matcher(vec!["if"], TOKEN_TYPE_KEYWORD, 1)),
+    ///     ],
+    /// ));
+    ///
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "if" && t.token_type == TOKEN_TYPE_KEYWORD));
+    /// lexx.push_mode(vec![Box::new(WordMatcher::default()), Box::new(WhitespaceMatcher::default())]);
+    /// lexx.next_token().unwrap();
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "if" && t.token_type == TOKEN_TYPE_WORD));
+    /// assert_eq!(lexx.pop_mode().map(|m| m.len()), Some(2));
+    /// // there is nothing under the first set
+    /// assert!(lexx.pop_mode().is_none());
+    /// ```
+    ///
+    fn push_mode(&mut self, matchers: Vec<Box<dyn Matcher<S>>>);
+
+    ///
+    /// Goes back to the [Matcher]s used before the last [Lexxer::push_mode] and returns the ones
+    /// that were in use, or [None] if there was no [Lexxer::push_mode] to undo, in which case
+    /// nothing changes.
+    ///
+    fn pop_mode(&mut self) -> Option<Vec<Box<dyn Matcher<S>>>>;
+
+    ///
+    /// Tokenizes the remaining input, sending every [Result<Token, LexxError>](Result) to `tx`.
+    /// Stops at the end of input, after sending the first error, or if the receiving end of the
+    /// channel has hung up.
+    ///
+    /// * `tx` - The [Sender] the results will be sent to.
+    ///
+    fn drain_into(&mut self, tx: &Sender<Result<Token, LexxError>>) {
+        loop {
+            let result = match self.next_token() {
+                Ok(Some(token)) => Ok(token),
+                Ok(None) => return,
+                Err(e) => Err(e),
+            };
+            let failed = result.is_err();
+            if tx.send(result).is_err() || failed {
+                return;
+            }
+        }
+    }
+}
+
+///
+/// Runs a [Lexxer] on a new thread, sending every [Result<Token, LexxError>](Result) over `tx`
+/// until the end of input, see [Lexxer::drain_into].
+///
+/// [Matcher]s and [LexxInput]s are not required to be [Send] so the [Lexxer] itself can't be moved
+/// to another thread, instead a `factory` that builds it is sent and called on the new thread.
+///
+/// * `factory` - Creates the [Lexxer] to run.
+/// * `tx` - The [Sender] the results will be sent to.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::mpsc::channel;
+/// use lexx::{spawn_into, Lexx, Lexxer};
+/// use lexx::input::InputString;
+/// use lexx::matcher_whitespace::WhitespaceMatcher;
+/// use lexx::matcher_word::WordMatcher;
+///
+/// let (tx, rx) = channel();
+/// let handle = spawn_into(
+///     || -> Box<dyn Lexxer> {
+///         Box::new(Lexx::<512>::new(
+///             Box::new(InputString::new(String::from("The quick fox"))),
+///             vec![
+///                 Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] }),
+///                 Box::new(WhitespaceMatcher { index: 0, column: 0, line: 0, precedence: 0, running: true, predicate: char::is_whitespace, line_continuation: None, mixed_indent: false }),
+///             ],
+///         ))
+///     },
+///     tx,
+/// );
+///
+/// let values: Vec<String> = rx.iter().map(|r| r.unwrap().value).collect();
+/// handle.join().unwrap();
+/// assert_eq!(values, vec!["The", " ", "quick", " ", "fox"]);
+/// ```
+pub fn spawn_into<F>(factory: F, tx: Sender<Result<Token, LexxError>>) -> JoinHandle<()>
+where
+    F: FnOnce() -> Box<dyn Lexxer> + Send + 'static,
+{
+    thread::spawn(move || {
+        let mut lexx = factory();
+        lexx.drain_into(&tx);
+    })
+}
+
+impl<S> Iterator for dyn Lexxer<S> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_token()
+            .unwrap_or_else(|e| panic!("{}", e.to_string()))
+    }
+}
+
+impl<const CAP: usize, S: BuildHasher> Iterator for Lexx<CAP, S> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_token()
+            .unwrap_or_else(|e| panic!("{}", e.to_string()))
+    }
+}
+
+/// [Iterator] adaptors for every [Lexxer], a [Lexx] as well as a `Box<dyn Lexxer>`. It is
+/// implemented for all of them, it only needs to be brought into scope with
+/// `use lexx::LexxerExt`.
+pub trait LexxerExt<S = RandomState>: Lexxer<S> {
+    ///
+    /// Returns an [Iterator] over `(previous, current, next)` [Token] windows, see [Windows3].
+    ///
+    fn windows3(&mut self) -> Windows3<'_, Self, S> {
+        Windows3 {
+            lexx: self,
+            prev: None,
+            hasher: PhantomData,
+        }
+    }
+
+    ///
+    /// Returns an [Iterator] over the remaining [Result<Token, LexxError>](Result)s that, unlike
+    /// iterating the [Lexxer] itself, doesn't panic on errors. It ends at the end of input or
+    /// right after yielding the first error. The [Lexxer] is only borrowed so it can still be used
+    /// afterwards, for example with [Lexxer::set_input].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lexx::{Lexx, LexxError, Lexxer, LexxerExt};
+    /// use lexx::input::InputString;
+    /// use lexx::matcher_word::WordMatcher;
+    ///
+    /// let mut lexx = Lexx::<512>::new(
+    ///     Box::new(InputString::new(String::from("abc!"))),
+    ///     vec![Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] })],
+    /// );
+    ///
+    /// let results: Vec<Result<_, LexxError>> = lexx.iter_results().collect();
+    /// assert!(matches!(&results[0], Ok(t) if t.value == "abc"));
+    /// assert!(matches!(&results[1], Err(LexxError::TokenNotFound(_))));
+    /// assert_eq!(results.len(), 2);
+    /// ```
+    fn iter_results(&mut self) -> impl Iterator<Item = Result<Token, LexxError>> + '_ {
+        results_of(self)
+    }
+
+    ///
+    /// Returns an [Iterator] over the remaining [Result<Token, LexxError>](Result)s with `f`
+    /// applied to each [Token] as it is lexed, without collecting them first. Like
+    /// [LexxerExt::iter_results] errors are passed on, not given to `f`, and it ends right after
+    /// the first one.
+    ///
+    /// Unlike [Iterator::map] this borrows the [Lexxer] instead of taking it. A `Box<dyn Lexxer>`
+    /// is not itself a [Lexxer] so it couldn't be passed by value, and the [Lexxer] can still be
+    /// used afterwards, for example with [Lexxer::set_input].
+    ///
+    /// * `f` - Transforms a [Token].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lexx::{Lexx, LexxerExt};
+    /// use lexx::input::InputString;
+    /// use lexx::token::TOKEN_TYPE_WORD;
+    ///
+    /// let mut lexx = Lexx::<512>::from_spec(
+    ///     "word,whitespace",
+    ///     Box::new(InputString::new(String::from("The fox"))),
+    /// ).unwrap();
+    ///
+    /// let values: Vec<String> = lexx
+    ///     .map_tokens(|mut t| {
+    ///         if t.token_type == TOKEN_TYPE_WORD {
+    ///             t.value = t.value.to_uppercase();
+    ///         }
+    ///         t
+    ///     })
+    ///     .map(|r| r.unwrap().value)
+    ///     .collect();
+    /// assert_eq!(values, vec!["THE", " ", "FOX"]);
+    /// ```
+    fn map_tokens<'a, F>(
+        &'a mut self,
+        f: F,
+    ) -> impl Iterator<Item = Result<Token, LexxError>> + 'a
+    where
+        F: FnMut(Token) -> Token + 'a,
+    {
+        results_of(self).map(mapped(f))
+    }
+}
+
+impl<S, L: Lexxer<S> + ?Sized> LexxerExt<S> for L {}
+
+/// Wraps `f` so it is applied to the [Token] of an [Ok] result, see [LexxerExt::map_tokens].
+fn mapped<F>(mut f: F) -> impl FnMut(Result<Token, LexxError>) -> Result<Token, LexxError>
+where
+    F: FnMut(Token) -> Token,
+{
+    move |result| result.map(&mut f)
+}
+
+fn results_of<S, L: Lexxer<S> + ?Sized>(
+    lexx: &mut L,
+) -> impl Iterator<Item = Result<Token, LexxError>> + '_ {
+    let mut done = false;
+    std::iter::from_fn(move || {
+        if done {
+            return None;
+        }
+        match lexx.next_token() {
+            Ok(Some(token)) => Some(Ok(token)),
+            Ok(None) => {
+                done = true;
+                None
+            }
+            Err(e) => {
+                done = true;
+                Some(Err(e))
+            }
+        }
+    })
+}
+
+/// An [Iterator] that yields each [Token] along with the [Token]s before and after it as
+/// `(Option<Token>, Token, Option<Token>)`. The previous [Token] is [None] for the first
+/// [Token] and the next [Token] is [None] for the last. The next [Token] is found with
+/// [Lexxer::look_ahead] so it is not lost.
+///
+/// # Panics
+///
+/// Like the [Iterator] for [Lexx] this panics if the [Lexxer] returns an error.
+///
+/// # Example
+///
+/// ```rust
+/// use lexx::{Lexx, Lexxer, LexxerExt};
+/// use lexx::input::InputString;
+/// use lexx::matcher_whitespace::WhitespaceMatcher;
+/// use lexx::matcher_word::WordMatcher;
+///
+/// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+///     Box::new(InputString::new(String::from("The fox"))),
+///     vec![
+///         Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] }),
+///         Box::new(WhitespaceMatcher { index: 0, column: 0, line: 0, precedence: 0, running: true, predicate: char::is_whitespace, line_continuation: None, mixed_indent: false }),
+///     ]
+/// ));
+///
+/// for (prev, current, next) in lexx.windows3() {
+///     if current.value == " " {
+///         assert!(matches!(prev, Some(t) if t.value == "The"));
+///         assert!(matches!(next, Some(t) if t.value == "fox"));
+///     }
+/// }
+/// ```
+pub struct Windows3<'a, L: Lexxer<S> + ?Sized = dyn Lexxer, S = RandomState> {
+    /// The [Lexxer] the [Token]s come from.
+    lexx: &'a mut L,
+    /// The last [Token] returned as the current one.
+    prev: Option<Token>,
+    /// The hasher of the [Lexxer]'s `ctx`.
+    hasher: PhantomData<fn() -> S>,
+}
+
+impl<'a, L: Lexxer<S> + ?Sized, S> fmt::Debug for Windows3<'a, L, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Windows3").field("prev", &self.prev).finish()
+    }
+}
+
+impl<'a, L: Lexxer<S> + ?Sized, S> Iterator for Windows3<'a, L, S> {
+    type Item = (Option<Token>, Token, Option<Token>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self
+            .lexx
+            .next_token()
+            .unwrap_or_else(|e| panic!("{}", e))?;
+        let next = self
+            .lexx
+            .look_ahead()
+            .unwrap_or_else(|e| panic!("{}", e));
+        let prev = self.prev.replace(current.clone());
+        Some((prev, current, next))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::matcher_exact::ExactMatcher;
+    use crate::matcher_integer::IntegerMatcher;
+    use crate::matcher_symbol::SymbolMatcher;
+    use crate::matcher_whitespace::WhitespaceMatcher;
+    use crate::matcher_word::WordMatcher;
+    use crate::{
+        spawn_into, Lexx, LexxError, Lexxer, LexxerExt, MatchStrategy, Token, LEXX_POP_MODE,
+    };
+    use crate::input::{InputIter, InputString, LexxInput};
+    use crate::matcher::{Ctx, Matcher, MatcherResult};
+    use crate::token::{
+        TOKEN_TYPE_EXACT, TOKEN_TYPE_INTEGER, TOKEN_TYPE_KEYWORD, TOKEN_TYPE_SYMBOL,
+        TOKEN_TYPE_WHITESPACE, TOKEN_TYPE_WORD,
+    };
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use std::sync::mpsc::channel;
+
+    /// A word matcher that boosts the precedence of the word `return` for that single match.
+    #[derive(Debug)]
+    struct ContextualKeywordMatcher {
+        index: usize,
+        running: bool,
+    }
+
+    impl Matcher for ContextualKeywordMatcher {
+        fn reset(&mut self, _ctx: &mut Box<Ctx>) {
+            self.index = 0;
+            self.running = true;
+        }
+
+        fn find_match(
+            &mut self,
+            oc: Option<char>,
+            value: &[char],
+            _ctx: &mut Box<Ctx>,
+        ) -> MatcherResult {
+            match oc {
+                Some(c) if c.is_alphabetic() => {
+                    self.index += 1;
+                    MatcherResult::Running()
+                }
+                _ => {
+                    self.running = false;
+                    if self.index == 0 {
+                        return MatcherResult::Failed();
+                    }
+                    let word: String = value[0..self.index].iter().collect();
+                    let precedence = if word == "return" { 1 } else { 0 };
+                    MatcherResult::Matched(Token {
+                        value: word,
+                        token_type: TOKEN_TYPE_KEYWORD,
+                        len: self.index,
+                        line: 0,
+                        column: self.index,
+                        precedence,
+                        ..Default::default()
+                    })
+                }
+            }
+        }
+
+        fn is_running(&self) -> bool {
+            self.running
+        }
+
+        fn precedence(&self) -> u8 {
+            0
+        }
+    }
+
+    /// A [WordMatcher] that rejects all uppercase words using [Matcher::on_match].
+    #[derive(Debug)]
+    struct NoShoutingMatcher {
+        words: WordMatcher,
+    }
+
+    impl Matcher for NoShoutingMatcher {
+        fn reset(&mut self, ctx: &mut Box<Ctx>) {
+            self.words.reset(ctx);
+        }
+
+        fn find_match(
+            &mut self,
+            oc: Option<char>,
+            value: &[char],
+            ctx: &mut Box<Ctx>,
+        ) -> MatcherResult {
+            self.words.find_match(oc, value, ctx)
+        }
+
+        fn on_match(&mut self, token: Token) -> Option<Token> {
+            if token.value.chars().all(char::is_uppercase) {
+                None
+            } else {
+                Some(token)
+            }
+        }
+
+        fn is_running(&self) -> bool {
+            <WordMatcher as Matcher>::is_running(&self.words)
+        }
+
+        fn precedence(&self) -> u8 {
+            <WordMatcher as Matcher>::precedence(&self.words)
+        }
+    }
+
+    /// A broken [Matcher] that matches nothing at all
+    #[cfg(debug_assertions)]
+    #[derive(Debug)]
+    struct ZeroLengthMatcher {
+        running: bool,
+    }
+
+    #[cfg(debug_assertions)]
+    impl Matcher for ZeroLengthMatcher {
+        fn reset(&mut self, _ctx: &mut Box<Ctx>) {
+            self.running = true;
+        }
+
+        fn find_match(
+            &mut self,
+            _oc: Option<char>,
+            _value: &[char],
+            _ctx: &mut Box<Ctx>,
+        ) -> MatcherResult {
+            self.running = false;
+            MatcherResult::Matched(Token {
+                value: String::new(),
+                token_type: 100,
+                len: 0,
+                line: 0,
+                column: 0,
+                precedence: 0,
+                ..Default::default()
+            })
+        }
+
+        fn is_running(&self) -> bool {
+            self.running
+        }
+
+        fn precedence(&self) -> u8 {
+            0
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn lexx_test_zero_length_token() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("fox"))),
+            vec![Box::new(ZeroLengthMatcher { running: true })],
+        );
+
+        assert!(
+            matches!(lexx.next_token(), Err(LexxError::Error(e)) if e.contains("zero length token at 1, 1"))
+        );
+    }
+
+    #[test]
+    fn lexx_test_match_strategy() {
+        let make = || {
+            Lexx::<512>::new(
+                Box::new(InputString::new(String::from("foxes fox"))),
+                vec![
+                    Box::new(WordMatcher {
+                        index: 0,
+                        precedence: 0,
+                        running: true,
+                        trailing: &[],
+                    }),
+                    Box::new(WhitespaceMatcher {
+                        index: 0,
+                        column: 0,
+                        line: 0,
+                        precedence: 0,
+                        running: true,
+                        predicate: char::is_whitespace,
+                        line_continuation: None,
+                        mixed_indent: false,
+                    }),
+                    Box::new(ExactMatcher::build_exact_matcher(
+                        vec!["fox"],
+                        TOKEN_TYPE_EXACT,
+                        0,
+                    )),
+                ],
+            )
+        };
+
+        // the longest match wins, then the later matcher for the same length
+        let mut lexx = make();
+        assert!(
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "foxes" && t.token_type == TOKEN_TYPE_WORD)
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_WHITESPACE));
+        assert!(
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "fox" && t.token_type == TOKEN_TYPE_EXACT)
+        );
+
+        // the first to complete wins, then the first in the list for the same length
+        let mut lexx = make();
+        lexx.set_match_strategy(MatchStrategy::FirstMatch);
+        assert!(
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "fox" && t.token_type == TOKEN_TYPE_EXACT && t.column == 1)
+        );
+        assert!(
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "es" && t.token_type == TOKEN_TYPE_WORD && t.column == 4)
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_WHITESPACE));
+        assert!(
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "fox" && t.token_type == TOKEN_TYPE_WORD)
+        );
+        assert!(matches!(lexx.next_token(), Ok(None)));
+
+        lexx.set_match_strategy(MatchStrategy::LongestMatch);
+        lexx.set_input(Box::new(InputString::new(String::from("foxes"))));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "foxes"));
+    }
+
+    #[test]
+    fn lexx_test_running_matcher_count() {
+        use crate::input::AppendableInput;
+        use crate::matcher_exact::ExactMatcher;
+
+        let input = AppendableInput::new();
+        let mut lexx = Lexx::<512>::new(
+            Box::new(input.clone()),
+            vec![
+                Box::new(WordMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    trailing: &[],
+                }),
+                Box::new(IntegerMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    radix_prefixes: false,
+                }),
+                Box::new(ExactMatcher::build_exact_matcher(
+                    vec!["fox", "fog"],
+                    TOKEN_TYPE_EXACT,
+                    1,
+                )),
+                Box::new(WhitespaceMatcher::with_predicate(char::is_whitespace, 0)),
+            ],
+        );
+
+        // "fo" could still be a word or either exact match, the integer and whitespace are out
+        input.push_str("fo");
+        assert!(matches!(lexx.next_token(), Ok(None)));
+        assert!(lexx.is_incomplete());
+        assert_eq!(lexx.running_matcher_count(), 2);
+
+        // both exact matches have failed, only the word is left
+        input.push_str("ur");
+        assert!(matches!(lexx.next_token(), Ok(None)));
+        assert_eq!(lexx.running_matcher_count(), 1);
+
+        // inactive matchers aren't counted
+        lexx.set_active(&[1, 2, 3]);
+        assert_eq!(lexx.running_matcher_count(), 0);
+        lexx.set_active(&[0, 1, 2, 3]);
+
+        input.push_str(" ");
+        input.close();
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "four"));
+        assert_eq!(lexx.running_matcher_count(), 0);
+
+        // the first match cuts the word short
+        lexx.set_match_strategy(MatchStrategy::FirstMatch);
+        lexx.set_input(Box::new(InputString::new(String::from("foxes"))));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "fox"));
+        assert_eq!(lexx.running_matcher_count(), 1);
+    }
+
+    /// A word matcher that pretends each char is a lot of work
+    #[derive(Debug)]
+    struct CostlyMatcher {
+        words: WordMatcher,
+    }
+
+    impl Matcher for CostlyMatcher {
+        fn reset(&mut self, ctx: &mut Box<Ctx>) {
+            self.words.reset(ctx);
+        }
+
+        fn find_match(
+            &mut self,
+            oc: Option<char>,
+            value: &[char],
+            ctx: &mut Box<Ctx>,
+        ) -> MatcherResult {
+            self.words.find_match(oc, value, ctx)
+        }
+
+        fn is_running(&self) -> bool {
+            <WordMatcher as Matcher>::is_running(&self.words)
+        }
+
+        fn precedence(&self) -> u8 {
+            <WordMatcher as Matcher>::precedence(&self.words)
+        }
+
+        fn step_cost(&self) -> u32 {
+            100
+        }
+    }
+
+    #[test]
+    fn lexx_test_step_budget() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("12 abcdef 34"))),
+            vec![
+                Box::new(IntegerMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    radix_prefixes: false,
+                }),
+                Box::new(WhitespaceMatcher::with_predicate(char::is_whitespace, 0)),
+                Box::new(CostlyMatcher {
+                    words: WordMatcher {
+                        index: 0,
+                        precedence: 0,
+                        running: true,
+                        trailing: &[],
+                    },
+                }),
+            ],
+        );
+        lexx.set_step_budget(Some(350));
+
+        // the costly matcher gives up on the first char of the integer
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "12"));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == " "));
+        assert!(
+            matches!(lexx.next_token(), Err(LexxError::Error(e)) if e == "matching at 1, 4 took 402 steps, the budget is 350")
+        );
+        // nothing was lost, the same chars are tried again
+        assert!(matches!(lexx.next_token(), Err(LexxError::Error(_))));
+        lexx.set_step_budget(None);
+        assert!(
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "abcdef" && t.column == 4 && t.start_byte == 3)
+        );
+        lexx.set_step_budget(Some(350));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == " "));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "34"));
+    }
+
+    #[test]
+    fn lexx_test_precedence() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("fox"))),
+            vec![
+                Box::new(ExactMatcher::build_exact_matcher(
+                    vec!["fox"],
+                    TOKEN_TYPE_EXACT,
+                    1,
+                )),
+                Box::new(WordMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    trailing: &[],
+                }),
+            ],
+        );
+
+        assert!(
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "fox" && t.token_type == TOKEN_TYPE_EXACT)
+        );
+
+        lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("fox"))),
+            vec![
+                Box::new(WordMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    trailing: &[],
+                }),
+                Box::new(ExactMatcher::build_exact_matcher(
+                    vec!["fox"],
+                    TOKEN_TYPE_EXACT,
+                    1,
+                )),
+            ],
+        );
+
+        assert!(
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "fox" && t.token_type == TOKEN_TYPE_EXACT)
+        );
+    }
+
+    #[test]
+    fn lexx_test_token_precedence_override() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("return value"))),
+            vec![
+                Box::new(ContextualKeywordMatcher {
+                    index: 0,
+                    running: true,
+                }),
+                Box::new(WhitespaceMatcher {
+                    index: 0,
+                    column: 0,
+                    line: 0,
+                    precedence: 0,
+                    running: true,
+                    predicate: char::is_whitespace,
+                    line_continuation: None,
+                    mixed_indent: false,
+                }),
+                Box::new(WordMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    trailing: &[],
+                }),
+            ],
+        );
+
+        assert!(
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "return" && t.token_type == TOKEN_TYPE_KEYWORD && t.precedence == 1)
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_WHITESPACE));
+        // without the boost the WordMatcher wins the tie as it comes later
+        assert!(
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "value" && t.token_type == TOKEN_TYPE_WORD && t.precedence == 0)
+        );
+        assert!(matches!(lexx.next_token(), Ok(None)));
+    }
+
+    #[test]
+    fn lexx_test_spawn_into() {
+        let (tx, rx) = channel();
+        let handle = spawn_into(
+            || -> Box<dyn Lexxer> {
+                Box::new(Lexx::<512>::new(
+                    Box::new(InputString::new(String::from("The lazy dog"))),
+                    vec![Box::new(WordMatcher {
+                        index: 0,
+                        precedence: 0,
+                        running: true,
+                        trailing: &[],
+                    })],
+                ))
+            },
+            tx,
+        );
+
+        let results: Vec<Result<Token, LexxError>> = rx.iter().collect();
+        handle.join().unwrap();
+
+        // the space can't be matched so the error is sent and the lexer stops
+        assert_eq!(results.len(), 2);
+        assert!(matches!(&results[0], Ok(t) if t.value == "The" && t.token_type == TOKEN_TYPE_WORD));
+        assert!(matches!(&results[1], Err(LexxError::TokenNotFound(_))));
+    }
+
+    #[test]
+    fn lexx_test_prev_gap() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("a   b c"))),
+            vec![
+                Box::new(WhitespaceMatcher {
+                    index: 0,
+                    column: 0,
+                    line: 0,
+                    precedence: 0,
+                    running: true,
+                    predicate: char::is_whitespace,
+                    line_continuation: None,
+                    mixed_indent: false,
+                }),
+                Box::new(WordMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    trailing: &[],
+                }),
+            ],
+        );
+
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "a" && t.prev_gap == 0));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_WHITESPACE && t.prev_gap == 0));
+        assert!(
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "b" && t.column == 5 && t.prev_gap == 3)
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_WHITESPACE));
+        assert!(
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "c" && t.column == 7 && t.prev_gap == 1)
+        );
+    }
+
+    #[test]
+    fn lexx_test_stateless_matchers_reset_light() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("abc123+-def"))),
+            vec![
+                Box::new(WordMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    trailing: &[],
+                }),
+                Box::new(IntegerMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    radix_prefixes: false,
+                }),
+                Box::new(SymbolMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    max_len: 0,
+                }),
+            ],
+        );
+
+        assert!(lexx.matchers.iter().all(|m| !m.is_stateful()));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "abc" && t.token_type == TOKEN_TYPE_WORD));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "123" && t.token_type == TOKEN_TYPE_INTEGER));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "+-" && t.token_type == TOKEN_TYPE_SYMBOL));
+        assert!(
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "def" && t.token_type == TOKEN_TYPE_WORD && t.column == 9)
+        );
+        assert!(matches!(lexx.next_token(), Ok(None)));
+    }
+
+    #[test]
+    fn lexx_test_matcher_label() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("fox 12"))),
+            vec![Box::new(WhitespaceMatcher {
+                index: 0,
+                column: 0,
+                line: 0,
+                precedence: 0,
+                running: true,
+                predicate: char::is_whitespace,
+                line_continuation: None,
+                mixed_indent: false,
+            })],
+        );
+        lexx.push_matcher(
+            Box::new(WordMatcher {
+                index: 0,
+                precedence: 0,
+                running: true,
+                trailing: &[],
+            }),
+            Some("ident"),
+        );
+        lexx.push_matcher(
+            Box::new(IntegerMatcher {
+                index: 0,
+                precedence: 0,
+                running: true,
+                radix_prefixes: false,
+            }),
+            None,
+        );
+
+        assert!(
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "fox" && t.label == Some("ident"))
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_WHITESPACE && t.label.is_none()));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "12" && t.label.is_none()));
+    }
+
+    #[test]
+    fn lexx_test_windows3() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("a b."))),
+            vec![
+                Box::new(WhitespaceMatcher {
+                    index: 0,
+                    column: 0,
+                    line: 0,
+                    precedence: 0,
+                    running: true,
+                    predicate: char::is_whitespace,
+                    line_continuation: None,
+                    mixed_indent: false,
+                }),
+                Box::new(WordMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    trailing: &[],
+                }),
+                Box::new(SymbolMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    max_len: 0,
+                }),
+            ],
+        );
+
+        let windows: Vec<(Option<Token>, Token, Option<Token>)> = lexx.windows3().collect();
+        let values: Vec<(Option<String>, String, Option<String>)> = windows
+            .into_iter()
+            .map(|(p, c, n)| (p.map(|t| t.value), c.value, n.map(|t| t.value)))
+            .collect();
+
+        assert_eq!(
+            values,
+            vec![
+                (None, "a".to_string(), Some(" ".to_string())),
+                (Some("a".to_string()), " ".to_string(), Some("b".to_string())),
+                (Some(" ".to_string()), "b".to_string(), Some(".".to_string())),
+                (Some("b".to_string()), ".".to_string(), None),
+            ]
+        );
+        assert!(matches!(lexx.next_token(), Ok(None)));
+    }
+
+    #[test]
+    fn lexx_test_new_checked() {
+        let lexx = Lexx::<512>::new_checked(
+            Box::new(InputString::new(String::from("fox"))),
+            vec![
+                Box::new(WordMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    trailing: &[],
+                }),
+                Box::new(ExactMatcher::build_exact_matcher(
+                    vec!["fox"],
+                    TOKEN_TYPE_EXACT,
+                    1,
+                )),
+            ],
+        );
+        let mut lexx = lexx.unwrap();
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "fox" && t.token_type == TOKEN_TYPE_EXACT));
+
+        let lexx = Lexx::<512>::new_checked(
+            Box::new(InputString::new(String::from("fox"))),
+            vec![
+                Box::new(WordMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    trailing: &[],
+                }),
+                Box::new(ExactMatcher::build_exact_matcher(vec![], TOKEN_TYPE_EXACT, 1)),
+            ],
+        );
+        assert!(
+            matches!(lexx, Err(LexxError::Error(e)) if e == "matcher 1 is invalid: ExactMatcher has no targets")
+        );
+
+        let lexx = Lexx::<512>::new_checked(
+            Box::new(InputString::new(String::from("fox"))),
+            vec![Box::new(ExactMatcher::build_exact_matcher(
+                vec!["fox", ""],
+                TOKEN_TYPE_EXACT,
+                1,
+            ))],
+        );
+        assert!(
+            matches!(lexx, Err(LexxError::Error(e)) if e == "matcher 0 is invalid: ExactMatcher has an empty target")
+        );
+    }
+
+    #[test]
+    fn lexx_test_look_ahead() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("The lazy dog"))),
+            vec![
+                Box::new(WhitespaceMatcher {
+                    index: 0,
+                    column: 0,
+                    line: 0,
+                    precedence: 0,
+                    running: true,
+                    predicate: char::is_whitespace,
+                    line_continuation: None,
+                    mixed_indent: false,
+                }),
+                Box::new(WordMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    trailing: &[],
+                }),
+            ],
+        );
+
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "The"));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_WHITESPACE));
+        assert!(
+            matches!(lexx.look_ahead(), Ok(Some(t)) if t.value == "lazy" && t.line == 1 && t.column == 5 && t.len == 4)
+        );
+        assert!(
+            matches!(lexx.look_ahead(), Ok(Some(t)) if t.value == "lazy" && t.line == 1 && t.column == 5 && t.len == 4)
+        );
+        assert!(
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "lazy" && t.line == 1 && t.column == 5 && t.len == 4)
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_WHITESPACE));
+        assert!(
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "dog" && t.line == 1 && t.column == 10 && t.len == 3)
+        );
+    }
+
+    #[test]
+    fn lexx_test_rewind() {
+        let mut lexx = Lexx::<20>::new(
+            Box::new(InputString::new(String::from("The lazy dog"))),
+            vec![
+                Box::new(WhitespaceMatcher {
+                    index: 0,
+                    column: 0,
+                    line: 0,
+                    precedence: 0,
+                    running: true,
+                    predicate: char::is_whitespace,
+                    line_continuation: None,
+                    mixed_indent: false,
+                }),
+                Box::new(WordMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    trailing: &[],
+                }),
+            ],
+        );
+
+        let mut the: Option<Token> = None;
+        if let Ok(t) = lexx.next_token() {
+            assert_eq!(t.as_ref().unwrap().value, "The");
+            the = t;
+        }
+        let mut whitespace: Option<Token> = None;
+        if let Ok(t) = lexx.next_token() {
+            assert_eq!(t.as_ref().unwrap().token_type, TOKEN_TYPE_WHITESPACE);
+            whitespace = t;
+        }
+        let mut lazy: Option<Token> = None;
+        if let Ok(t) = lexx.next_token() {
+            assert_eq!(t.as_ref().unwrap().value, "lazy");
+            lazy = t;
+        }
+        if let Some(t) = lazy {
+            assert_eq!(lexx.rewind(t), Ok(15))
+        }
+        if let Some(t) = whitespace {
+            assert_eq!(lexx.rewind(t), Ok(14))
+        }
+        if let Some(t) = the {
+            assert_eq!(lexx.rewind(t), Ok(11))
+        }
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "The"));
+        let w = lexx.next_token();
+        assert!(matches!(w, Ok(Some(t)) if t.token_type == TOKEN_TYPE_WHITESPACE));
+        assert!(
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "lazy" && t.line == 1 && t.column == 5 && t.len == 4)
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_WHITESPACE));
+        assert!(
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "dog" && t.line == 1 && t.column == 10 && t.len == 3)
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn lexx_test_last_competition() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("fox foxes"))),
+            vec![
+                Box::new(WordMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    trailing: &[],
+                }),
+                Box::new(WhitespaceMatcher {
+                    index: 0,
+                    column: 0,
+                    line: 0,
+                    precedence: 0,
+                    running: true,
+                    predicate: char::is_whitespace,
+                    line_continuation: None,
+                    mixed_indent: false,
+                }),
+                Box::new(ExactMatcher::build_exact_matcher(
+                    vec!["fox"],
+                    TOKEN_TYPE_EXACT,
+                    1,
+                )),
+            ],
+        );
+
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "fox" && t.token_type == TOKEN_TYPE_EXACT));
+        assert_eq!(
+            lexx.last_competition(),
+            vec![(TOKEN_TYPE_WORD, 3, 0), (TOKEN_TYPE_EXACT, 3, 1)]
+        );
+        lexx.next_token().unwrap();
+        assert_eq!(lexx.last_competition(), vec![(TOKEN_TYPE_WHITESPACE, 1, 0)]);
+        // the longer word loses to the higher precedence
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "fox" && t.token_type == TOKEN_TYPE_EXACT));
+        assert_eq!(
+            lexx.last_competition(),
+            vec![(TOKEN_TYPE_EXACT, 3, 1), (TOKEN_TYPE_WORD, 5, 0)]
+        );
+    }
+
+    #[test]
+    #[cfg(not(debug_assertions))]
+    fn lexx_test_last_competition_release() {
+        let mut lexx = Lexx::<512>::from_spec(
+            "word,whitespace",
+            Box::new(InputString::new(String::from("fox"))),
+        )
+        .unwrap();
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "fox"));
+        // nothing is recorded in a release build
+        assert_eq!(lexx.last_competition(), vec![]);
+    }
+
+    #[test]
+    fn lexx_test_next_token_with() {
+        let text = "The quick\nbrown fox";
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from(text))),
+            vec![
+                Box::new(WordMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    trailing: &[],
+                }),
+                Box::new(WhitespaceMatcher {
+                    index: 0,
+                    column: 0,
+                    line: 0,
+                    precedence: 0,
+                    running: true,
+                    predicate: char::is_whitespace,
+                    line_continuation: None,
+                    mixed_indent: false,
+                }),
+            ],
+        );
+
+        let checksum = |chars: &[char]| chars.iter().map(|c| *c as u32).sum::<u32>();
+        let mut total = 0;
+        let mut tokens = 0;
+        // mixing in look_ahead must not change which chars are seen
+        assert!(matches!(lexx.look_ahead(), Ok(Some(t)) if t.value == "The"));
+        while let Some(sum) = lexx.next_token_with(checksum).unwrap() {
+            total += sum;
+            tokens += 1;
+            if tokens == 2 {
+                lexx.look_ahead().unwrap();
+            }
+        }
+        assert_eq!(tokens, 7);
+        assert_eq!(total, text.chars().map(|c| c as u32).sum::<u32>());
+
+        // trivia is skipped like next_token does and nothing is added to the Token
+        let normalized = Rc::new(Cell::new(0));
+        let seen = normalized.clone();
+        lexx.set_input(Box::new(InputString::new(String::from(" The fox "))));
+        lexx.set_attach_trivia(&[TOKEN_TYPE_WHITESPACE]);
+        lexx.set_normalize(Box::new(move |v| {
+            seen.set(seen.get() + 1);
+            v.to_lowercase()
+        }));
+        let mut words: Vec<String> = vec![];
+        while let Some(word) = lexx.next_token_with(|chars| chars.iter().collect()).unwrap() {
+            words.push(word);
+        }
+        assert_eq!(words, vec!["The", "fox"]);
+        assert_eq!(normalized.get(), 0);
+    }
+
+    #[test]
+    fn lexx_test_on_match_veto() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("THE quick FOX"))),
+            vec![
+                Box::new(NoShoutingMatcher {
+                    words: WordMatcher {
+                        index: 0,
+                        precedence: 1,
+                        running: true,
+                        trailing: &[],
+                    },
+                }),
+                Box::new(WhitespaceMatcher {
+                    index: 0,
+                    column: 0,
+                    line: 0,
+                    precedence: 0,
+                    running: true,
+                    predicate: char::is_whitespace,
+                    line_continuation: None,
+                    mixed_indent: false,
+                }),
+                // would always lose to the word matcher's higher precedence, if not for the veto
+                Box::new(ExactMatcher::build_exact_matcher(
+                    vec!["THE", "FOX", "quick"],
+                    TOKEN_TYPE_EXACT,
+                    0,
+                )),
+            ],
+        );
+
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "THE" && t.token_type == TOKEN_TYPE_EXACT));
+        lexx.next_token().unwrap();
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "quick" && t.token_type == TOKEN_TYPE_WORD));
+        lexx.next_token().unwrap();
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "FOX" && t.token_type == TOKEN_TYPE_EXACT && t.column == 11));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+
+        // with nothing to fall back on a vetoed word isn't matched at all
+        lexx.set_input(Box::new(InputString::new(String::from("LOUD "))));
+        assert!(matches!(lexx.next_token(), Err(LexxError::TokenNotFound(_))));
+    }
+
+    #[test]
+    fn lexx_test_attach_trivia() {
+        let src = "let  x =\n\t42;";
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from(src))),
+            vec![
+                Box::new(WordMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    trailing: &[],
+                }),
+                Box::new(WhitespaceMatcher {
+                    index: 0,
+                    column: 0,
+                    line: 0,
+                    precedence: 0,
+                    running: true,
+                    predicate: char::is_whitespace,
+                    line_continuation: None,
+                    mixed_indent: false,
+                }),
+                Box::new(IntegerMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    radix_prefixes: false,
+                }),
+                Box::new(SymbolMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    max_len: 0,
+                }),
+            ],
+        );
+        lexx.set_attach_trivia(&[TOKEN_TYPE_WHITESPACE]);
+
+        let tokens: Vec<Token> = lexx.by_ref().collect();
+        assert!(tokens.iter().all(|t| t.token_type != TOKEN_TYPE_WHITESPACE));
+        assert_eq!(tokens.len(), 5);
+        assert!(matches!(&tokens[1], t if t.value == "x" && t.leading_trivia == "  " && t.column == 6));
+        assert!(matches!(&tokens[3], t if t.value == "42" && t.leading_trivia == "\n\t" && t.line == 2));
+        // nothing is lost
+        let rebuilt: String = tokens
+            .iter()
+            .map(|t| format!("{}{}", t.leading_trivia, t.value))
+            .collect();
+        assert_eq!(rebuilt, src);
+
+        lexx.set_attach_trivia(&[]);
+        lexx.set_input(Box::new(InputString::new(String::from("a b"))));
+        assert_eq!(lexx.count(), 3);
+    }
+
+    #[test]
+    fn lexx_test_next_with_gap() {
+        use crate::matcher_prefixed::PrefixedMatcher;
+
+        let comment = PrefixedMatcher::build_prefixed_matcher(
+            '/',
+            Box::new(PrefixedMatcher::build_prefixed_matcher(
+                '/',
+                Box::new(WhitespaceMatcher::with_predicate(|c| c != '\n', 0)),
+                20,
+                0,
+            )),
+            20,
+            1,
+        );
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("a  // c\nb+c"))),
+            vec![
+                Box::new(WordMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    trailing: &[],
+                }),
+                Box::new(WhitespaceMatcher::with_predicate(char::is_whitespace, 0)),
+                Box::new(SymbolMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    max_len: 0,
+                }),
+                Box::new(comment),
+            ],
+        );
+        lexx.set_attach_trivia(&[TOKEN_TYPE_WHITESPACE, 20]);
+
+        assert!(matches!(lexx.next_with_gap(), Ok(Some((g, t))) if g.is_empty() && t.value == "a"));
+        assert!(
+            matches!(lexx.next_with_gap(), Ok(Some((g, t))) if g == "  // c\n" && t.value == "b" && t.line == 2 && t.leading_trivia.is_empty())
+        );
+        assert!(matches!(lexx.next_with_gap(), Ok(Some((g, t))) if g.is_empty() && t.value == "+"));
+        assert!(matches!(lexx.next_with_gap(), Ok(Some((g, t))) if g.is_empty() && t.value == "c"));
+        assert!(matches!(lexx.next_with_gap(), Ok(None)));
+    }
+
+    #[test]
+    fn lexx_test_normalize() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("FOO Bar"))),
+            vec![
+                Box::new(WordMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    trailing: &[],
+                }),
+                Box::new(WhitespaceMatcher {
+                    index: 0,
+                    column: 0,
+                    line: 0,
+                    precedence: 0,
+                    running: true,
+                    predicate: char::is_whitespace,
+                    line_continuation: None,
+                    mixed_indent: false,
+                }),
+            ],
+        );
+
+        assert!(matches!(lexx.look_ahead(), Ok(Some(t)) if t.value == "FOO" && t.normalized.is_none()));
+        lexx.set_normalize(Box::new(|v| v.to_lowercase()));
+        // the looked ahead token was found before there was a normalizer
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.normalized.is_none()));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == " " && t.normalized == Some(String::from(" "))));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "Bar" && t.normalized == Some(String::from("bar"))));
+
+        lexx.set_input(Box::new(InputString::new(String::from("FOO"))));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "FOO" && t.normalized == Some(String::from("foo"))));
+    }
+
+    #[test]
+    fn lexx_test_describe_config() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from(""))),
+            vec![
+                Box::new(IntegerMatcher::default()),
+                Box::new(CostlyMatcher {
+                    words: WordMatcher::with_trailing(&['?'], 2),
+                }),
+            ],
+        );
+        lexx.push_matcher(
+            Box::new(SymbolMatcher {
+                index: 0,
+                precedence: 1,
+                running: true,
+                max_len: 1,
+            }),
+            Some("punct"),
+        );
+        lexx.set_active(&[0, 2]);
+
+        let config = lexx.describe_config();
+        let lines: Vec<&str> = config.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "0: IntegerMatcher { precedence: 0, radix_prefixes: false }",
+                // matchers outside the crate get the default, just the name
+                "1: CostlyMatcher inactive",
+                "2: SymbolMatcher { precedence: 1, max_len: 1 } label \"punct\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn lexx_test_adjacency_rule() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("abc12 456abc x9"))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(IntegerMatcher::default()),
+                Box::new(ExactMatcher::build_exact_matcher(
+                    vec!["12"],
+                    TOKEN_TYPE_EXACT,
+                    1,
+                )),
+            ],
+        );
+        lexx.set_adjacency_rule(Box::new(|t, c| {
+            !(t.value.chars().all(|d| d.is_ascii_digit()) && c.is_alphabetic())
+        }));
+
+        // letters then digits are fine
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "abc"));
+        // the exact match ends without reading past it, the next char is still checked
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "12" && t.token_type == TOKEN_TYPE_EXACT));
+        lexx.next_token().unwrap();
+        assert!(
+            matches!(lexx.next_token(), Err(LexxError::Error(e)) if e == "\"456\" can't be followed by 'a' at 1, 10")
+        );
+        // lexing goes on after the error
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "abc" && t.column == 10));
+        lexx.next_token().unwrap();
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "x"));
+        // the last token has nothing after it
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "9"));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+    }
+
+    #[test]
+    fn lexx_test_map_tokens() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("up 42 and! away"))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(IntegerMatcher::default()),
+            ],
+        );
+
+        let mut seen = 0;
+        let results: Vec<Result<Token, LexxError>> = lexx
+            .map_tokens(|mut t| {
+                if t.token_type == TOKEN_TYPE_WORD {
+                    t.value = t.value.to_uppercase();
+                }
+                t
+            })
+            .inspect(|_| seen += 1)
+            .collect();
+        // mapped as they are lexed, the error stops it
+        assert_eq!(seen, 6);
+        let values: Vec<&str> = results[0..5]
+            .iter()
+            .map(|r| r.as_ref().unwrap().value.as_str())
+            .collect();
+        assert_eq!(values, vec!["UP", " ", "42", " ", "AND"]);
+        assert!(matches!(&results[5], Err(LexxError::TokenNotFound(_))));
+
+        // works on a boxed Lexxer too, and the Lexx is still usable afterwards
+        lexx.set_input(Box::new(InputString::new(String::from("a b"))));
+        let mut boxed: Box<dyn Lexxer> = Box::new(lexx);
+        let lens: Vec<usize> = boxed
+            .map_tokens(|mut t| {
+                t.len *= 10;
+                t
+            })
+            .map(|r| r.unwrap().len)
+            .collect();
+        assert_eq!(lens, vec![10, 10, 10]);
+        assert!(matches!(boxed.next_token(), Ok(None)));
+    }
+
+    #[test]
+    fn lexx_test_token_at_cap() {
+        let make = |text: &str| {
+            Lexx::<8>::new(
+                Box::new(InputString::new(String::from(text))),
+                vec![
+                    Box::new(WordMatcher::default()),
+                    Box::new(WhitespaceMatcher::default()),
+                ],
+            )
+        };
+
+        // one under CAP and exactly CAP, followed by something and at the end of the input
+        let mut lexx = make("abcdefg abcdefgh abcdefgh");
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "abcdefg"));
+        lexx.next_token().unwrap();
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "abcdefgh" && t.len == 8));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == " " && t.column == 17));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "abcdefgh" && t.end_byte == 25));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+
+        // one over CAP, followed by something and at the end of the input
+        for text in ["abcdefghi ", "abcdefghi"] {
+            let mut lexx = make(text);
+            assert_eq!(lexx.next_token(), Err(LexxError::BufferOverflow(1)));
+        }
+
+        // the char after a CAP + 1 token isn't lost
+        let mut lexx = Lexx::<4>::from_spec(
+            "word,whitespace,symbol",
+            Box::new(InputString::new(String::from("abcde+x"))),
+        )
+        .unwrap();
+        assert_eq!(lexx.next_token(), Err(LexxError::BufferOverflow(1)));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "+" && t.column == 6 && t.start_byte == 5));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "x" && t.column == 7));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+
+        let mut lexx = Lexx::<4>::from_spec(
+            "word,whitespace,symbol",
+            Box::new(InputString::new(String::from("     x"))),
+        )
+        .unwrap();
+        assert_eq!(lexx.next_token(), Err(LexxError::BufferOverflow(1)));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "x" && t.column == 6));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+
+        // a longer token is dropped whole, none of it comes back
+        let mut lexx = Lexx::<4>::from_spec(
+            "word,whitespace,symbol",
+            Box::new(InputString::new(String::from("abcdefgh x"))),
+        )
+        .unwrap();
+        assert_eq!(lexx.next_token(), Err(LexxError::BufferOverflow(1)));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == " " && t.column == 9 && t.start_byte == 8));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "x" && t.column == 10));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+
+        let mut lexx = make("abcdefghijklm no");
+        assert_eq!(lexx.next_token(), Err(LexxError::BufferOverflow(1)));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == " " && t.column == 14));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "no" && t.column == 15));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+    }
+
+    #[test]
+    fn lexx_test_buffer_overflow_shorter_match() {
+        use crate::matcher_block_comment::BlockCommentMatcher;
+        use crate::token::TOKEN_TYPE_COMMENT;
+
+        let mut lexx = Lexx::<8>::new(
+            Box::new(InputString::new(String::from(
+                "a /* unterminated long comment",
+            ))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(SymbolMatcher::default()),
+                Box::new(BlockCommentMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    open: vec!['/', '*'],
+                    close: vec!['*', '/'],
+                    include_prefix: true,
+                    nested: false,
+                    depth: 0,
+                    max_depth: None,
+                    last: 0,
+                    token_type: TOKEN_TYPE_COMMENT,
+                }),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "a"));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == " "));
+        // the comment can't fit, so the symbol that matched before it ran out is used
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "/*" && t.column == 3));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == " " && t.column == 5));
+        assert_eq!(lexx.next_token(), Err(LexxError::BufferOverflow(1)));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == " " && t.column == 18));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "long" && t.column == 19));
+    }
+
+    #[test]
+    fn lexx_test_buffer_overflow_message() {
+        let text = format!("ok\n  {}", "x".repeat(100));
+        let mut lexx = Lexx::<64>::new(
+            Box::new(InputString::new(text)),
+            vec![
+                Box::new(WordMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    trailing: &[],
+                }),
+                Box::new(WhitespaceMatcher {
+                    index: 0,
+                    column: 0,
+                    line: 0,
+                    precedence: 0,
+                    running: true,
+                    predicate: char::is_whitespace,
+                    line_continuation: None,
+                    mixed_indent: false,
+                }),
+            ],
+        );
+        lexx.next_token().unwrap();
+        lexx.next_token().unwrap();
+
+        let e = lexx.next_token().unwrap_err();
+        assert_eq!(e, LexxError::BufferOverflow(2));
+        assert_eq!(e.to_string(), "ran out of buffer space for a token at line 2");
+
+        // all of it is dropped and lexing goes on after it
+        assert!(matches!(lexx.next_token(), Ok(None)));
+    }
+
+    #[test]
+    fn lexx_test_relex() {
+        use crate::matcher_exact::ExactMatcher;
+        use crate::token::TOKEN_TYPE_EXACT;
+
+        let tokens: Vec<Token> = Lexx::<512>::from_spec(
+            "word,whitespace,symbol",
+            Box::new(InputString::new(String::from("a <= b\nc"))),
+        )
+        .unwrap()
+        .collect();
+        assert_eq!(tokens.len(), 7);
+
+        let relexed = Lexx::<512>::relex(
+            &tokens[2..],
+            vec![
+                Box::new(ExactMatcher::build_exact_matcher(vec!["<= b"], TOKEN_TYPE_EXACT, 0)),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(WordMatcher::default()),
+            ],
+        )
+        .unwrap();
+        let values: Vec<(&str, usize, usize)> = relexed
+            .iter()
+            .map(|t| (t.value.as_str(), t.line, t.column))
+            .collect();
+        // the positions are in "<= b\nc", not the original input
+        assert_eq!(values, vec![("<= b", 1, 1), ("\n", 1, 5), ("c", 2, 1)]);
+        assert_eq!(relexed[0].token_type, TOKEN_TYPE_EXACT);
+
+        assert!(matches!(
+            Lexx::<512>::relex(&tokens, vec![Box::new(WordMatcher::default())]),
+            Err(LexxError::TokenNotFound(_))
+        ));
+        assert_eq!(Lexx::<512>::relex(&[], vec![Box::new(WordMatcher::default())]), Ok(vec![]));
+    }
+
+    #[test]
+    fn lexx_test_take_input() {
+        let make_lexx = || {
+            Lexx::<512>::new(
+                Box::new(InputString::new(String::from("The quick fox"))),
+                vec![
+                    Box::new(WordMatcher::default()),
+                    Box::new(WhitespaceMatcher::default()),
+                ],
+            )
+        };
+
+        let mut lexx = make_lexx();
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "The"));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == " "));
+        let mut input = lexx.take_input();
+        // the 'q' that ended the whitespace was read and is lost with the cache
+        assert_eq!(input.next(), Ok(Some('u')));
+        assert_eq!(input.next(), Ok(Some('i')));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+
+        // the input can be given back to carry on
+        lexx.set_input(input);
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "ck" && t.line == 1 && t.column == 1));
+
+        let mut lexx = make_lexx();
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "The"));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == " "));
+        let mut input = lexx.into_input();
+        assert_eq!(input.next(), Ok(Some('u')));
+    }
+
+    /// Matches a `"` and pops the mode it is in.
+    #[derive(Debug)]
+    struct CloseQuoteMatcher {
+        quote: ExactMatcher,
+    }
+
+    impl Matcher for CloseQuoteMatcher {
+        fn reset(&mut self, ctx: &mut Box<Ctx>) {
+            self.quote.reset(ctx);
+        }
+
+        fn find_match(
+            &mut self,
+            oc: Option<char>,
+            value: &[char],
+            ctx: &mut Box<Ctx>,
+        ) -> MatcherResult {
+            let result = self.quote.find_match(oc, value, ctx);
+            if let MatcherResult::Matched(_) = result {
+                ctx.insert(LEXX_POP_MODE.to_string(), 1);
+            }
+            result
+        }
+
+        fn is_running(&self) -> bool {
+            <ExactMatcher as Matcher>::is_running(&self.quote)
+        }
+
+        fn precedence(&self) -> u8 {
+            <ExactMatcher as Matcher>::precedence(&self.quote)
+        }
+    }
+
+    #[test]
+    fn lexx_test_mode_stack() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from(r#"if "if x" if"#))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(SymbolMatcher::with_max_len(1, 0)),
+                Box::new(ExactMatcher::build_exact_matcher(vec!["if"], TOKEN_TYPE_KEYWORD, 1)),
+            ],
+        );
+        let mut tokens = vec![];
+        while let Some(t) = lexx.next_token().unwrap() {
+            if t.value == "\"" && t.token_type == TOKEN_TYPE_SYMBOL {
+                // into string mode, where keywords are just words
+                lexx.push_mode(vec![
+                    Box::new(WordMatcher::default()),
+                    Box::new(WhitespaceMatcher::default()),
+                    Box::new(CloseQuoteMatcher {
+                        quote: ExactMatcher::build_exact_matcher(vec!["\""], TOKEN_TYPE_EXACT, 0),
+                    }),
+                ]);
+            }
+            tokens.push((t.value, t.token_type));
+        }
+        assert_eq!(
+            tokens,
+            vec![
+                (String::from("if"), TOKEN_TYPE_KEYWORD),
+                (String::from(" "), TOKEN_TYPE_WHITESPACE),
+                (String::from("\""), TOKEN_TYPE_SYMBOL),
+                (String::from("if"), TOKEN_TYPE_WORD),
+                (String::from(" "), TOKEN_TYPE_WHITESPACE),
+                (String::from("x"), TOKEN_TYPE_WORD),
+                (String::from("\""), TOKEN_TYPE_EXACT),
+                // the closing quote popped back to the first set
+                (String::from(" "), TOKEN_TYPE_WHITESPACE),
+                (String::from("if"), TOKEN_TYPE_KEYWORD),
+            ]
+        );
+        assert!(lexx.pop_mode().is_none());
+    }
+
+    #[test]
+    fn lexx_test_mode_stack_pop_loses() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from(r#""a""b" c"#))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(SymbolMatcher::with_max_len(1, 0)),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "\""));
+        lexx.push_mode(vec![
+            Box::new(WordMatcher::default()),
+            Box::new(WhitespaceMatcher::default()),
+            Box::new(CloseQuoteMatcher {
+                quote: ExactMatcher::build_exact_matcher(vec!["\""], TOKEN_TYPE_EXACT, 0),
+            }),
+            // an escaped quote, it is longer so it wins over the closing quote
+            Box::new(ExactMatcher::build_exact_matcher(
+                vec!["\"\""],
+                TOKEN_TYPE_SYMBOL,
+                0,
+            )),
+        ]);
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "a"));
+        // the closing quote matched first but lost, so its pop is dropped
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "\"\"" && t.token_type == TOKEN_TYPE_SYMBOL));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "b" && t.token_type == TOKEN_TYPE_WORD));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "\"" && t.token_type == TOKEN_TYPE_EXACT));
+        assert!(lexx.pop_mode().is_none());
+    }
+
+    #[test]
+    fn lexx_test_validate() {
+        let mut lexx = Lexx::<512>::from_spec(
+            "word,whitespace,integer",
+            Box::new(InputString::new(String::from("The 42 quick\n  brown\nfox"))),
+        )
+        .unwrap();
+        assert_eq!(lexx.validate(), Ok((9, 3, 4)));
+        // nothing is left after a validate
+        assert_eq!(lexx.validate(), Ok((0, 3, 4)));
+
+        let mut lexx = Lexx::<512>::from_spec(
+            "word,whitespace",
+            Box::new(InputString::new(String::from("The quick\n+fox"))),
+        )
+        .unwrap();
+        assert!(matches!(lexx.validate(), Err(LexxError::TokenNotFound(_))));
+    }
+
+    #[test]
+    fn lexx_test_position_saturates() {
         let mut lexx = Lexx::<512>::new(
-            Box::new(InputString::new(String::from("fox"))),
+            Box::new(InputString::new(String::from("ab cd\n\n\nx"))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+            ],
+        );
+        lexx.reset_position(usize::MAX - 1, usize::MAX - 2);
+
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "ab" && t.line == usize::MAX - 1 && t.column == usize::MAX - 2));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == " " && t.column == usize::MAX));
+        // the column stays at usize::MAX rather than wrapping around to 0
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "cd" && t.column == usize::MAX));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "\n\n\n" && t.line == usize::MAX - 1));
+        // three lines down from usize::MAX - 1 stops at usize::MAX
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "x" && t.line == usize::MAX && t.column == 1));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+    }
+
+    #[test]
+    fn lexx_test_reset_position() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("The lazy\ndog"))),
+            vec![
+                Box::new(WordMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    trailing: &[],
+                }),
+                Box::new(WhitespaceMatcher {
+                    index: 0,
+                    column: 0,
+                    line: 0,
+                    precedence: 0,
+                    running: true,
+                    predicate: char::is_whitespace,
+                    line_continuation: None,
+                    mixed_indent: false,
+                }),
+            ],
+        );
+
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "The" && t.line == 1 && t.column == 1));
+        lexx.reset_position(10, 5);
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == " " && t.line == 10 && t.column == 5 && t.start_byte == 3));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "lazy" && t.line == 10 && t.column == 6));
+        lexx.next_token().unwrap();
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "dog" && t.line == 11 && t.column == 1));
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            lexx.reset_position(0, 1)
+        }));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn lexx_test_checkpoint() {
+        let src = "let x = 12;\nlet y = x;";
+        let make = |input: Box<dyn LexxInput>| {
+            Lexx::<512>::new(
+                input,
+                vec![
+                    Box::new(WordMatcher::default()),
+                    Box::new(WhitespaceMatcher::default()),
+                    Box::new(IntegerMatcher::default()),
+                    Box::new(SymbolMatcher::default()),
+                ],
+            )
+        };
+        let mut lexx = make(Box::new(InputString::new(String::from(src))));
+
+        for _ in 0..6 {
+            lexx.next_token().unwrap();
+        }
+        // the integer matcher reads the ';' ahead, and a token is waiting from look_ahead
+        lexx.look_ahead().unwrap();
+        let checkpoint = lexx.checkpoint();
+        let first: Vec<Token> = lexx.by_ref().take(6).collect();
+        assert_eq!(first[0].value, "12");
+        assert_eq!(first[5].value, "y");
+
+        lexx.restore(checkpoint.clone()).unwrap();
+        let second: Vec<Token> = lexx.by_ref().take(6).collect();
+        assert_eq!(first, second);
+        assert_eq!(
+            (second[5].line, second[5].column, second[5].start_byte),
+            (2, 5, 16)
+        );
+
+        // a checkpoint can be used more than once
+        lexx.restore(checkpoint).unwrap();
+        let rest: Vec<Token> = lexx.by_ref().collect();
+        assert_eq!(rest[0..6], first[..]);
+        assert_eq!(rest.len(), 11);
+
+        // an input that can't go back
+        let mut lexx = make(Box::new(InputIter::new(src.chars())));
+        let checkpoint = lexx.checkpoint();
+        lexx.next_token().unwrap();
+        assert!(matches!(lexx.restore(checkpoint), Err(LexxError::Error(_))));
+    }
+
+    #[test]
+    fn lexx_test_default_unmatched_type() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("a%b\n12c+"))),
+            vec![Box::new(WordMatcher::default())],
+        );
+        lexx.set_default_unmatched_type(Some(TOKEN_TYPE_SYMBOL));
+
+        let tokens: Vec<(String, u16, usize, usize)> = lexx
+            .by_ref()
+            .map(|t| (t.value, t.token_type, t.line, t.column))
+            .collect();
+        let expected = [
+            ("a", TOKEN_TYPE_WORD, 1, 1),
+            ("%", TOKEN_TYPE_SYMBOL, 1, 2),
+            ("b", TOKEN_TYPE_WORD, 1, 3),
+            ("\n", TOKEN_TYPE_SYMBOL, 1, 4),
+            ("1", TOKEN_TYPE_SYMBOL, 2, 1),
+            ("2", TOKEN_TYPE_SYMBOL, 2, 2),
+            ("c", TOKEN_TYPE_WORD, 2, 3),
+            ("+", TOKEN_TYPE_SYMBOL, 2, 4),
+        ];
+        assert_eq!(tokens.len(), expected.len());
+        for (t, e) in tokens.iter().zip(expected.iter()) {
+            assert_eq!((t.0.as_str(), t.1, t.2, t.3), *e);
+        }
+
+        lexx.set_input(Box::new(InputString::new(String::from("a%b"))));
+        lexx.set_default_unmatched_type(None);
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "a"));
+        assert!(matches!(lexx.next_token(), Err(LexxError::TokenNotFound(_))));
+    }
+
+    #[test]
+    fn lexx_test_chain_input() {
+        let make = || {
+            Lexx::<512>::new(
+                Box::new(InputString::new(String::from("The quick\nbro"))),
+                vec![
+                    Box::new(WordMatcher {
+                        index: 0,
+                        precedence: 0,
+                        running: true,
+                        trailing: &[],
+                    }),
+                    Box::new(WhitespaceMatcher::with_predicate(char::is_whitespace, 0)),
+                ],
+            )
+        };
+
+        // carrying on, "bro" and "wn" are one word
+        let mut lexx = make();
+        lexx.chain_input(Box::new(InputString::new(String::from("wn fox"))), false);
+        lexx.chain_input(Box::new(InputString::new(String::from(""))), false);
+        lexx.chain_input(Box::new(InputString::new(String::from(" jumps"))), false);
+        let tokens: Vec<Token> = lexx.by_ref().collect();
+        let words: Vec<&str> = tokens.iter().map(|t| t.value.as_str()).collect();
+        assert_eq!(
+            words,
+            vec!["The", " ", "quick", "\n", "brown", " ", "fox", " ", "jumps"]
+        );
+        assert!(matches!(&tokens[4], t if t.line == 2 && t.column == 1 && t.start_byte == 10 && t.len == 5));
+        assert!(matches!(&tokens[8], t if t.line == 2 && t.column == 11 && t.start_byte == 20));
+
+        // restarting, the word is split and the positions start over
+        let mut lexx = make();
+        lexx.chain_input(Box::new(InputString::new(String::from("wn fox"))), true);
+        let tokens: Vec<Token> = lexx.by_ref().collect();
+        let words: Vec<&str> = tokens.iter().map(|t| t.value.as_str()).collect();
+        assert_eq!(words, vec!["The", " ", "quick", "\n", "bro", "wn", " ", "fox"]);
+        assert!(matches!(&tokens[4], t if t.line == 2 && t.column == 1 && t.start_byte == 10));
+        assert!(matches!(&tokens[5], t if t.line == 1 && t.column == 1 && t.start_byte == 0));
+        assert!(matches!(&tokens[7], t if t.line == 1 && t.column == 4 && t.start_byte == 3));
+
+        // set_input drops the chain
+        lexx.chain_input(Box::new(InputString::new(String::from(" more"))), false);
+        lexx.set_input(Box::new(InputString::new(String::from("a"))));
+        assert_eq!(lexx.count(), 1);
+    }
+
+    #[test]
+    fn lexx_test_skip_shebang() {
+        use crate::input::AppendableInput;
+
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from(
+                "#!/usr/bin/env foo\nlet x\n#! not a shebang",
+            ))),
             vec![
-                Box::new(ExactMatcher::build_exact_matcher(
-                    vec!["fox"],
-                    TOKEN_TYPE_EXACT,
-                    1,
-                )),
                 Box::new(WordMatcher {
                     index: 0,
                     precedence: 0,
                     running: true,
+                    trailing: &[],
+                }),
+                Box::new(WhitespaceMatcher::with_predicate(char::is_whitespace, 0)),
+                Box::new(SymbolMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    max_len: 0,
                 }),
             ],
         );
+        lexx.set_skip_shebang(true);
 
         assert!(
-            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "fox" && t.token_type == TOKEN_TYPE_EXACT)
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "let" && t.line == 2 && t.column == 1 && t.start_byte == 19)
         );
+        let rest: Vec<Token> = lexx.by_ref().collect();
+        assert!(rest.iter().any(|t| t.value == "#!" && t.line == 3));
 
-        lexx = Lexx::<512>::new(
-            Box::new(InputString::new(String::from("fox"))),
+        // without a shebang nothing is skipped
+        lexx.set_input(Box::new(InputString::new(String::from("#x"))));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "#" && t.column == 1));
+        lexx.set_input(Box::new(InputString::new(String::from("#"))));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "#"));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+
+        // a shebang that is the whole input
+        lexx.set_input(Box::new(InputString::new(String::from("#!/bin/sh"))));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+
+        // streaming input is waited on until the line is known
+        let input = AppendableInput::new();
+        lexx.set_input(Box::new(input.clone()));
+        input.push_str("#");
+        assert!(matches!(lexx.next_token(), Ok(None)));
+        input.push_str("!/bin/sh");
+        assert!(matches!(lexx.next_token(), Ok(None)));
+        input.push_str("\necho");
+        input.close();
+        assert!(
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "echo" && t.line == 2 && t.column == 1)
+        );
+    }
+
+    #[test]
+    fn lexx_test_rewind_too_large() {
+        use crate::rolling_char_buffer::RollingCharBufferError;
+
+        let mut lexx = Lexx::<16>::new(
+            Box::new(InputString::new(String::from("The lazy dog"))),
             vec![
                 Box::new(WordMatcher {
                     index: 0,
                     precedence: 0,
                     running: true,
+                    trailing: &[],
                 }),
-                Box::new(ExactMatcher::build_exact_matcher(
-                    vec!["fox"],
-                    TOKEN_TYPE_EXACT,
-                    1,
-                )),
+                Box::new(WhitespaceMatcher::with_predicate(char::is_whitespace, 0)),
             ],
         );
 
+        let the = lexx.next_token().unwrap().unwrap();
+        lexx.next_token().unwrap();
+        let mut huge = the.clone();
+        huge.value = "x".repeat(32);
+        huge.line = 7;
+        huge.column = 3;
+
+        assert_eq!(
+            lexx.rewind(huge),
+            Err(RollingCharBufferError::BufferFullError)
+        );
+        assert!(
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "lazy" && t.line == 1 && t.column == 5 && t.start_byte == 4)
+        );
+
+        // a rewind that fits still works
+        assert!(lexx.rewind(the).is_ok());
         assert!(
-            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "fox" && t.token_type == TOKEN_TYPE_EXACT)
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "The" && t.line == 1 && t.column == 1 && t.start_byte == 0)
         );
     }
 
     #[test]
-    fn lexx_test_look_ahead() {
-        let mut lexx = Lexx::<512>::new(
+    fn lexx_test_rewind_budget() {
+        let mut lexx = Lexx::<20>::new(
             Box::new(InputString::new(String::from("The lazy dog"))),
             vec![
                 Box::new(WhitespaceMatcher {
@@ -602,85 +4639,309 @@ mod tests {
                     line: 0,
                     precedence: 0,
                     running: true,
+                    predicate: char::is_whitespace,
+                    line_continuation: None,
+                    mixed_indent: false,
                 }),
                 Box::new(WordMatcher {
                     index: 0,
                     precedence: 0,
                     running: true,
+                    trailing: &[],
                 }),
             ],
         );
+        lexx.set_rewind_budget(Some(2));
 
-        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "The"));
-        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_WHITESPACE));
-        assert!(
-            matches!(lexx.look_ahead(), Ok(Some(t)) if t.value == "lazy" && t.line == 1 && t.column == 5 && t.len == 4)
-        );
-        assert!(
-            matches!(lexx.look_ahead(), Ok(Some(t)) if t.value == "lazy" && t.line == 1 && t.column == 5 && t.len == 4)
+        // rewinding the same token within the budget is fine
+        for _ in 0..2 {
+            let the = lexx.next_token().unwrap().unwrap();
+            assert_eq!(the.value, "The");
+            lexx.rewind(the).unwrap();
+        }
+        // reading past "The" is progress and starts the count over
+        lexx.next_token().unwrap();
+        let space = lexx.next_token().unwrap().unwrap();
+        lexx.rewind(space).unwrap();
+        let space = lexx.next_token().unwrap().unwrap();
+        lexx.rewind(space).unwrap();
+        let space = lexx.next_token().unwrap().unwrap();
+        lexx.rewind(space).unwrap();
+        assert!(matches!(lexx.next_token(), Err(LexxError::Error(_))));
+
+        // without a budget there is no limit
+        lexx.set_rewind_budget(None);
+        for _ in 0..10 {
+            let space = lexx.next_token().unwrap().unwrap();
+            lexx.rewind(space).unwrap();
+        }
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_WHITESPACE && t.column == 4));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "lazy"));
+    }
+
+    #[test]
+    fn lexx_test_line_column_never_zero() {
+        let mut lexx = Lexx::<64>::new(
+            Box::new(InputString::new(String::from(
+                "\n\nfn main() {\r\n\tlet x = 42;\n\n  \t y += x*2;\n}\n",
+            ))),
+            vec![
+                Box::new(WhitespaceMatcher {
+                    index: 0,
+                    column: 0,
+                    line: 0,
+                    precedence: 0,
+                    running: true,
+                    predicate: char::is_whitespace,
+                    line_continuation: None,
+                    mixed_indent: false,
+                }),
+                Box::new(WordMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    trailing: &[],
+                }),
+                Box::new(IntegerMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    radix_prefixes: false,
+                }),
+                Box::new(SymbolMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    max_len: 0,
+                }),
+                Box::new(ExactMatcher::build_exact_matcher(
+                    vec!["fn", "let", "+="],
+                    TOKEN_TYPE_EXACT,
+                    1,
+                )),
+            ],
         );
-        assert!(
-            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "lazy" && t.line == 1 && t.column == 5 && t.len == 4)
+
+        let mut count = 0;
+        while let Some(t) = lexx.next_token().unwrap() {
+            assert!(t.line >= 1, "{:?}", t);
+            assert!(t.column >= 1, "{:?}", t);
+            count += 1;
+        }
+        assert!(count > 20);
+    }
+
+    #[test]
+    fn lexx_test_set_active() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("count 42"))),
+            vec![
+                Box::new(IntegerMatcher {
+                    index: 0,
+                    precedence: 1,
+                    running: true,
+                    radix_prefixes: false,
+                }),
+                Box::new(WordMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    trailing: &[],
+                }),
+                Box::new(SymbolMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    max_len: 0,
+                }),
+                Box::new(WhitespaceMatcher {
+                    index: 0,
+                    column: 0,
+                    line: 0,
+                    precedence: 0,
+                    running: true,
+                    predicate: char::is_whitespace,
+                    line_continuation: None,
+                    mixed_indent: false,
+                }),
+            ],
         );
+
+        lexx.set_active(&[1, 3]);
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "count" && t.token_type == TOKEN_TYPE_WORD));
         assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_WHITESPACE));
-        assert!(
-            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "dog" && t.line == 1 && t.column == 10 && t.len == 3)
-        );
+        // the IntegerMatcher is inactive so nothing matches the digits
+        assert!(matches!(lexx.next_token(), Err(LexxError::TokenNotFound(_))));
+
+        lexx.set_active(&[0, 1, 2, 3]);
+        lexx.set_input(Box::new(InputString::new(String::from("next 7"))));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "next" && t.token_type == TOKEN_TYPE_WORD));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_WHITESPACE));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "7" && t.token_type == TOKEN_TYPE_INTEGER));
+        assert!(matches!(lexx.next_token(), Ok(None)));
     }
 
     #[test]
-    fn lexx_test_rewind() {
-        let mut lexx = Lexx::<20>::new(
-            Box::new(InputString::new(String::from("The lazy dog"))),
+    fn lexx_test_lookahead_buffered_len() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("The quick"))),
             vec![
+                Box::new(WordMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    trailing: &[],
+                }),
                 Box::new(WhitespaceMatcher {
                     index: 0,
                     column: 0,
                     line: 0,
                     precedence: 0,
                     running: true,
+                    predicate: char::is_whitespace,
+                    line_continuation: None,
+                    mixed_indent: false,
                 }),
+            ],
+        );
+
+        assert_eq!(lexx.lookahead_buffered_len(), 0);
+        // the WordMatcher had to read the ' ' to know "The" ended
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "The"));
+        assert_eq!(lexx.lookahead_buffered_len(), 1);
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_WHITESPACE));
+        assert_eq!(lexx.lookahead_buffered_len(), 1);
+
+        // "quick" runs to the end of input so nothing past it is read
+        let peeked = lexx.look_ahead().unwrap().unwrap();
+        assert_eq!(lexx.lookahead_buffered_len(), peeked.len);
+        assert_eq!(lexx.lookahead_buffered_len(), 5);
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "quick"));
+        assert_eq!(lexx.lookahead_buffered_len(), 0);
+    }
+
+    #[test]
+    fn lexx_test_find_ambiguities() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::new())),
+            vec![
+                Box::new(WhitespaceMatcher::with_predicate(char::is_whitespace, 0)),
                 Box::new(WordMatcher {
                     index: 0,
                     precedence: 0,
                     running: true,
+                    trailing: &[],
                 }),
+                Box::new(ExactMatcher::build_exact_matcher(vec!["+=", "-="], TOKEN_TYPE_EXACT, 1)),
+                Box::new(ExactMatcher::build_exact_matcher(vec!["+", "+="], TOKEN_TYPE_EXACT, 1)),
+                Box::new(ExactMatcher::build_exact_matcher(vec!["fn"], TOKEN_TYPE_EXACT, 1)),
             ],
         );
 
-        let mut the: Option<Token> = None;
-        if let Ok(t) = lexx.next_token() {
-            assert_eq!(t.as_ref().unwrap().value, "The");
-            the = t;
-        }
-        let mut whitespace: Option<Token> = None;
-        if let Ok(t) = lexx.next_token() {
-            assert_eq!(t.as_ref().unwrap().token_type, TOKEN_TYPE_WHITESPACE);
-            whitespace = t;
-        }
-        let mut lazy: Option<Token> = None;
-        if let Ok(t) = lexx.next_token() {
-            assert_eq!(t.as_ref().unwrap().value, "lazy");
-            lazy = t;
-        }
-        if let Some(t) = lazy {
-            assert_eq!(lexx.rewind(t), Ok(15))
-        }
-        if let Some(t) = whitespace {
-            assert_eq!(lexx.rewind(t), Ok(14))
-        }
-        if let Some(t) = the {
-            assert_eq!(lexx.rewind(t), Ok(11))
+        let ambiguities = lexx.find_ambiguities("fn x += y + z -= fn += w");
+        // "fn" is also a word, but at a lower precedence so it isn't ambiguous
+        assert_eq!(ambiguities, vec![(2, 3, String::from("+="))]);
+
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::new())),
+            vec![
+                Box::new(WhitespaceMatcher::with_predicate(char::is_whitespace, 0)),
+                Box::new(WordMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    trailing: &[],
+                }),
+            ],
+        );
+        assert!(lexx.find_ambiguities("no overlap here").is_empty());
+        // the lexx still works normally afterwards
+        lexx.set_input(Box::new(InputString::new(String::from("ok"))));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "ok" && t.token_type == TOKEN_TYPE_WORD));
+    }
+
+    #[test]
+    fn lexx_test_iter_results() {
+        fn count_words(lexx: &mut Lexx<512>) -> Result<usize, LexxError> {
+            let mut words = 0;
+            for result in lexx.iter_results() {
+                if result?.token_type == TOKEN_TYPE_WORD {
+                    words += 1;
+                }
+            }
+            Ok(words)
         }
-        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "The"));
-        let w = lexx.next_token();
-        assert!(matches!(w, Ok(Some(t)) if t.token_type == TOKEN_TYPE_WHITESPACE));
-        assert!(
-            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "lazy" && t.line == 1 && t.column == 5 && t.len == 4)
+
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("one two 3 four"))),
+            vec![
+                Box::new(WordMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    trailing: &[],
+                }),
+                Box::new(WhitespaceMatcher::with_predicate(char::is_whitespace, 0)),
+            ],
         );
-        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_WHITESPACE));
-        assert!(
-            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "dog" && t.line == 1 && t.column == 10 && t.len == 3)
+        assert!(matches!(count_words(&mut lexx), Err(LexxError::TokenNotFound(_))));
+
+        // only borrowed, so it can be reused
+        lexx.set_input(Box::new(InputString::new(String::from("one two four"))));
+        assert_eq!(count_words(&mut lexx), Ok(3));
+
+        lexx.set_input(Box::new(InputString::new(String::from("one 3 four"))));
+        let mut boxed: Box<dyn Lexxer> = Box::new(lexx);
+        let results: Vec<Result<Token, LexxError>> = boxed.iter_results().collect();
+        assert_eq!(results.len(), 3);
+        assert!(results[2].is_err());
+    }
+
+    #[test]
+    fn lexx_test_source_len() {
+        let text = "The ünïcode fox";
+        let lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+            Box::new(InputString::new(String::from(text))),
+            vec![Box::new(WordMatcher {
+                index: 0,
+                precedence: 0,
+                running: true,
+                trailing: &[],
+            })],
+        ));
+        assert_eq!(lexx.source_len(), Some(text.chars().count()));
+
+        let lexx = Lexx::<512>::new(
+            Box::new(InputIter::new(text.chars())),
+            vec![Box::new(WordMatcher {
+                index: 0,
+                precedence: 0,
+                running: true,
+                trailing: &[],
+            })],
+        );
+        assert_eq!(lexx.source_len(), None);
+    }
+
+    #[test]
+    fn lexx_test_from_spec() {
+        let mut lexx = Lexx::<512>::from_spec(
+            "word,whitespace",
+            Box::new(InputString::new(String::from("The quick fox"))),
+        )
+        .unwrap();
+
+        let tokens: Vec<Token> = lexx.iter_results().map(|r| r.unwrap()).collect();
+        assert_eq!(tokens.len(), 5);
+        assert_eq!(tokens[2].value, "quick");
+        assert_eq!(tokens[2].token_type, TOKEN_TYPE_WORD);
+        assert_eq!(tokens[3].token_type, TOKEN_TYPE_WHITESPACE);
+
+        assert_eq!(
+            Lexx::<512>::from_spec("word,,nope", Box::new(InputString::new(String::new())))
+                .unwrap_err(),
+            LexxError::Error("unknown matcher 'nope'".to_string())
         );
+        assert!(Lexx::<512>::from_spec(" , ", Box::new(InputString::new(String::new()))).is_err());
     }
 }
@@ -9,17 +9,16 @@
 ///
 /// ```rust
 ///
-/// use std::collections::HashMap;
-/// use lexx::matcher::Matcher;
+/// use lexx::matcher::{Ctx, Matcher};
 /// use lexx::matcher::MatcherResult::Failed;
 /// use lexx::matcher::MatcherResult::Running;
 /// use lexx::matcher::MatcherResult::Matched;
 /// use lexx::token::TOKEN_TYPE_WORD;
 /// use lexx::matcher_word::WordMatcher;
 ///
-/// let mut ctx: Box<HashMap<String, i32>> = Box::new(HashMap::new());
+/// let mut ctx: Box<Ctx> = Box::new(Ctx::default());
 ///
-/// let mut matcher_word: WordMatcher = WordMatcher{ index: 0, precedence: 0, running: true };
+/// let mut matcher_word: WordMatcher = WordMatcher{ index: 0, precedence: 0, running: true, trailing: &[] };
 ///
 /// assert!(matches!(matcher_word.find_match(Some('w'), &['w'], &mut ctx), Running()));
 /// assert!(matches!(matcher_word.find_match(Some('o'), &['w','o'], &mut ctx), Running()));
@@ -30,9 +29,21 @@
 /// ```
 
 use crate::token::Token;
+use std::collections::hash_map::RandomState;
 use std::collections::HashMap;
 use std::fmt::Debug;
 
+/// The general use map that [Lexx](crate::Lexx) shares between [Matcher]s for context, see
+/// [Lexx::ctx](crate::Lexx::ctx). It uses the default hasher unless the [Lexx](crate::Lexx) was
+/// made with [Lexx::with_hasher](crate::Lexx::with_hasher).
+pub type Ctx<S = RandomState> = HashMap<String, i32, S>;
+
+/// The faster, but not DoS resistant, `FxHasher` from `rustc-hash` for the `ctx` of a
+/// [Lexx](crate::Lexx) made with [Lexx::with_hasher](crate::Lexx::with_hasher). Only available
+/// with the `fast-hash` feature.
+#[cfg(feature = "fast-hash")]
+pub type FastHasher = rustc_hash::FxBuildHasher;
+
 /// The result of a match
 #[derive(Debug)]
 pub enum MatcherResult {
@@ -55,17 +66,16 @@ pub enum MatcherResult {
 ///
 /// ```rust
 ///
-/// use std::collections::HashMap;
-/// use lexx::matcher::Matcher;
+/// use lexx::matcher::{Ctx, Matcher};
 /// use lexx::matcher::MatcherResult::Failed;
 /// use lexx::matcher::MatcherResult::Running;
 /// use lexx::matcher::MatcherResult::Matched;
 /// use lexx::token::TOKEN_TYPE_WORD;
 /// use lexx::matcher_word::WordMatcher;
 ///
-/// let mut ctx: Box<HashMap<String, i32>> = Box::new(HashMap::new());
+/// let mut ctx: Box<Ctx> = Box::new(Ctx::default());
 ///
-/// let mut matcher_word: WordMatcher = WordMatcher{ index: 0, precedence: 0, running: true };
+/// let mut matcher_word: WordMatcher = WordMatcher{ index: 0, precedence: 0, running: true, trailing: &[] };
 ///
 /// assert!(matches!(matcher_word.find_match(Some('w'), &['w'], &mut ctx), Running()));
 /// assert!(matches!(matcher_word.find_match(Some('o'), &['w','o'], &mut ctx), Running()));
@@ -74,9 +84,24 @@ pub enum MatcherResult {
 /// // None signals the end of input
 /// assert!(matches!(matcher_word.find_match(None, &['w','o','r','d'], &mut ctx), Matched(t) if t.value == "word"));
 /// ```
-pub trait Matcher: Debug {
+///
+/// `S` is the hasher of the `ctx`, it's the default one unless the [Lexx](crate::Lexx) was made
+/// with [Lexx::with_hasher](crate::Lexx::with_hasher). The built in matchers implement `Matcher<S>`
+/// for any `S: BuildHasher`, a custom [Matcher] that only implements `Matcher` can only be used
+/// with the default hasher.
+pub trait Matcher<S = RandomState>: Debug {
     /// Puts the matcher in a starting state to beging accepting [char]s
-    fn reset(&mut self, ctx: &mut Box<HashMap<String, i32>>);
+    fn reset(&mut self, ctx: &mut Box<Ctx<S>>);
+    /// If the matcher keeps any state other than how far into a match it is and if it is running,
+    /// or uses the `ctx` when it is reset. [Lexx](crate::Lexx) resets matchers that return `false`
+    /// with the cheaper [Matcher::reset_light] instead of [Matcher::reset]. Defaults to `true`.
+    fn is_stateful(&self) -> bool {
+        true
+    }
+    /// Puts a matcher that is not [stateful](Matcher::is_stateful) in a starting state, usually
+    /// this is just setting the match index to 0 and running to `true`. Matchers that return
+    /// `false` from [Matcher::is_stateful] must implement this, the default does nothing.
+    fn reset_light(&mut self) {}
     /// The function that does all the work, it is called repeatedly
     /// with new [Option<char>] values until it returns a [MatcherResult::Matched] or
     /// [MatcherResult::Failed].
@@ -86,14 +111,70 @@ pub trait Matcher: Debug {
     /// * `value` - Is an array of [char] characters that have already been sent to the [Matcher],
     /// so that the [Matcher]s don't have to keep their own history. [Matcher]s can use this to make
     /// the [String] value for their [Token](crate::token::Token) if they find a match.
+    ///
+    /// The `precedence` of a returned [Token](crate::token::Token) does not have to be the same as
+    /// [Matcher::precedence], [Lexx](crate::Lexx) resolves competing matches using the precedence
+    /// of the [Token](crate::token::Token) itself. This lets a [Matcher] raise (or lower) the
+    /// precedence of a single match, for example for a word that turns out to be a contextual keyword.
     fn find_match(
         &mut self,
         oc: Option<char>,
         value: &[char],
-        ctx: &mut Box<HashMap<String, i32>>,
+        ctx: &mut Box<Ctx<S>>,
     ) -> MatcherResult;
+    /// Called by [Lexx](crate::Lexx) with every [Token] this matcher returns as a
+    /// [MatcherResult::Matched], giving it a chance to change the [Token] or to veto it by
+    /// returning [None], in which case it's as if this matcher never matched and the other
+    /// [Matcher]s decide the [Token]. Handy for wrapping a [Matcher] without rewriting its
+    /// [Matcher::find_match]. Defaults to returning the [Token] unchanged.
+    fn on_match(&mut self, token: Token) -> Option<Token> {
+        Some(token)
+    }
     /// If the matcher is still accepting [char]s or not, it hasn't yet found a match or failed
     fn is_running(&self) -> bool;
     /// Used for resolving same length matches, higher numbers have higher precedence
     fn precedence(&self) -> u8;
+    /// A hint of how much work one call to [Matcher::find_match] does compared to the built in
+    /// matchers, which cost 1. [Lexx](crate::Lexx) adds this up for every call while finding a
+    /// [Token] and gives up when it goes over the budget set with
+    /// [Lexxer::set_step_budget](crate::Lexxer::set_step_budget). Defaults to 1.
+    fn step_cost(&self) -> u32 {
+        1
+    }
+    /// Checks the matcher's configuration, returning a description of the problem if it can never
+    /// work as intended, for example an [ExactMatcher](crate::matcher_exact::ExactMatcher) with
+    /// nothing to match. Used by [Lexx::new_checked](crate::Lexx::new_checked). Defaults to `Ok`.
+    fn validate(&self) -> Result<(), String> {
+        Ok(())
+    }
+    /// Describes the matcher's configuration, such as its precedence and what it matches, for
+    /// reproducing problems, see [Lexxer::describe_config](crate::Lexxer::describe_config). The
+    /// built in matchers give their name and settings. Defaults to the name of the type.
+    fn describe(&self) -> String {
+        let name = std::any::type_name::<Self>();
+        let path = name.split('<').next().unwrap_or(name);
+        path.rsplit("::").next().unwrap_or(path).to_string()
+    }
+}
+
+/// How far the `matched` [char]s of a [Token] move the position, as the `(line, column)` a
+/// [Matcher] puts in the [Token]: the number of line breaks and the column after the last one, or
+/// 0 and the number of [char]s if there are none. `\n`, `\r\n` and a lone `\r` are each one line
+/// break, the same as for the [WhitespaceMatcher](crate::matcher_whitespace::WhitespaceMatcher).
+pub(crate) fn line_column_delta(matched: &[char]) -> (usize, usize) {
+    let mut lines = 0;
+    let mut last_break = None;
+    for (i, c) in matched.iter().enumerate() {
+        // the line was already counted at the \r of a \r\n
+        if *c == '\r' || (*c == '\n' && (i == 0 || matched[i - 1] != '\r')) {
+            lines += 1;
+        }
+        if *c == '\r' || *c == '\n' {
+            last_break = Some(i);
+        }
+    }
+    match last_break {
+        Some(i) => (lines, matched.len() - i),
+        None => (0, matched.len()),
+    }
 }
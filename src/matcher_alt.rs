@@ -0,0 +1,259 @@
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::BuildHasher;
+use crate::matcher::{Ctx, Matcher, MatcherResult};
+use crate::token::Token;
+
+/// The AltMatcher runs several sub-matchers side by side on the same [char]s and returns the
+/// longest [Token] any of them match, when two are the same length the one first in the list
+/// wins. The [Token] keeps the token type the sub-matcher gave it but has the AltMatcher's
+/// precedence, so the group competes with the other [Matcher]s as a single unit whatever the
+/// precedences of the sub-matchers are.
+///
+/// # Example
+///
+/// ```rust
+/// use lexx::{Lexx, Lexxer};
+/// use lexx::token::{TOKEN_TYPE_EXACT, TOKEN_TYPE_INTEGER, TOKEN_TYPE_WORD};
+/// use lexx::input::InputString;
+/// use lexx::matcher_alt::AltMatcher;
+/// use lexx::matcher_exact::ExactMatcher;
+/// use lexx::matcher_integer::IntegerMatcher;
+/// use lexx::matcher_word::WordMatcher;
+///
+/// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+///     Box::new(InputString::new(String::from("in42"))),
+///     vec![
+///         Box::new(ExactMatcher::build_exact_matcher(vec!["in"], TOKEN_TYPE_EXACT, 1)),
+///         // the AltMatcher's precedence of 2 beats the ExactMatcher for whatever it matches
+///         Box::new(AltMatcher::build_alt_matcher(
+///             vec![
+///                 Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] }),
+///                 Box::new(IntegerMatcher { index: 0, precedence: 0, running: true, radix_prefixes: false }),
+///             ],
+///             2,
+///         )),
+///     ]
+/// ));
+///
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "in" && t.token_type == TOKEN_TYPE_WORD && t.precedence == 2));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "42" && t.token_type == TOKEN_TYPE_INTEGER && t.column == 3));
+/// assert!(matches!(lexx.next_token(), Ok(None)));
+/// ```
+pub struct AltMatcher<S = RandomState> {
+    /// This matchers precedence.
+    pub precedence: u8,
+    /// If the matcher is currently running.
+    pub running: bool,
+    /// The [Matcher]s to pick the longest match from.
+    pub sub_matchers: Vec<Box<dyn Matcher<S>>>,
+    /// The longest match the sub-matchers have made so far.
+    pub found: Option<Token>,
+}
+
+// not derived, that would need the hasher to be Debug
+impl<S> fmt::Debug for AltMatcher<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AltMatcher")
+            .field("precedence", &self.precedence)
+            .field("running", &self.running)
+            .field("sub_matchers", &self.sub_matchers)
+            .field("found", &self.found)
+            .finish()
+    }
+}
+
+impl<S: BuildHasher> Matcher<S> for AltMatcher<S> {
+    fn reset(&mut self, ctx: &mut Box<Ctx<S>>) {
+        self.running = true;
+        self.found = None;
+        for m in self.sub_matchers.iter_mut() {
+            m.reset(ctx);
+        }
+    }
+
+    fn find_match(
+        &mut self,
+        oc: Option<char>,
+        value: &[char],
+        ctx: &mut Box<Ctx<S>>,
+    ) -> MatcherResult {
+        let mut running = false;
+        for m in self.sub_matchers.iter_mut() {
+            if !m.is_running() {
+                continue;
+            }
+            match m.find_match(oc, value, ctx) {
+                MatcherResult::Running() => running = true,
+                MatcherResult::Failed() => {}
+                MatcherResult::Matched(token) => {
+                    // the sub matcher gets its say before the token is compared
+                    let Some(token) = m.on_match(token) else {
+                        continue;
+                    };
+                    if self.found.as_ref().is_none_or(|f| token.len > f.len) {
+                        self.found = Some(token);
+                    }
+                }
+            }
+        }
+        if running {
+            return MatcherResult::Running();
+        }
+        self.running = false;
+        match self.found.take() {
+            Some(token) => MatcherResult::Matched(Token {
+                precedence: self.precedence,
+                ..token
+            }),
+            None => MatcherResult::Failed(),
+        }
+    }
+    fn is_running(&self) -> bool {
+        self.running
+    }
+    fn precedence(&self) -> u8 {
+        self.precedence
+    }
+    fn describe(&self) -> String {
+        let subs: Vec<String> = self.sub_matchers.iter().map(|m| m.describe()).collect();
+        format!(
+            "AltMatcher {{ precedence: {}, sub_matchers: [{}] }}",
+            self.precedence,
+            subs.join(", ")
+        )
+    }
+    fn validate(&self) -> Result<(), String> {
+        if self.sub_matchers.is_empty() {
+            return Err("AltMatcher has no sub-matchers".to_string());
+        }
+        for (i, m) in self.sub_matchers.iter().enumerate() {
+            if let Err(e) = m.validate() {
+                return Err(format!("AltMatcher sub-matcher {} is invalid: {}", i, e));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<S> AltMatcher<S> {
+    /// Build an alt matcher
+    ///
+    /// # Arguments
+    ///
+    /// * `sub_matchers` - the [Matcher]s to pick the longest match from, earlier ones win ties
+    /// * `precedence` - the precedence for this matcher, used for every match it makes
+    ///
+    pub fn build_alt_matcher(sub_matchers: Vec<Box<dyn Matcher<S>>>, precedence: u8) -> AltMatcher<S> {
+        AltMatcher {
+            precedence,
+            running: true,
+            sub_matchers,
+            found: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::InputString;
+    use crate::matcher::Matcher;
+    use crate::matcher_alt::AltMatcher;
+    use crate::matcher_exact::ExactMatcher;
+    use crate::matcher_float::FloatMatcher;
+    use crate::matcher_integer::IntegerMatcher;
+    use crate::matcher_whitespace::WhitespaceMatcher;
+    use crate::matcher_word::WordMatcher;
+    use crate::token::{TOKEN_TYPE_EXACT, TOKEN_TYPE_FLOAT, TOKEN_TYPE_INTEGER, TOKEN_TYPE_WORD};
+    use crate::{Lexx, LexxError, Lexxer};
+
+    #[test]
+    fn matcher_alt_longest_wins() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("abc 12 x1.5 forest"))),
+            vec![
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(AltMatcher::build_alt_matcher(
+                    vec![
+                        Box::new(ExactMatcher::build_exact_matcher(
+                            vec!["for", "forest"],
+                            TOKEN_TYPE_EXACT,
+                            5,
+                        )),
+                        Box::new(WordMatcher::default()),
+                        Box::new(IntegerMatcher::default()),
+                        Box::new(FloatMatcher::default()),
+                    ],
+                    0,
+                )),
+            ],
+        );
+
+        let tokens: Vec<(String, u16, u8)> = lexx
+            .by_ref()
+            .filter(|t| t.value != " ")
+            .map(|t| (t.value, t.token_type, t.precedence))
+            .collect();
+        assert_eq!(
+            tokens,
+            vec![
+                (String::from("abc"), TOKEN_TYPE_WORD, 0),
+                (String::from("12"), TOKEN_TYPE_INTEGER, 0),
+                (String::from("x"), TOKEN_TYPE_WORD, 0),
+                (String::from("1.5"), TOKEN_TYPE_FLOAT, 0),
+                // the exact match and word are the same length, the exact match is first
+                (String::from("forest"), TOKEN_TYPE_EXACT, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn matcher_alt_fails_when_all_fail() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("ab+"))),
+            vec![Box::new(AltMatcher::build_alt_matcher(
+                vec![
+                    Box::new(WordMatcher::default()),
+                    Box::new(IntegerMatcher::default()),
+                ],
+                0,
+            ))],
+        );
+
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "ab"));
+        assert!(matches!(lexx.next_token(), Err(LexxError::TokenNotFound(_))));
+
+        let matcher: AltMatcher = AltMatcher::build_alt_matcher(vec![], 0);
+        assert!(<AltMatcher as Matcher>::validate(&matcher).is_err());
+    }
+
+    #[test]
+    fn matcher_alt_precedence() {
+        let make_lexx = |alt_precedence: u8| {
+            Lexx::<512>::new(
+                Box::new(InputString::new(String::from("format"))),
+                vec![
+                    Box::new(AltMatcher::build_alt_matcher(
+                        vec![
+                            Box::new(WordMatcher {
+                                precedence: 5,
+                                ..WordMatcher::default()
+                            }),
+                            Box::new(IntegerMatcher::default()),
+                        ],
+                        alt_precedence,
+                    )),
+                    Box::new(ExactMatcher::build_exact_matcher(vec!["for"], TOKEN_TYPE_EXACT, 3)),
+                ],
+            )
+        };
+
+        // the word has a precedence of 5 but the alt's 2 is what is compared to the exact match's 3
+        let mut lexx = make_lexx(2);
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "for" && t.token_type == TOKEN_TYPE_EXACT && t.precedence == 3));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "mat" && t.token_type == TOKEN_TYPE_WORD && t.precedence == 2));
+
+        let mut lexx = make_lexx(4);
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "format" && t.token_type == TOKEN_TYPE_WORD && t.precedence == 4));
+    }
+}
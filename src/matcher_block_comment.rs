@@ -0,0 +1,417 @@
+use std::hash::BuildHasher;
+use crate::matcher::{line_column_delta, Ctx, Matcher, MatcherResult};
+use crate::token::Token;
+
+/// The BlockCommentMatcher matches comments between an open and a close delimiter, such as
+/// `/* a comment */`, and may run over several lines. The [Token] `value` is the whole comment,
+/// delimiters and all, or without [include_prefix](BlockCommentMatcher::include_prefix) only the
+/// text between the delimiters. The `len` and positions of the [Token] always cover the whole
+/// comment, so without the delimiters the `value` is shorter than the `len` and doesn't match
+/// [Token::slice_from]. A comment that is still open at the end of the input doesn't match, so
+/// unless another [Matcher] matches it [Lexx](crate::Lexx) returns a
+/// [TokenNotFound](crate::LexxError::TokenNotFound).
+///
+/// If [nested](BlockCommentMatcher::nested) is set, as in Rust, every open delimiter inside the
+/// comment needs its own close delimiter, so `/* a /* b */ c */` is one comment. Otherwise the
+/// comment ends at the first close delimiter, as in C. A delimiter can't share [char]s with the
+/// one before it, so `/*/` is not a whole comment.
+///
+/// Input like `/* /* /* ...` would keep nested comments opening, so
+/// [max_depth](BlockCommentMatcher::max_depth) can limit how many may be open at once, the
+/// comment doesn't match as soon as one more is opened.
+///
+/// The whole comment has to fit in the `CAP` of the [Lexx](crate::Lexx).
+///
+/// # Example
+///
+/// ```rust
+/// use lexx::{Lexx, Lexxer};
+/// use lexx::token::{TOKEN_TYPE_COMMENT, TOKEN_TYPE_WORD};
+/// use lexx::input::InputString;
+/// use lexx::matcher_block_comment::BlockCommentMatcher;
+/// use lexx::matcher_whitespace::WhitespaceMatcher;
+/// use lexx::matcher_word::WordMatcher;
+///
+/// let lexx_input = InputString::new(String::from("a /* b /* c */\n d */ e"));
+///
+/// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+///     Box::new(lexx_input),
+///     vec![
+///         Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] }),
+///         Box::new(WhitespaceMatcher::with_predicate(char::is_whitespace, 0)),
+///         Box::new(BlockCommentMatcher::build_block_comment("/*", "*/", true, TOKEN_TYPE_COMMENT, 0)),
+///     ]
+/// ));
+///
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "a" && t.token_type == TOKEN_TYPE_WORD));
+/// lexx.next_token().unwrap();
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "/* b /* c */\n d */" && t.token_type == TOKEN_TYPE_COMMENT && t.column == 3));
+/// lexx.next_token().unwrap();
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "e" && t.line == 2 && t.column == 7));
+/// assert!(matches!(lexx.next_token(), Ok(None)));
+/// ```
+#[derive(Clone, Debug)]
+pub struct BlockCommentMatcher {
+    /// Current size of the ongoing match.
+    pub index: usize,
+    /// This matchers precedence.
+    pub precedence: u8,
+    /// If the matcher is currently running.
+    pub running: bool,
+    /// The [char]s that open a comment, such as `/*`.
+    pub open: Vec<char>,
+    /// The [char]s that close a comment, such as `*/`.
+    pub close: Vec<char>,
+    /// If the open and close delimiters are part of the [Token] `value`. Without them the [Token]
+    /// can't be put back with [Lexxer::rewind](crate::Lexxer::rewind).
+    pub include_prefix: bool,
+    /// If comments inside a comment have to be closed too.
+    pub nested: bool,
+    /// How many comments are open.
+    pub depth: usize,
+    /// The most comments that may be open at once when [nested](BlockCommentMatcher::nested),
+    /// or [None] for no limit.
+    pub max_depth: Option<usize>,
+    /// Where the last delimiter ended, the next one can't start before it.
+    pub last: usize,
+    /// What token type to return if a match is made.
+    pub token_type: u16,
+}
+
+impl<S: BuildHasher> Matcher<S> for BlockCommentMatcher {
+    fn reset(&mut self, _ctx: &mut Box<Ctx<S>>) {
+        Matcher::<S>::reset_light(self);
+    }
+
+    fn is_stateful(&self) -> bool {
+        false
+    }
+
+    fn reset_light(&mut self) {
+        self.index = 0;
+        self.depth = 0;
+        self.last = 0;
+        self.running = true;
+    }
+
+    fn find_match(
+        &mut self,
+        oc: Option<char>,
+        value: &[char],
+        _ctx: &mut Box<Ctx<S>>,
+    ) -> MatcherResult {
+        if self.index < self.open.len() {
+            if oc == Some(self.open[self.index]) {
+                self.index += 1;
+                if self.index == self.open.len() {
+                    self.depth = 1;
+                    self.last = self.index;
+                }
+                return MatcherResult::Running();
+            }
+            self.running = false;
+            return MatcherResult::Failed();
+        }
+        if self.depth == 0 {
+            // the comment was closed by the char before this one
+            return self.generate_block_comment_token(value);
+        }
+        if oc.is_none() {
+            self.running = false;
+            return MatcherResult::Failed();
+        }
+        self.index += 1;
+        let seen = &value[self.last..self.index];
+        if seen.ends_with(&self.close) {
+            self.depth -= 1;
+            self.last = self.index;
+        } else if self.nested && seen.ends_with(&self.open) {
+            if self.max_depth.is_some_and(|max| self.depth >= max) {
+                self.running = false;
+                return MatcherResult::Failed();
+            }
+            self.depth += 1;
+            self.last = self.index;
+        }
+        MatcherResult::Running()
+    }
+    fn is_running(&self) -> bool {
+        self.running
+    }
+    fn precedence(&self) -> u8 {
+        self.precedence
+    }
+    fn describe(&self) -> String {
+        let open: String = self.open.iter().collect();
+        let close: String = self.close.iter().collect();
+        format!(
+            "BlockCommentMatcher {{ precedence: {}, open: {:?}, close: {:?}, include_prefix: {}, nested: {}, max_depth: {:?}, token_type: {} }}",
+            self.precedence, open, close, self.include_prefix, self.nested, self.max_depth, self.token_type
+        )
+    }
+    fn validate(&self) -> Result<(), String> {
+        if self.open.is_empty() || self.close.is_empty() {
+            return Err("BlockCommentMatcher has an empty delimiter".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl BlockCommentMatcher {
+    /// Build a block comment matcher
+    ///
+    /// # Arguments
+    ///
+    /// * `open` - the [&str](std::str) that opens a comment, such as `/*`
+    /// * `close` - the [&str](std::str) that closes a comment, such as `*/`
+    /// * `nested` - if comments inside a comment have to be closed too
+    /// * `token_type` - the token type to produce, usually
+    ///   [TOKEN_TYPE_COMMENT](crate::token::TOKEN_TYPE_COMMENT)
+    /// * `precedence` - the precedence for this matcher
+    ///
+    pub fn build_block_comment(
+        open: &str,
+        close: &str,
+        nested: bool,
+        token_type: u16,
+        precedence: u8,
+    ) -> BlockCommentMatcher {
+        BlockCommentMatcher {
+            index: 0,
+            precedence,
+            running: true,
+            open: open.chars().collect(),
+            close: close.chars().collect(),
+            include_prefix: true,
+            nested,
+            depth: 0,
+            max_depth: None,
+            last: 0,
+            token_type,
+        }
+    }
+
+    #[inline(always)]
+    fn generate_block_comment_token(&mut self, value: &[char]) -> MatcherResult {
+        self.running = false;
+        let matched = &value[0..self.index];
+        let (line, column) = line_column_delta(matched);
+        let text = if self.include_prefix {
+            matched
+        } else {
+            &matched[self.open.len()..self.index - self.close.len()]
+        };
+        MatcherResult::Matched(Token {
+            value: text.iter().collect(),
+            token_type: self.token_type,
+            len: self.index,
+            line,
+            column,
+            precedence: self.precedence,
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::InputString;
+    use crate::matcher::Matcher;
+    use crate::matcher_block_comment::BlockCommentMatcher;
+    use crate::matcher_symbol::SymbolMatcher;
+    use crate::matcher_whitespace::WhitespaceMatcher;
+    use crate::matcher_word::WordMatcher;
+    use crate::token::{TOKEN_TYPE_COMMENT, TOKEN_TYPE_SYMBOL, TOKEN_TYPE_WORD};
+    use crate::{Lexx, LexxError, Lexxer};
+
+    #[test]
+    fn matcher_block_comment_nested() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("/* a /* b */ c */x"))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(BlockCommentMatcher::build_block_comment(
+                    "/*",
+                    "*/",
+                    true,
+                    TOKEN_TYPE_COMMENT,
+                    1,
+                )),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "/* a /* b */ c */" && t.token_type == TOKEN_TYPE_COMMENT && t.len == 17));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "x" && t.token_type == TOKEN_TYPE_WORD && t.column == 18));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+
+        // a close can't reuse the '*' of the open before it
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("/*/ a */"))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(BlockCommentMatcher::build_block_comment(
+                    "/*",
+                    "*/",
+                    true,
+                    TOKEN_TYPE_COMMENT,
+                    1,
+                )),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "/*/ a */"));
+    }
+
+    #[test]
+    fn matcher_block_comment_not_nested() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("/* a /* b */ c */"))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(BlockCommentMatcher::build_block_comment(
+                    "/*",
+                    "*/",
+                    false,
+                    TOKEN_TYPE_COMMENT,
+                    1,
+                )),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "/* a /* b */" && t.token_type == TOKEN_TYPE_COMMENT));
+        lexx.next_token().unwrap();
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "c" && t.column == 14));
+        lexx.next_token().unwrap();
+        // the stray close isn't a comment
+        assert!(matches!(lexx.next_token(), Err(LexxError::TokenNotFound(_))));
+
+        // multi char delimiters of different lengths
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("{- a\n-}}"))),
+            vec![
+                Box::new(BlockCommentMatcher::build_block_comment(
+                    "{-",
+                    "\n-}",
+                    false,
+                    TOKEN_TYPE_COMMENT,
+                    0,
+                )),
+                Box::new(SymbolMatcher::default()),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "{- a\n-}" && t.len == 7));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "}" && t.token_type == TOKEN_TYPE_SYMBOL && t.line == 2 && t.column == 3));
+    }
+
+    #[test]
+    fn matcher_block_comment_line_breaks() {
+        // \n, \r\n and a lone \r are each one line break
+        for (text, line, column) in [("/*a\rb*/ x", 2, 5), ("/*a\r\nb*/ x", 2, 5), ("/*a\n\rb*/ x", 3, 5), ("/*\r*/x", 2, 3)] {
+            let mut lexx = Lexx::<512>::new(
+                Box::new(InputString::new(String::from(text))),
+                vec![
+                    Box::new(WordMatcher::default()),
+                    Box::new(WhitespaceMatcher::default()),
+                    Box::new(BlockCommentMatcher::build_block_comment("/*", "*/", true, TOKEN_TYPE_COMMENT, 1)),
+                ],
+            );
+            assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_COMMENT));
+            let x = loop {
+                let t = lexx.next_token().unwrap().unwrap();
+                if t.value == "x" {
+                    break t;
+                }
+            };
+            assert_eq!((x.line, x.column), (line, column), "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn matcher_block_comment_include_prefix() {
+        for include_prefix in [true, false] {
+            let mut lexx = Lexx::<512>::new(
+                Box::new(InputString::new(String::from("/* a\n /* b */ */x"))),
+                vec![
+                    Box::new(WordMatcher::default()),
+                    Box::new(BlockCommentMatcher {
+                        include_prefix,
+                        ..BlockCommentMatcher::build_block_comment("/*", "*/", true, TOKEN_TYPE_COMMENT, 1)
+                    }),
+                ],
+            );
+            // only the outer delimiters are left out
+            let value = if include_prefix { "/* a\n /* b */ */" } else { " a\n /* b */ " };
+            assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == value && t.len == 16 && t.end_byte == 16));
+            assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "x" && t.line == 2 && t.column == 12));
+        }
+    }
+
+    #[test]
+    fn matcher_block_comment_max_depth() {
+        let text = format!("{}x{}", "/*".repeat(100), "*/".repeat(100));
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(text)),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(BlockCommentMatcher {
+                    max_depth: Some(4),
+                    ..BlockCommentMatcher::build_block_comment("/*", "*/", true, TOKEN_TYPE_COMMENT, 1)
+                }),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Err(LexxError::TokenNotFound(_))));
+
+        // up to the limit is fine
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("/* /* /* /* a */ */ */ */x"))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(BlockCommentMatcher {
+                    max_depth: Some(4),
+                    ..BlockCommentMatcher::build_block_comment("/*", "*/", true, TOKEN_TYPE_COMMENT, 1)
+                }),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_COMMENT && t.len == 25));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "x"));
+    }
+
+    #[test]
+    fn matcher_block_comment_unterminated() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("/* a /* b */"))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(BlockCommentMatcher::build_block_comment(
+                    "/*",
+                    "*/",
+                    true,
+                    TOKEN_TYPE_COMMENT,
+                    1,
+                )),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Err(LexxError::TokenNotFound(_))));
+
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("/* a"))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(BlockCommentMatcher::build_block_comment(
+                    "/*",
+                    "*/",
+                    false,
+                    TOKEN_TYPE_COMMENT,
+                    1,
+                )),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Err(LexxError::TokenNotFound(_))));
+
+        let matcher =
+            BlockCommentMatcher::build_block_comment("/*", "", false, TOKEN_TYPE_COMMENT, 0);
+        assert!(<BlockCommentMatcher as Matcher>::validate(&matcher).is_err());
+    }
+}
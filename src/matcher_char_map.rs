@@ -0,0 +1,161 @@
+use std::hash::BuildHasher;
+use std::collections::HashMap;
+
+use crate::matcher::{Ctx, Matcher, MatcherResult};
+use crate::token::Token;
+
+/// The CharMapMatcher matches a single [char] and uses a lookup table to decide the token type,
+/// so one matcher can handle all the single char tokens of a language such as `+ - * / ( ) { } ,`
+/// each with their own type. Chars not in the table do not match.
+///
+/// # Example
+///
+/// ```rust
+/// use std::collections::HashMap;
+/// use lexx::{Lexx, Lexxer};
+/// use lexx::input::InputString;
+/// use lexx::matcher_char_map::CharMapMatcher;
+///
+/// let lexx_input = InputString::new(String::from("(+)"));
+///
+/// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+///     Box::new(lexx_input),
+///     vec![
+///         Box::new(CharMapMatcher::build_char_map_matcher(
+///             HashMap::from([('(', 10), (')', 11), ('+', 12)]),
+///             0,
+///         )),
+///     ]
+/// ));
+///
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "(" && t.token_type == 10));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "+" && t.token_type == 12));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == ")" && t.token_type == 11));
+/// assert!(matches!(lexx.next_token(), Ok(None)));
+/// ```
+#[derive(Clone, Debug)]
+pub struct CharMapMatcher {
+    /// This matchers precedence.
+    pub precedence: u8,
+    /// If the matcher is currently running.
+    pub running: bool,
+    /// The chars to match and the token type to return for each.
+    pub types: HashMap<char, u16>,
+}
+
+impl<S: BuildHasher> Matcher<S> for CharMapMatcher {
+    fn reset(&mut self, _ctx: &mut Box<Ctx<S>>) {
+        Matcher::<S>::reset_light(self);
+    }
+
+    fn is_stateful(&self) -> bool {
+        false
+    }
+
+    fn reset_light(&mut self) {
+        self.running = true;
+    }
+
+    fn find_match(
+        &mut self,
+        oc: Option<char>,
+        _value: &[char],
+        _ctx: &mut Box<Ctx<S>>,
+    ) -> MatcherResult {
+        self.running = false;
+        match oc.and_then(|c| self.types.get(&c).map(|t| (c, *t))) {
+            Some((c, token_type)) => MatcherResult::Matched(Token {
+                value: c.to_string(),
+                token_type,
+                len: 1,
+                line: 0,
+                column: 1,
+                precedence: self.precedence,
+                ..Default::default()
+            }),
+            None => MatcherResult::Failed(),
+        }
+    }
+    fn is_running(&self) -> bool {
+        self.running
+    }
+    fn precedence(&self) -> u8 {
+        self.precedence
+    }
+    fn describe(&self) -> String {
+        let mut types: Vec<(&char, &u16)> = self.types.iter().collect();
+        types.sort();
+        format!(
+            "CharMapMatcher {{ precedence: {}, types: {:?} }}",
+            self.precedence, types
+        )
+    }
+    fn validate(&self) -> Result<(), String> {
+        if self.types.is_empty() {
+            return Err("CharMapMatcher has no chars to match".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl CharMapMatcher {
+    /// Build a char map matcher
+    ///
+    /// # Arguments
+    ///
+    /// * `types` - the chars to match, each mapped to the token type to produce for it
+    /// * `precedence` - the precedence for this matcher
+    ///
+    pub fn build_char_map_matcher(types: HashMap<char, u16>, precedence: u8) -> CharMapMatcher {
+        CharMapMatcher {
+            precedence,
+            running: true,
+            types,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::input::InputString;
+    use crate::matcher_char_map::CharMapMatcher;
+    use crate::matcher_symbol::SymbolMatcher;
+    use crate::matcher_word::WordMatcher;
+    use crate::token::{TOKEN_TYPE_SYMBOL, TOKEN_TYPE_WORD};
+    use crate::{Lexx, Lexxer};
+
+    #[test]
+    fn matcher_char_map_maps_types() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("f(x)+"))),
+            vec![
+                Box::new(WordMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    trailing: &[],
+                }),
+                Box::new(SymbolMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    max_len: 0,
+                }),
+                Box::new(CharMapMatcher::build_char_map_matcher(
+                    HashMap::from([('(', 10), (')', 11)]),
+                    1,
+                )),
+            ],
+        );
+
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "f" && t.token_type == TOKEN_TYPE_WORD));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "(" && t.token_type == 10 && t.column == 2));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "x" && t.token_type == TOKEN_TYPE_WORD));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == ")" && t.token_type == 11 && t.column == 4));
+        // not in the map so left to the SymbolMatcher
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "+" && t.token_type == TOKEN_TYPE_SYMBOL));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+    }
+}
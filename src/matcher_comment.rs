@@ -0,0 +1,248 @@
+use std::hash::BuildHasher;
+use crate::matcher::{Ctx, Matcher, MatcherResult};
+use crate::token::Token;
+
+/// The CommentMatcher matches single line comments, such as `// a comment` or `# a comment`. The
+/// match starts only when the input starts with the whole prefix and runs up to, but not
+/// including, the line break at the end of the line, or to the end of the input if there is no
+/// line break. The [Token] `value` is the whole comment, prefix and all.
+///
+/// Without [include_prefix](CommentMatcher::include_prefix) the `value` is only the text after the
+/// prefix, for example for a documentation tool. The `len` and positions of the [Token] still
+/// cover the whole comment, so the input can be put back together from them, which means the
+/// `value` is shorter than the `len` and doesn't match [Token::slice_from].
+///
+/// # Example
+///
+/// ```rust
+/// use lexx::{Lexx, Lexxer};
+/// use lexx::token::{TOKEN_TYPE_COMMENT, TOKEN_TYPE_WHITESPACE, TOKEN_TYPE_WORD};
+/// use lexx::input::InputString;
+/// use lexx::matcher_comment::CommentMatcher;
+/// use lexx::matcher_whitespace::WhitespaceMatcher;
+/// use lexx::matcher_word::WordMatcher;
+///
+/// let lexx_input = InputString::new(String::from("x // the x\ny"));
+///
+/// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+///     Box::new(lexx_input),
+///     vec![
+///         Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] }),
+///         Box::new(WhitespaceMatcher::with_predicate(char::is_whitespace, 0)),
+///         Box::new(CommentMatcher::build_line_comment("//", TOKEN_TYPE_COMMENT, 1)),
+///     ]
+/// ));
+///
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "x" && t.token_type == TOKEN_TYPE_WORD));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_WHITESPACE));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "// the x" && t.token_type == TOKEN_TYPE_COMMENT && t.column == 3));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "\n" && t.token_type == TOKEN_TYPE_WHITESPACE));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "y" && t.line == 2));
+/// assert!(matches!(lexx.next_token(), Ok(None)));
+/// ```
+#[derive(Clone, Debug)]
+pub struct CommentMatcher {
+    /// Current size of the ongoing match.
+    pub index: usize,
+    /// This matchers precedence.
+    pub precedence: u8,
+    /// If the matcher is currently running.
+    pub running: bool,
+    /// The [char]s that start a comment, such as `//`.
+    pub prefix: Vec<char>,
+    /// If the prefix is part of the [Token] `value`. Without it the [Token] can't be put back
+    /// with [Lexxer::rewind](crate::Lexxer::rewind).
+    pub include_prefix: bool,
+    /// What token type to return if a match is made.
+    pub token_type: u16,
+}
+
+impl<S: BuildHasher> Matcher<S> for CommentMatcher {
+    fn reset(&mut self, _ctx: &mut Box<Ctx<S>>) {
+        Matcher::<S>::reset_light(self);
+    }
+
+    fn is_stateful(&self) -> bool {
+        false
+    }
+
+    fn reset_light(&mut self) {
+        self.index = 0;
+        self.running = true;
+    }
+
+    fn find_match(
+        &mut self,
+        oc: Option<char>,
+        value: &[char],
+        _ctx: &mut Box<Ctx<S>>,
+    ) -> MatcherResult {
+        if self.index < self.prefix.len() {
+            if oc == Some(self.prefix[self.index]) {
+                self.index += 1;
+                return MatcherResult::Running();
+            }
+            self.running = false;
+            return MatcherResult::Failed();
+        }
+        match oc {
+            Some(c) if c != '\n' && c != '\r' => {
+                self.index += 1;
+                MatcherResult::Running()
+            }
+            _ => self.generate_comment_token(value),
+        }
+    }
+    fn is_running(&self) -> bool {
+        self.running
+    }
+    fn precedence(&self) -> u8 {
+        self.precedence
+    }
+    fn describe(&self) -> String {
+        let prefix: String = self.prefix.iter().collect();
+        format!(
+            "CommentMatcher {{ precedence: {}, prefix: {:?}, include_prefix: {}, token_type: {} }}",
+            self.precedence, prefix, self.include_prefix, self.token_type
+        )
+    }
+    fn validate(&self) -> Result<(), String> {
+        if self.prefix.is_empty() {
+            return Err("CommentMatcher has an empty prefix".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl CommentMatcher {
+    /// Build a comment matcher for comments that run to the end of the line
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - the [&str](std::str) that starts a comment, such as `//`
+    /// * `token_type` - the token type to produce, usually
+    ///   [TOKEN_TYPE_COMMENT](crate::token::TOKEN_TYPE_COMMENT)
+    /// * `precedence` - the precedence for this matcher
+    ///
+    pub fn build_line_comment(prefix: &str, token_type: u16, precedence: u8) -> CommentMatcher {
+        CommentMatcher {
+            index: 0,
+            precedence,
+            running: true,
+            prefix: prefix.chars().collect(),
+            include_prefix: true,
+            token_type,
+        }
+    }
+
+    #[inline(always)]
+    fn generate_comment_token(&mut self, value: &[char]) -> MatcherResult {
+        self.running = false;
+        let start = if self.include_prefix { 0 } else { self.prefix.len() };
+        MatcherResult::Matched(Token {
+            value: value[start..self.index].iter().collect(),
+            token_type: self.token_type,
+            len: self.index,
+            line: 0,
+            column: self.index,
+            precedence: self.precedence,
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::InputString;
+    use crate::matcher::Matcher;
+    use crate::matcher_comment::CommentMatcher;
+    use crate::matcher_symbol::SymbolMatcher;
+    use crate::matcher_whitespace::WhitespaceMatcher;
+    use crate::matcher_word::WordMatcher;
+    use crate::token::{TOKEN_TYPE_COMMENT, TOKEN_TYPE_SYMBOL, TOKEN_TYPE_WHITESPACE};
+    use crate::{Lexx, Lexxer};
+
+    #[test]
+    fn matcher_comment_to_end_of_line() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("a // b /* c\r\n//\nd"))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(SymbolMatcher::default()),
+                Box::new(CommentMatcher::build_line_comment("//", TOKEN_TYPE_COMMENT, 1)),
+            ],
+        );
+
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "a"));
+        lexx.next_token().unwrap();
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "// b /* c" && t.token_type == TOKEN_TYPE_COMMENT && t.len == 9 && t.column == 3));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "\r\n" && t.token_type == TOKEN_TYPE_WHITESPACE));
+        // a comment can be just the prefix
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "//" && t.token_type == TOKEN_TYPE_COMMENT && t.line == 2));
+        lexx.next_token().unwrap();
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "d" && t.line == 3));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+    }
+
+    #[test]
+    fn matcher_comment_to_end_of_input() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("//last"))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(SymbolMatcher::default()),
+                Box::new(CommentMatcher::build_line_comment("//", TOKEN_TYPE_COMMENT, 1)),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "//last" && t.token_type == TOKEN_TYPE_COMMENT));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+    }
+
+    #[test]
+    fn matcher_comment_include_prefix() {
+        for include_prefix in [true, false] {
+            let mut lexx = Lexx::<512>::new(
+                Box::new(InputString::new(String::from("a // b\nc"))),
+                vec![
+                    Box::new(WordMatcher::default()),
+                    Box::new(WhitespaceMatcher::default()),
+                    Box::new(CommentMatcher {
+                        include_prefix,
+                        ..CommentMatcher::build_line_comment("//", TOKEN_TYPE_COMMENT, 1)
+                    }),
+                ],
+            );
+            lexx.next_token().unwrap();
+            lexx.next_token().unwrap();
+            let value = if include_prefix { "// b" } else { " b" };
+            // the len and positions cover the prefix either way
+            assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == value && t.len == 4 && t.column == 3 && t.start_byte == 2 && t.end_byte == 6));
+            assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "\n" && t.token_type == TOKEN_TYPE_WHITESPACE && t.column == 7));
+            assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "c" && t.line == 2 && t.column == 1));
+        }
+    }
+
+    #[test]
+    fn matcher_comment_needs_whole_prefix() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("a/b /"))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(SymbolMatcher::default()),
+                Box::new(CommentMatcher::build_line_comment("//", TOKEN_TYPE_COMMENT, 1)),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "a"));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "/" && t.token_type == TOKEN_TYPE_SYMBOL));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "b"));
+        lexx.next_token().unwrap();
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "/" && t.token_type == TOKEN_TYPE_SYMBOL));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+
+        let matcher = CommentMatcher::build_line_comment("", TOKEN_TYPE_COMMENT, 0);
+        assert!(<CommentMatcher as Matcher>::validate(&matcher).is_err());
+    }
+}
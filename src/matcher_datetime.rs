@@ -0,0 +1,318 @@
+use std::hash::BuildHasher;
+use crate::matcher::{Ctx, Matcher, MatcherResult};
+use crate::token::{Token, TOKEN_TYPE_DATETIME};
+
+/// The shape of a date and time up to the seconds, `d` is any ascii digit.
+const TEMPLATE: [char; 19] = [
+    'd', 'd', 'd', 'd', '-', 'd', 'd', '-', 'd', 'd', 'T', 'd', 'd', ':', 'd', 'd', ':', 'd', 'd',
+];
+
+/// The DateTimeMatcher matches ISO-8601 dates such as `2024-01-15`, optionally with a time such
+/// as `2024-01-15T10:30` or `2024-01-15T10:30:00`. A time with seconds may have a fraction, as in
+/// `10:30:00.25`, and a time zone of `Z` or an offset such as `+01:00`. Only this extended form
+/// with the `-`, `:` and `T` separators is matched, not the basic form such as `20240115`.
+///
+/// Each part is checked against its range, the month is 01 to 12, the day 01 to 31, the hour 00
+/// to 23, the minute 00 to 59 and the second 00 to 60 to allow for leap seconds. The day is NOT
+/// checked against the month or year, so `2024-02-31` is matched. When a part is out of range
+/// the longest valid date or date and time before it is matched, so `2024-01-15T25:00` matches
+/// `2024-01-15`, and if the date itself is bad, such as `2024-13-40`, nothing is matched.
+///
+/// # Example
+///
+/// ```rust
+/// use lexx::{Lexx, Lexxer};
+/// use lexx::token::{TOKEN_TYPE_DATETIME, TOKEN_TYPE_WHITESPACE};
+/// use lexx::input::InputString;
+/// use lexx::matcher_datetime::DateTimeMatcher;
+/// use lexx::matcher_whitespace::WhitespaceMatcher;
+///
+/// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+///     Box::new(InputString::new(String::from("2024-01-15 2024-01-15T10:30:00Z"))),
+///     vec![
+///         Box::new(DateTimeMatcher::build_datetime_matcher(0)),
+///         Box::new(WhitespaceMatcher::with_predicate(char::is_whitespace, 0)),
+///     ]
+/// ));
+///
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "2024-01-15" && t.token_type == TOKEN_TYPE_DATETIME));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_WHITESPACE));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "2024-01-15T10:30:00Z" && t.token_type == TOKEN_TYPE_DATETIME && t.column == 12));
+/// assert!(matches!(lexx.next_token(), Ok(None)));
+/// ```
+#[derive(Clone, Debug, Copy)]
+pub struct DateTimeMatcher {
+    /// Current size of the ongoing match.
+    pub index: usize,
+    /// This matchers precedence.
+    pub precedence: u8,
+    /// If the matcher is currently running.
+    pub running: bool,
+    /// Where the sign of a time zone offset is, 0 if there isn't one.
+    pub zone: usize,
+    /// If the time zone has been matched, nothing can follow it.
+    pub ended: bool,
+    /// The length of the longest valid date or date and time seen so far, 0 if none.
+    pub found: usize,
+}
+
+impl<S: BuildHasher> Matcher<S> for DateTimeMatcher {
+    fn reset(&mut self, _ctx: &mut Box<Ctx<S>>) {
+        Matcher::<S>::reset_light(self);
+    }
+
+    fn is_stateful(&self) -> bool {
+        false
+    }
+
+    fn reset_light(&mut self) {
+        self.index = 0;
+        self.zone = 0;
+        self.ended = false;
+        self.found = 0;
+        self.running = true;
+    }
+
+    fn find_match(
+        &mut self,
+        oc: Option<char>,
+        value: &[char],
+        _ctx: &mut Box<Ctx<S>>,
+    ) -> MatcherResult {
+        let c = match oc {
+            None => return self.generate_datetime_token(value),
+            Some(c) => c,
+        };
+        let i = self.index;
+        let fits = if i < TEMPLATE.len() {
+            (TEMPLATE[i] == 'd' && c.is_ascii_digit()) || TEMPLATE[i] == c
+        } else if self.ended {
+            false
+        } else if self.zone > 0 {
+            if i - self.zone == 3 {
+                c == ':'
+            } else {
+                c.is_ascii_digit()
+            }
+        } else if c == '.' {
+            i == TEMPLATE.len()
+        } else if c.is_ascii_digit() {
+            // a digit after the seconds is part of the fraction
+            value[TEMPLATE.len()] == '.'
+        } else if c == 'Z' || c == '+' || c == '-' {
+            // the zone can't follow a '.' with no digits after it
+            self.found == i
+        } else {
+            false
+        };
+        if !fits {
+            return self.generate_datetime_token(value);
+        }
+        self.index += 1;
+        let len = self.index;
+        let in_range = match len {
+            7 => (1..=12).contains(&number(&value[5..7])),
+            10 => (1..=31).contains(&number(&value[8..10])),
+            13 => number(&value[11..13]) <= 23,
+            16 => number(&value[14..16]) <= 59,
+            19 => number(&value[17..19]) <= 60,
+            _ => true,
+        };
+        if !in_range {
+            return self.generate_datetime_token(value);
+        }
+        if len == 10 || len == 16 || len == 19 {
+            self.found = len;
+        } else if len > TEMPLATE.len() {
+            if self.zone > 0 {
+                match len - self.zone {
+                    3 if number(&value[len - 2..len]) > 23 => {
+                        return self.generate_datetime_token(value)
+                    }
+                    6 if number(&value[len - 2..len]) > 59 => {
+                        return self.generate_datetime_token(value)
+                    }
+                    6 => {
+                        self.found = len;
+                        self.ended = true;
+                    }
+                    _ => {}
+                }
+            } else if c == 'Z' {
+                self.found = len;
+                self.ended = true;
+            } else if c == '+' || c == '-' {
+                self.zone = i;
+            } else if c.is_ascii_digit() {
+                self.found = len;
+            }
+        }
+        MatcherResult::Running()
+    }
+    fn is_running(&self) -> bool {
+        self.running
+    }
+    fn precedence(&self) -> u8 {
+        self.precedence
+    }
+    fn describe(&self) -> String {
+        format!("DateTimeMatcher {{ precedence: {} }}", self.precedence)
+    }
+}
+
+impl Default for DateTimeMatcher {
+    /// A DateTimeMatcher with a precedence of 0, ready to match.
+    fn default() -> Self {
+        DateTimeMatcher::build_datetime_matcher(0)
+    }
+}
+
+impl DateTimeMatcher {
+    /// Build a date time matcher
+    ///
+    /// # Arguments
+    ///
+    /// * `precedence` - the precedence for this matcher
+    ///
+    pub fn build_datetime_matcher(precedence: u8) -> DateTimeMatcher {
+        DateTimeMatcher {
+            index: 0,
+            precedence,
+            running: true,
+            zone: 0,
+            ended: false,
+            found: 0,
+        }
+    }
+
+    #[inline(always)]
+    fn generate_datetime_token(&mut self, value: &[char]) -> MatcherResult {
+        self.running = false;
+        if self.found > 0 {
+            MatcherResult::Matched(Token {
+                value: value[0..self.found].iter().collect(),
+                token_type: TOKEN_TYPE_DATETIME,
+                len: self.found,
+                line: 0,
+                column: self.found,
+                precedence: self.precedence,
+                ..Default::default()
+            })
+        } else {
+            MatcherResult::Failed()
+        }
+    }
+}
+
+/// The value of a run of ascii digits.
+fn number(digits: &[char]) -> u32 {
+    digits
+        .iter()
+        .fold(0, |n, c| n * 10 + c.to_digit(10).unwrap_or(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::InputString;
+    use crate::matcher_datetime::DateTimeMatcher;
+    use crate::matcher_integer::IntegerMatcher;
+    use crate::matcher_symbol::SymbolMatcher;
+    use crate::matcher_whitespace::WhitespaceMatcher;
+    use crate::matcher_word::WordMatcher;
+    use crate::token::{TOKEN_TYPE_DATETIME, TOKEN_TYPE_INTEGER, TOKEN_TYPE_SYMBOL};
+    use crate::{Lexx, Lexxer};
+
+    fn datetimes(text: &str) -> Vec<String> {
+        Lexx::<512>::new(
+            Box::new(InputString::new(String::from(text))),
+            vec![
+                Box::new(DateTimeMatcher::default()),
+                Box::new(IntegerMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(SymbolMatcher::with_max_len(1, 0)),
+                Box::new(WordMatcher::default()),
+            ],
+        )
+            .filter(|t| t.token_type == TOKEN_TYPE_DATETIME)
+            .map(|t| t.value)
+            .collect()
+    }
+
+    #[test]
+    fn matcher_datetime_date() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("on 2024-01-15."))),
+            vec![
+                Box::new(DateTimeMatcher::default()),
+                Box::new(IntegerMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(SymbolMatcher::with_max_len(1, 0)),
+                Box::new(WordMatcher::default()),
+            ],
+        );
+        lexx.next_token().unwrap();
+        lexx.next_token().unwrap();
+        assert!(
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "2024-01-15" && t.token_type == TOKEN_TYPE_DATETIME && t.len == 10 && t.column == 4)
+        );
+        assert!(
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "." && t.token_type == TOKEN_TYPE_SYMBOL)
+        );
+        assert!(matches!(lexx.next_token(), Ok(None)));
+    }
+
+    #[test]
+    fn matcher_datetime_time() {
+        assert_eq!(
+            datetimes("2024-01-15T10:30:00 2024-01-15T10:30 1999-12-31T23:59:60.5+05:30"),
+            vec![
+                String::from("2024-01-15T10:30:00"),
+                String::from("2024-01-15T10:30"),
+                String::from("1999-12-31T23:59:60.5+05:30"),
+            ]
+        );
+        // an hour that is out of range leaves just the date
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("2024-01-15T25:00"))),
+            vec![
+                Box::new(DateTimeMatcher::default()),
+                Box::new(IntegerMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(SymbolMatcher::with_max_len(1, 0)),
+                Box::new(WordMatcher::default()),
+            ],
+        );
+        assert!(
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "2024-01-15" && t.token_type == TOKEN_TYPE_DATETIME)
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "T"));
+        // as does a '.' with no fraction after it
+        assert_eq!(
+            datetimes("2024-01-15T10:30:00.Z"),
+            vec![String::from("2024-01-15T10:30:00")]
+        );
+    }
+
+    #[test]
+    fn matcher_datetime_invalid_month() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("2024-13-40"))),
+            vec![
+                Box::new(DateTimeMatcher::default()),
+                Box::new(IntegerMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(SymbolMatcher::with_max_len(1, 0)),
+                Box::new(WordMatcher::default()),
+            ],
+        );
+        assert!(
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "2024" && t.token_type == TOKEN_TYPE_INTEGER)
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "-"));
+        assert!(
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "13" && t.token_type == TOKEN_TYPE_INTEGER)
+        );
+
+        assert!(datetimes("2024-00-10 2024-12-32 2024-1-15").is_empty());
+    }
+}
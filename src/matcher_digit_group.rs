@@ -0,0 +1,312 @@
+use std::hash::BuildHasher;
+use crate::matcher::{Ctx, Matcher, MatcherResult};
+use crate::token::{Token, TOKEN_TYPE_DIGIT_GROUP};
+
+/// The key in [Lexx::ctx](crate::Lexx::ctx) the [DigitGroupMatcher] stores the number of digits
+/// of its last match under. It is written whether or not that match is the [Token] returned, see
+/// [DigitGroupMatcher].
+pub const DIGIT_GROUP_DIGITS: &str = "digit_group_digits";
+
+/// The DigitGroupMatcher matches runs of digits broken up by separators, the way phone and card
+/// numbers are written, such as `123-456-7890` or `4111 1111 1111 1111`, as a single
+/// [TOKEN_TYPE_DIGIT_GROUP] token. A separator only belongs to the match when there is a digit on
+/// both sides of it, and all the separators in a match must be the same, so `123-456-7890 4111`
+/// is two runs. The match must have between `min_digits` and `max_digits` digits, not counting
+/// the separators. A longer run of digits doesn't match at all.
+///
+/// Whenever it matches, the number of digits is stored in the `ctx` under [DIGIT_GROUP_DIGITS].
+/// That happens as the match is made, before [Lexx](crate::Lexx) picks the winner, so when another
+/// [Matcher] wins it describes a match that wasn't returned. Use [DigitGroupMatcher::digits] on
+/// the returned [Token] to get its digits, and their count, reliably.
+///
+/// A run without any separators matches too, so when an
+/// [IntegerMatcher](crate::matcher_integer::IntegerMatcher) is also used give one of them a
+/// higher precedence to decide which wins.
+///
+/// # Example
+///
+/// ```rust
+/// use lexx::{Lexx, Lexxer};
+/// use lexx::token::{TOKEN_TYPE_DIGIT_GROUP, TOKEN_TYPE_INTEGER};
+/// use lexx::input::InputString;
+/// use lexx::matcher_digit_group::{DigitGroupMatcher, DIGIT_GROUP_DIGITS};
+/// use lexx::matcher_integer::IntegerMatcher;
+/// use lexx::matcher_whitespace::WhitespaceMatcher;
+///
+/// let mut lexx = Lexx::<512>::new(
+///     Box::new(InputString::new(String::from("call 555-0100 x 12"))),
+///     vec![
+///         Box::new(IntegerMatcher::default()),
+///         Box::new(WhitespaceMatcher::default()),
+///         Box::new(lexx::matcher_word::WordMatcher::default()),
+///         Box::new(DigitGroupMatcher::build_digit_group_matcher(vec!['-', ' '], 7, 19, 1)),
+///     ]
+/// );
+///
+/// lexx.next_token().unwrap();
+/// lexx.next_token().unwrap();
+/// let phone = lexx.next_token().unwrap().unwrap();
+/// assert!(phone.value == "555-0100" && phone.token_type == TOKEN_TYPE_DIGIT_GROUP);
+/// assert_eq!(DigitGroupMatcher::digits(&phone), "5550100");
+/// assert_eq!(DigitGroupMatcher::digits(&phone).len(), 7);
+/// assert_eq!(lexx.ctx.get(DIGIT_GROUP_DIGITS), Some(&7));
+/// // too few digits
+/// lexx.next_token().unwrap();
+/// lexx.next_token().unwrap();
+/// lexx.next_token().unwrap();
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "12" && t.token_type == TOKEN_TYPE_INTEGER));
+/// ```
+#[derive(Clone, Debug)]
+pub struct DigitGroupMatcher {
+    /// Current size of the ongoing match.
+    pub index: usize,
+    /// This matchers precedence.
+    pub precedence: u8,
+    /// If the matcher is currently running.
+    pub running: bool,
+    /// The number of digits in the ongoing match.
+    pub digits: usize,
+    /// If the last char was a separator.
+    pub separator: bool,
+    /// The separator the ongoing match uses, once it has seen one.
+    pub used: Option<char>,
+    /// The chars allowed between digits.
+    pub separators: Vec<char>,
+    /// The least digits a match may have.
+    pub min_digits: usize,
+    /// The most digits a match may have.
+    pub max_digits: usize,
+}
+
+impl<S: BuildHasher> Matcher<S> for DigitGroupMatcher {
+    fn reset(&mut self, _ctx: &mut Box<Ctx<S>>) {
+        self.index = 0;
+        self.digits = 0;
+        self.separator = false;
+        self.used = None;
+        self.running = true;
+    }
+
+    fn find_match(
+        &mut self,
+        oc: Option<char>,
+        value: &[char],
+        ctx: &mut Box<Ctx<S>>,
+    ) -> MatcherResult {
+        match oc {
+            Some(c) if c.is_ascii_digit() => {
+                self.index += 1;
+                self.digits += 1;
+                self.separator = false;
+                if self.digits > self.max_digits {
+                    self.running = false;
+                    return MatcherResult::Failed();
+                }
+                MatcherResult::Running()
+            }
+            Some(c)
+                if self.digits > 0
+                    && !self.separator
+                    && self.separators.contains(&c)
+                    && self.used.is_none_or(|u| u == c) =>
+            {
+                self.index += 1;
+                self.separator = true;
+                self.used = Some(c);
+                MatcherResult::Running()
+            }
+            _ => {
+                self.running = false;
+                // a separator not followed by a digit isn't part of the match
+                if self.separator {
+                    self.index -= 1;
+                }
+                if self.digits < self.min_digits {
+                    return MatcherResult::Failed();
+                }
+                ctx.insert(DIGIT_GROUP_DIGITS.to_string(), self.digits as i32);
+                MatcherResult::Matched(Token {
+                    value: value[0..self.index].iter().collect(),
+                    token_type: TOKEN_TYPE_DIGIT_GROUP,
+                    len: self.index,
+                    line: 0,
+                    column: self.index,
+                    precedence: self.precedence,
+                    ..Default::default()
+                })
+            }
+        }
+    }
+    fn is_running(&self) -> bool {
+        self.running
+    }
+    fn precedence(&self) -> u8 {
+        self.precedence
+    }
+    fn describe(&self) -> String {
+        format!(
+            "DigitGroupMatcher {{ precedence: {}, separators: {:?}, min_digits: {}, max_digits: {} }}",
+            self.precedence, self.separators, self.min_digits, self.max_digits
+        )
+    }
+    fn validate(&self) -> Result<(), String> {
+        if self.min_digits == 0 {
+            return Err("DigitGroupMatcher min_digits must be at least 1".to_string());
+        }
+        if self.min_digits > self.max_digits {
+            return Err("DigitGroupMatcher min_digits is more than max_digits".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl DigitGroupMatcher {
+    /// Build a digit group matcher
+    ///
+    /// # Arguments
+    ///
+    /// * `separators` - the [char]s allowed between digits, such as `-` and ` `
+    /// * `min_digits` - the least digits a match may have
+    /// * `max_digits` - the most digits a match may have
+    /// * `precedence` - the precedence for this matcher
+    ///
+    pub fn build_digit_group_matcher(
+        separators: Vec<char>,
+        min_digits: usize,
+        max_digits: usize,
+        precedence: u8,
+    ) -> DigitGroupMatcher {
+        DigitGroupMatcher {
+            index: 0,
+            precedence,
+            running: true,
+            digits: 0,
+            separator: false,
+            used: None,
+            separators,
+            min_digits,
+            max_digits,
+        }
+    }
+
+    /// Returns just the digits of a [Token] made by a DigitGroupMatcher, without the separators.
+    pub fn digits(token: &Token) -> String {
+        token.value.chars().filter(char::is_ascii_digit).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::InputString;
+    use crate::matcher_digit_group::{DigitGroupMatcher, DIGIT_GROUP_DIGITS};
+    use crate::matcher_integer::IntegerMatcher;
+    use crate::matcher_symbol::SymbolMatcher;
+    use crate::matcher_whitespace::WhitespaceMatcher;
+    use crate::token::{TOKEN_TYPE_DIGIT_GROUP, TOKEN_TYPE_INTEGER, TOKEN_TYPE_SYMBOL};
+    use crate::{Lexx, Lexxer};
+
+    #[test]
+    fn matcher_digit_group_phone_and_card() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("123-456-7890 4111 1111 1111 1111-"))),
+            vec![
+                Box::new(IntegerMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(SymbolMatcher::default()),
+                Box::new(DigitGroupMatcher::build_digit_group_matcher(
+                    vec!['-', ' '],
+                    7,
+                    16,
+                    1,
+                )),
+            ],
+        );
+
+        let phone = lexx.next_token().unwrap().unwrap();
+        assert_eq!(phone.value, "123-456-7890");
+        assert_eq!(phone.token_type, TOKEN_TYPE_DIGIT_GROUP);
+        assert_eq!(DigitGroupMatcher::digits(&phone), "1234567890");
+        assert_eq!(lexx.ctx.get(DIGIT_GROUP_DIGITS), Some(&10));
+        lexx.next_token().unwrap();
+        let card = lexx.next_token().unwrap().unwrap();
+        assert_eq!(card.value, "4111 1111 1111 1111");
+        assert_eq!(card.len, 19);
+        assert_eq!(card.column, 14);
+        assert_eq!(DigitGroupMatcher::digits(&card), "4111111111111111");
+        assert_eq!(lexx.ctx.get(DIGIT_GROUP_DIGITS), Some(&16));
+        // the trailing separator isn't part of it
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "-" && t.token_type == TOKEN_TYPE_SYMBOL));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+    }
+
+    #[test]
+    fn matcher_digit_group_digit_count() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("12"))),
+            vec![
+                Box::new(IntegerMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(SymbolMatcher::default()),
+                Box::new(DigitGroupMatcher::build_digit_group_matcher(
+                    vec!['-', ' '],
+                    7,
+                    16,
+                    1,
+                )),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "12" && t.token_type == TOKEN_TYPE_INTEGER));
+        assert_eq!(lexx.ctx.get(DIGIT_GROUP_DIGITS), None);
+
+        // more than 16 digits
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("4111 1111 1111 1111 1"))),
+            vec![
+                Box::new(IntegerMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(SymbolMatcher::default()),
+                Box::new(DigitGroupMatcher::build_digit_group_matcher(
+                    vec!['-', ' '],
+                    7,
+                    16,
+                    1,
+                )),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "4111" && t.token_type == TOKEN_TYPE_INTEGER));
+
+        // two separators in a row end the match
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("555--0100"))),
+            vec![
+                Box::new(IntegerMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(SymbolMatcher::default()),
+                Box::new(DigitGroupMatcher::build_digit_group_matcher(
+                    vec!['-', ' '],
+                    7,
+                    16,
+                    1,
+                )),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "555" && t.token_type == TOKEN_TYPE_INTEGER));
+
+        // so does a different separator
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("555-0100 12"))),
+            vec![
+                Box::new(IntegerMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(SymbolMatcher::default()),
+                Box::new(DigitGroupMatcher::build_digit_group_matcher(
+                    vec!['-', ' '],
+                    7,
+                    16,
+                    1,
+                )),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "555-0100" && t.token_type == TOKEN_TYPE_DIGIT_GROUP));
+    }
+}
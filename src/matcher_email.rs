@@ -0,0 +1,284 @@
+use std::hash::BuildHasher;
+use crate::matcher::{Ctx, Matcher, MatcherResult};
+use crate::token::{Token, TOKEN_TYPE_EMAIL};
+
+/// The EmailMatcher matches email address like strings such as `user@example.com`. The local part
+/// before the `@` may contain alphanumerics and `._%+-`, the domain after it must be dot separated
+/// labels of alphanumerics and `-` with at least one dot, and must end with an alphanumeric.
+///
+/// This is NOT a full RFC 5322 validator, it is meant to find the common forms of email
+/// addresses in text. If the domain ends with a `.`, such as at the end of a sentence, the
+/// `.` is not included in the match.
+///
+/// # Example
+///
+/// ```rust
+/// use lexx::{Lexx, Lexxer};
+/// use lexx::token::{TOKEN_TYPE_EMAIL, TOKEN_TYPE_SYMBOL, TOKEN_TYPE_WHITESPACE, TOKEN_TYPE_WORD};
+/// use lexx::input::InputString;
+/// use lexx::matcher_email::EmailMatcher;
+/// use lexx::matcher_symbol::SymbolMatcher;
+/// use lexx::matcher_whitespace::WhitespaceMatcher;
+/// use lexx::matcher_word::WordMatcher;
+///
+/// let lexx_input = InputString::new(String::from("mail jo.e@example.com."));
+///
+/// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+///     Box::new(lexx_input),
+///     vec![
+///         Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] }),
+///         Box::new(WhitespaceMatcher { index: 0, column: 0, line: 0, precedence: 0, running: true, predicate: char::is_whitespace, line_continuation: None, mixed_indent: false }),
+///         Box::new(SymbolMatcher { index: 0, precedence: 0, running: true, max_len: 0 }),
+///         Box::new(EmailMatcher::build_email_matcher(0)),
+///     ]
+/// ));
+///
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "mail" && t.token_type == TOKEN_TYPE_WORD));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_WHITESPACE));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "jo.e@example.com" && t.token_type == TOKEN_TYPE_EMAIL && t.column == 6));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "." && t.token_type == TOKEN_TYPE_SYMBOL && t.column == 22));
+/// assert!(matches!(lexx.next_token(), Ok(None)));
+/// ```
+#[derive(Clone, Debug, Copy)]
+pub struct EmailMatcher {
+    /// Current size of the ongoing match.
+    pub index: usize,
+    /// This matchers precedence.
+    pub precedence: u8,
+    /// If the matcher is currently running.
+    pub running: bool,
+    /// If the `@` has been seen.
+    pub at: bool,
+    /// How many dots have been seen in the domain.
+    pub dots: usize,
+    /// The length of the longest valid email seen so far, 0 if none.
+    pub found: usize,
+}
+
+impl<S: BuildHasher> Matcher<S> for EmailMatcher {
+    fn reset(&mut self, _ctx: &mut Box<Ctx<S>>) {
+        self.index = 0;
+        self.at = false;
+        self.dots = 0;
+        self.found = 0;
+        self.running = true;
+    }
+
+    fn find_match(
+        &mut self,
+        oc: Option<char>,
+        value: &[char],
+        _ctx: &mut Box<Ctx<S>>,
+    ) -> MatcherResult {
+        let c = match oc {
+            None => return self.generate_email_token(value),
+            Some(c) => c,
+        };
+        if !self.at {
+            if c == '@' && self.index > 0 {
+                self.at = true;
+            } else if !(c.is_alphanumeric() || "._%+-".contains(c)) {
+                return self.generate_email_token(value);
+            }
+            self.index += 1;
+            return MatcherResult::Running();
+        }
+        // in the domain, labels can't start or end with a `-` or be empty
+        let prev = value[self.index - 1];
+        if c.is_alphanumeric() {
+            self.index += 1;
+            if self.dots > 0 {
+                self.found = self.index;
+            }
+        } else if c == '-' && prev != '@' && prev != '.' {
+            self.index += 1;
+        } else if c == '.' && prev.is_alphanumeric() {
+            self.index += 1;
+            self.dots += 1;
+        } else {
+            return self.generate_email_token(value);
+        }
+        MatcherResult::Running()
+    }
+    fn is_running(&self) -> bool {
+        self.running
+    }
+    fn precedence(&self) -> u8 {
+        self.precedence
+    }
+    fn describe(&self) -> String {
+        format!("EmailMatcher {{ precedence: {} }}", self.precedence)
+    }
+}
+
+impl Default for EmailMatcher {
+    /// An EmailMatcher with a precedence of 0, ready to match.
+    fn default() -> Self {
+        EmailMatcher {
+            index: 0,
+            precedence: 0,
+            running: true,
+            at: false,
+            dots: 0,
+            found: 0,
+        }
+    }
+}
+
+impl EmailMatcher {
+    /// Build an email matcher
+    ///
+    /// # Arguments
+    ///
+    /// * `precedence` - the precedence for this matcher
+    ///
+    pub fn build_email_matcher(precedence: u8) -> EmailMatcher {
+        EmailMatcher {
+            index: 0,
+            precedence,
+            running: true,
+            at: false,
+            dots: 0,
+            found: 0,
+        }
+    }
+
+    #[inline(always)]
+    fn generate_email_token(&mut self, value: &[char]) -> MatcherResult {
+        self.running = false;
+        if self.found > 0 {
+            MatcherResult::Matched(Token {
+                value: value[0..self.found].iter().collect(),
+                token_type: TOKEN_TYPE_EMAIL,
+                len: self.found,
+                line: 0,
+                column: self.found,
+                precedence: self.precedence,
+                ..Default::default()
+            })
+        } else {
+            MatcherResult::Failed()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::InputString;
+    use crate::matcher_email::EmailMatcher;
+    use crate::matcher_symbol::SymbolMatcher;
+    use crate::matcher_whitespace::WhitespaceMatcher;
+    use crate::matcher_word::WordMatcher;
+    use crate::token::{TOKEN_TYPE_EMAIL, TOKEN_TYPE_SYMBOL, TOKEN_TYPE_WHITESPACE, TOKEN_TYPE_WORD};
+    use crate::{Lexx, Lexxer};
+
+    #[test]
+    fn matcher_email_matches_email() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("first.last+tag@mail.example-site.com"))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(SymbolMatcher::default()),
+                Box::new(EmailMatcher::build_email_matcher(0)),
+            ],
+        );
+
+        assert!(
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "first.last+tag@mail.example-site.com" && t.token_type == TOKEN_TYPE_EMAIL && t.len == 36)
+        );
+        assert!(matches!(lexx.next_token(), Ok(None)));
+    }
+
+    #[test]
+    fn matcher_email_does_not_include_trailing_dot() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("user@example.com. next"))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(SymbolMatcher::default()),
+                Box::new(EmailMatcher::build_email_matcher(0)),
+            ],
+        );
+
+        assert!(
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "user@example.com" && t.token_type == TOKEN_TYPE_EMAIL)
+        );
+        assert!(
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "." && t.token_type == TOKEN_TYPE_SYMBOL && t.column == 17)
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_WHITESPACE));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "next" && t.column == 19));
+    }
+
+    #[test]
+    fn matcher_email_rejects_partial_emails() {
+        // no local part
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("@example.com"))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(SymbolMatcher::default()),
+                Box::new(EmailMatcher::build_email_matcher(0)),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "@" && t.token_type == TOKEN_TYPE_SYMBOL));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "example" && t.token_type == TOKEN_TYPE_WORD));
+
+        // no domain
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("user@ x"))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(SymbolMatcher::default()),
+                Box::new(EmailMatcher::build_email_matcher(0)),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "user" && t.token_type == TOKEN_TYPE_WORD));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "@" && t.token_type == TOKEN_TYPE_SYMBOL));
+
+        // a domain without a dot
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("user@localhost"))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(SymbolMatcher::default()),
+                Box::new(EmailMatcher::build_email_matcher(0)),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "user" && t.token_type == TOKEN_TYPE_WORD));
+
+        // a bare @
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("@"))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(SymbolMatcher::default()),
+                Box::new(EmailMatcher::build_email_matcher(0)),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "@" && t.token_type == TOKEN_TYPE_SYMBOL));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+    }
+
+    #[test]
+    fn matcher_email_default() {
+        let explicit = EmailMatcher {
+            index: 0,
+            precedence: 0,
+            running: true,
+            at: false,
+            dots: 0,
+            found: 0,
+        };
+        assert_eq!(
+            format!("{:?}", EmailMatcher::default()),
+            format!("{:?}", explicit)
+        );
+    }
+}
@@ -1,6 +1,6 @@
-use crate::matcher::{Matcher, MatcherResult};
-use crate::token::Token;
-use std::collections::HashMap;
+use std::hash::BuildHasher;
+use crate::matcher::{Ctx, Matcher, MatcherResult};
+use crate::token::{Token, TOKEN_TYPE_EXACT};
 
 /// An exact match to be made
 #[derive(Clone, Debug)]
@@ -9,6 +9,9 @@ pub struct Target {
     pub matching: bool,
     /// What this match is
     pub target: Box<Vec<char>>,
+    /// The token type to return if this match is made, when [None] the
+    /// [ExactMatcher::token_type] is used.
+    pub token_type: Option<u16>,
 }
 
 /// The Exact matcher does exactly what you'd expect. You give it a list of strings to match against
@@ -28,7 +31,7 @@ pub struct Target {
 /// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
 ///     Box::new(lexx_input),
 ///     vec![
-///         Box::new(SymbolMatcher { index: 0, precedence: 0, running: true }),
+///         Box::new(SymbolMatcher { index: 0, precedence: 0, running: true, max_len: 0 }),
 ///         // Note the precedence of 1 will cause the ExactMatcher to be be returned
 ///         // when the SymbolMatcher would have matched the same, or a longer thing.
 ///         Box::new(ExactMatcher::build_exact_matcher(vec!["^", "$gxv ", "gxv ", "llj)9", "d$rrr"], TOKEN_TYPE_EXACT, 1)),
@@ -65,8 +68,8 @@ pub struct ExactMatcher {
     pub token_type: u16,
 }
 
-impl Matcher for ExactMatcher {
-    fn reset(&mut self, _ctx: &mut Box<HashMap<String, i32>>) {
+impl<S: BuildHasher> Matcher<S> for ExactMatcher {
+    fn reset(&mut self, _ctx: &mut Box<Ctx<S>>) {
         for t in self.targets.iter_mut() {
             t.matching = true
         }
@@ -79,7 +82,7 @@ impl Matcher for ExactMatcher {
         &mut self,
         oc: Option<char>,
         _value: &[char],
-        _ctx: &mut Box<HashMap<String, i32>>,
+        _ctx: &mut Box<Ctx<S>>,
     ) -> MatcherResult {
         return match oc {
             None => {
@@ -95,6 +98,10 @@ impl Matcher for ExactMatcher {
             }
             Some(c) => {
                 self.running = false;
+                // once every target has stopped matching there is nothing left to check
+                if !self.targets.iter().any(|t| t.matching) {
+                    return self.generate_exact_token();
+                }
                 let mut i: usize = 0;
                 for target in self.targets.iter_mut() {
                     if target.matching {
@@ -131,6 +138,34 @@ impl Matcher for ExactMatcher {
     fn precedence(&self) -> u8 {
         self.precedence
     }
+    fn describe(&self) -> String {
+        let targets: Vec<String> = self
+            .targets
+            .iter()
+            .map(|t| {
+                let target: String = t.target.iter().collect();
+                match t.token_type {
+                    Some(token_type) => format!("{:?} as {}", target, token_type),
+                    None => format!("{:?}", target),
+                }
+            })
+            .collect();
+        format!(
+            "ExactMatcher {{ precedence: {}, token_type: {}, targets: [{}] }}",
+            self.precedence,
+            self.token_type,
+            targets.join(", ")
+        )
+    }
+    fn validate(&self) -> Result<(), String> {
+        if self.targets.is_empty() {
+            return Err("ExactMatcher has no targets".to_string());
+        }
+        if self.targets.iter().any(|t| t.target.is_empty()) {
+            return Err("ExactMatcher has an empty target".to_string());
+        }
+        Ok(())
+    }
 }
 
 impl ExactMatcher {
@@ -152,6 +187,7 @@ impl ExactMatcher {
             let mut target = Target {
                 matching: true,
                 target: Box::new(vec![]),
+                token_type: None,
             };
             for c in m.chars() {
                 target.target.push(c)
@@ -168,22 +204,67 @@ impl ExactMatcher {
         }
     }
 
+    /// Build an exact matcher where each of the strings has its own token type, such as a table
+    /// of operators. The [ExactMatcher::token_type] is set to [TOKEN_TYPE_EXACT] but is never
+    /// used since every target has its own.
+    ///
+    /// # Arguments
+    ///
+    /// * `matches` - the [&str](std::str)s that will be matched with the token type to produce
+    ///   for each
+    /// * `precedence` - the precedence for this matcher
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lexx::{Lexx, Lexxer};
+    /// use lexx::input::InputString;
+    /// use lexx::matcher_exact::ExactMatcher;
+    ///
+    /// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+    ///     Box::new(InputString::new(String::from("==+="))),
+    ///     vec![Box::new(ExactMatcher::build_exact_matcher_with_types(
+    ///         &[("==", 10), ("+", 11), ("=", 12)],
+    ///         0,
+    ///     ))],
+    /// ));
+    ///
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "==" && t.token_type == 10));
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "+" && t.token_type == 11));
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "=" && t.token_type == 12));
+    /// ```
+    pub fn build_exact_matcher_with_types(
+        matches: &[(&str, u16)],
+        precedence: u8,
+    ) -> ExactMatcher {
+        let mut matcher = ExactMatcher::build_exact_matcher(
+            matches.iter().map(|(m, _)| *m).collect(),
+            TOKEN_TYPE_EXACT,
+            precedence,
+        );
+        for (target, (_, token_type)) in matcher.targets.iter_mut().zip(matches) {
+            target.token_type = Some(*token_type);
+        }
+        matcher
+    }
+
     #[inline(always)]
     fn generate_exact_token(&mut self) -> MatcherResult {
         match self.found {
             None => MatcherResult::Failed(),
             Some(_) => {
                 let i = self.found.unwrap();
-                let target = &self.targets.get(i).unwrap().target;
-                let token_value: String = target.clone().into_iter().collect();
+                let target = self.targets.get(i).unwrap();
+                let token_value: String = target.target.clone().into_iter().collect();
                 let len = token_value.len();
                 MatcherResult::Matched(Token {
                     value: token_value,
-                    token_type: self.token_type,
+                    token_type: target.token_type.unwrap_or(self.token_type),
                     len,
                     line: 0,
                     column: len,
                     precedence: self.precedence,
+                    ..Default::default()
                 })
             }
         }
@@ -214,7 +295,7 @@ mod tests {
                 LexxError::TokenNotFound(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
-                LexxError::Error(_) => {
+                LexxError::Error(_) | LexxError::BufferOverflow(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
             },
@@ -245,6 +326,9 @@ mod tests {
                     line: 0,
                     precedence: 0,
                     running: true,
+                    predicate: char::is_whitespace,
+                    line_continuation: None,
+                    mixed_indent: false,
                 }),
             ],
         );
@@ -263,7 +347,7 @@ mod tests {
                 LexxError::TokenNotFound(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
-                LexxError::Error(_) => {
+                LexxError::Error(_) | LexxError::BufferOverflow(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
             },
@@ -299,6 +383,9 @@ mod tests {
                     line: 0,
                     precedence: 0,
                     running: true,
+                    predicate: char::is_whitespace,
+                    line_continuation: None,
+                    mixed_indent: false,
                 }),
             ],
         );
@@ -324,14 +411,14 @@ mod tests {
                 LexxError::TokenNotFound(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
-                LexxError::Error(_) => {
+                LexxError::Error(_) | LexxError::BufferOverflow(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
             },
             Ok(Some(t)) => {
                 assert_eq!(t.value, "dog");
-                assert_eq!(t.line, 1);
-                assert_eq!(t.column, 42);
+                assert_eq!(t.line, 4);
+                assert_eq!(t.column, 15);
             }
             Ok(None) => {
                 assert!(false, "Should not hit None");
@@ -355,7 +442,7 @@ mod tests {
                 LexxError::TokenNotFound(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
-                LexxError::Error(_) => {
+                LexxError::Error(_) | LexxError::BufferOverflow(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
             },
@@ -369,6 +456,37 @@ mod tests {
         }
     }
 
+    #[test]
+    fn matcher_exact_stops_when_no_target_matches() {
+        use crate::matcher::{Ctx, Matcher, MatcherResult};
+
+        let mut ctx: Box<Ctx> = Box::default();
+        let mut matcher = ExactMatcher::build_exact_matcher(vec!["abc", "abd"], TOKEN_TYPE_EXACT, 0);
+        matcher.reset(&mut ctx);
+
+        let long: Vec<char> = "ab".chars().chain(std::iter::repeat_n('x', 10000)).collect();
+        assert!(matches!(matcher.find_match(Some('a'), &long[0..1], &mut ctx), MatcherResult::Running()));
+        assert!(matches!(matcher.find_match(Some('b'), &long[0..2], &mut ctx), MatcherResult::Running()));
+        for i in 2..long.len() {
+            assert!(matches!(
+                matcher.find_match(Some(long[i]), &long[0..=i], &mut ctx),
+                MatcherResult::Failed()
+            ));
+            assert!(!<ExactMatcher as Matcher>::is_running(&matcher));
+            assert!(matcher.targets.iter().all(|t| !t.matching));
+        }
+        // the index stops moving once nothing is matching
+        assert_eq!(matcher.index, 3);
+
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("abcabd"))),
+            vec![Box::new(matcher)],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "abc"));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "abd" && t.column == 4));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+    }
+
     #[test]
     fn example_test() {
         use crate::matcher_exact::ExactMatcher;
@@ -385,6 +503,7 @@ mod tests {
                     index: 0,
                     precedence: 0,
                     running: true,
+                    max_len: 0,
                 }),
                 // Note the precedence of 1 will cause the ExactMatcher to be be returned when
                 // when the SymbolMatcher would have matched the same thing.
@@ -419,4 +538,56 @@ mod tests {
             matches!(lexx.next_token(), Ok(Some(t)) if t.value == "d$rrr" && t.token_type == TOKEN_TYPE_EXACT && t.line == 1 && t.column == 15)
         );
     }
+
+    #[test]
+    fn matcher_exact_with_types() {
+        use crate::token::TOKEN_TYPE_WHITESPACE;
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("a == b + c"))),
+            vec![
+                Box::new(ExactMatcher::build_exact_matcher_with_types(
+                    &[("==", 10), ("+", 11)],
+                    0,
+                )),
+                Box::new(ExactMatcher::build_exact_matcher(
+                    vec!["a", "b", "c"],
+                    TOKEN_TYPE_EXACT,
+                    0,
+                )),
+                Box::new(WhitespaceMatcher::with_predicate(char::is_whitespace, 0)),
+            ],
+        );
+
+        let types: Vec<(String, u16)> = lexx
+            .by_ref()
+            .filter(|t| t.token_type != TOKEN_TYPE_WHITESPACE)
+            .map(|t| (t.value, t.token_type))
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                (String::from("a"), TOKEN_TYPE_EXACT),
+                (String::from("=="), 10),
+                (String::from("b"), TOKEN_TYPE_EXACT),
+                (String::from("+"), 11),
+                (String::from("c"), TOKEN_TYPE_EXACT),
+            ]
+        );
+    }
+
+    #[test]
+    fn matcher_exact_describe() {
+        use crate::matcher::Matcher;
+
+        let matcher = ExactMatcher::build_exact_matcher(vec!["if", "else"], TOKEN_TYPE_EXACT, 3);
+        assert_eq!(
+            <ExactMatcher as Matcher>::describe(&matcher),
+            "ExactMatcher { precedence: 3, token_type: 6, targets: [\"if\", \"else\"] }"
+        );
+
+        let matcher = ExactMatcher::build_exact_matcher_with_types(&[("==", 10), ("+", 11)], 0);
+        let description = <ExactMatcher as Matcher>::describe(&matcher);
+        assert!(description.contains("precedence: 0"), "{}", description);
+        assert!(description.contains("\"==\" as 10, \"+\" as 11"), "{}", description);
+    }
 }
@@ -1,11 +1,21 @@
-use crate::matcher::{Matcher, MatcherResult};
+use std::hash::BuildHasher;
+use crate::matcher::{Ctx, Matcher, MatcherResult};
 use crate::token::{Token, TOKEN_TYPE_FLOAT};
-use std::collections::HashMap;
 
 /// The float matcher matches floating point numbers. To qualify as floating point the numbers must
 /// start and end with a numeric digit and have a period within them. For example `1.0`. Thus
 /// `.1` and `1.` do not qualify as floating point numbers.
 ///
+/// If [FloatMatcher::lenient_trailing_dot] is set then `1.` is matched as a float as well, unless
+/// the dot is followed by another dot. That keeps range syntax like `1..2` working, but be aware
+/// that in lenient mode `1.` can still swallow the dot of something like a method call in `1.max(2)`.
+///
+/// If [FloatMatcher::exponent] is set a float may end with an exponent, an `e` or `E` then an
+/// optional `+` or `-` and at least one digit, as in `6.022e23` or `1.5E-3`. If
+/// [FloatMatcher::integer_mantissa] is set as well the number before the exponent may be an
+/// integer, so `1e10` is a float. An exponent with no digits is left out of the match, so `1.5e`
+/// matches `1.5` and leaves the `e` for the other matchers.
+///
 /// # Example
 ///
 /// ```rust
@@ -20,10 +30,10 @@ use std::collections::HashMap;
 /// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
 /// Box::new(InputString::new(String::from("1.0 5 0.012345 .9 00.00 100.0 4."))),
 /// vec![
-///     Box::new(FloatMatcher{ index: 0, precedence: 0, dot: false, float:false, running: true }),
-///     Box::new(SymbolMatcher { index: 0, precedence: 0, running: true }),
-///     Box::new(IntegerMatcher { index: 0, precedence: 0, running: true }),
-///     Box::new(WhitespaceMatcher { index: 0, column: 0,line: 0,precedence: 0, running: true}),
+///     Box::new(FloatMatcher{ index: 0, precedence: 0, dot: false, float:false, running: true, lenient_trailing_dot: false, exponent: false, integer_mantissa: false }),
+///     Box::new(SymbolMatcher { index: 0, precedence: 0, running: true, max_len: 0 }),
+///     Box::new(IntegerMatcher { index: 0, precedence: 0, running: true, radix_prefixes: false }),
+///     Box::new(WhitespaceMatcher { index: 0, column: 0,line: 0,precedence: 0, running: true, predicate: char::is_whitespace, line_continuation: None, mixed_indent: false}),
 /// ]
 /// ));
 ///
@@ -56,10 +66,17 @@ pub struct FloatMatcher {
     pub float: bool,
     /// If the matcher is currently running.
     pub running: bool,
+    /// If a number ending with a dot, such as `1.`, should match as a float.
+    pub lenient_trailing_dot: bool,
+    /// If a float may end with an exponent such as `e10`.
+    pub exponent: bool,
+    /// If an integer followed by an exponent, such as `1e10`, is a float, only used along with
+    /// [FloatMatcher::exponent].
+    pub integer_mantissa: bool,
 }
 
-impl Matcher for FloatMatcher {
-    fn reset(&mut self, _ctx: &mut Box<HashMap<String, i32>>) {
+impl<S: BuildHasher> Matcher<S> for FloatMatcher {
+    fn reset(&mut self, _ctx: &mut Box<Ctx<S>>) {
         self.index = 0;
         self.dot = false;
         self.float = false;
@@ -70,11 +87,34 @@ impl Matcher for FloatMatcher {
         &mut self,
         oc: Option<char>,
         value: &[char],
-        _ctx: &mut Box<HashMap<String, i32>>,
+        _ctx: &mut Box<Ctx<S>>,
     ) -> MatcherResult {
         return match oc {
-            None => self.generate_float_token(value),
+            None => {
+                self.accept_trailing_dot(None);
+                self.generate_float_token(value)
+            }
             Some(c) => {
+                if let Some(e) = self.exponent_start(value) {
+                    let sign = (c == '+' || c == '-') && self.index == e + 1;
+                    if sign || c.is_ascii_digit() {
+                        self.index += 1;
+                        if !sign {
+                            self.float = true;
+                        }
+                        return MatcherResult::Running();
+                    }
+                    return self.generate_float_token(value);
+                }
+                if (c == 'e' || c == 'E')
+                    && self.exponent
+                    && self.index > 0
+                    && value[self.index - 1].is_numeric()
+                    && (self.float || (self.integer_mantissa && !self.dot))
+                {
+                    self.index += 1;
+                    return MatcherResult::Running();
+                }
                 if c == '.' && !self.dot && self.index > 0 {
                     self.index += 1;
                     self.dot = true;
@@ -87,6 +127,7 @@ impl Matcher for FloatMatcher {
                     }
                     MatcherResult::Running()
                 } else {
+                    self.accept_trailing_dot(Some(c));
                     self.generate_float_token(value)
                 }
             }
@@ -98,23 +139,303 @@ impl Matcher for FloatMatcher {
     fn precedence(&self) -> u8 {
         self.precedence
     }
+    fn describe(&self) -> String {
+        format!(
+            "FloatMatcher {{ precedence: {}, lenient_trailing_dot: {}, exponent: {}, integer_mantissa: {} }}",
+            self.precedence, self.lenient_trailing_dot, self.exponent, self.integer_mantissa
+        )
+    }
+}
+
+impl Default for FloatMatcher {
+    /// A FloatMatcher with a precedence of 0, ready to match.
+    fn default() -> Self {
+        FloatMatcher {
+            index: 0,
+            precedence: 0,
+            dot: false,
+            float: false,
+            running: true,
+            lenient_trailing_dot: false,
+            exponent: false,
+            integer_mantissa: false,
+        }
+    }
 }
 
 impl FloatMatcher {
+    /// A FloatMatcher that also matches floats with an exponent, such as `6.022e23`
+    ///
+    /// # Arguments
+    ///
+    /// * `integer_mantissa` - if an integer with an exponent, such as `1e10`, is a float too
+    /// * `precedence` - the precedence for this matcher
+    ///
+    pub fn with_exponent(integer_mantissa: bool, precedence: u8) -> FloatMatcher {
+        FloatMatcher {
+            index: 0,
+            precedence,
+            dot: false,
+            float: false,
+            running: true,
+            lenient_trailing_dot: false,
+            exponent: true,
+            integer_mantissa,
+        }
+    }
+
+    /// Where the `e` of the exponent is in the match, if there is one yet.
+    #[inline(always)]
+    fn exponent_start(&self, value: &[char]) -> Option<usize> {
+        if !self.exponent {
+            return None;
+        }
+        value[0..self.index]
+            .iter()
+            .position(|c| *c == 'e' || *c == 'E')
+    }
+
+    /// In lenient mode a match that ended right after the dot still counts as a float, as long as
+    /// the next char isn't another dot.
+    #[inline(always)]
+    fn accept_trailing_dot(&mut self, oc: Option<char>) {
+        if self.lenient_trailing_dot && self.dot && !self.float && oc != Some('.') {
+            self.float = true;
+        }
+    }
+
     #[inline(always)]
     fn generate_float_token(&mut self, value: &[char]) -> MatcherResult {
         self.running = false;
+        if let Some(e) = self.exponent_start(value) {
+            if !value[self.index - 1].is_ascii_digit() {
+                // an exponent with no digits isn't part of the float
+                self.index = e;
+            }
+        }
         if self.index > 0 && self.float {
             MatcherResult::Matched(Token {
-                value: value[0..self.index].into_iter().collect(),
+                value: value[0..self.index].iter().collect(),
                 token_type: TOKEN_TYPE_FLOAT,
                 len: self.index,
                 line: 0,
                 column: self.index,
                 precedence: self.precedence,
+                ..Default::default()
             })
         } else {
             MatcherResult::Failed()
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::input::InputString;
+    use crate::matcher_float::FloatMatcher;
+    use crate::matcher_integer::IntegerMatcher;
+    use crate::matcher_symbol::SymbolMatcher;
+    use crate::token::{TOKEN_TYPE_FLOAT, TOKEN_TYPE_INTEGER, TOKEN_TYPE_SYMBOL};
+    use crate::{Lexx, Lexxer};
+
+    #[test]
+    fn matcher_float_trailing_dot() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("1."))),
+            vec![
+                Box::new(FloatMatcher::default()),
+                Box::new(IntegerMatcher::default()),
+                Box::new(SymbolMatcher::default()),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "1" && t.token_type == TOKEN_TYPE_INTEGER));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "." && t.token_type == TOKEN_TYPE_SYMBOL));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("1."))),
+            vec![
+                Box::new(FloatMatcher {
+                    lenient_trailing_dot: true,
+                    ..FloatMatcher::default()
+                }),
+                Box::new(IntegerMatcher::default()),
+                Box::new(SymbolMatcher::default()),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "1." && t.token_type == TOKEN_TYPE_FLOAT && t.len == 2));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("12.+"))),
+            vec![
+                Box::new(FloatMatcher {
+                    lenient_trailing_dot: true,
+                    ..FloatMatcher::default()
+                }),
+                Box::new(IntegerMatcher::default()),
+                Box::new(SymbolMatcher::default()),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "12." && t.token_type == TOKEN_TYPE_FLOAT));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "+" && t.token_type == TOKEN_TYPE_SYMBOL));
+    }
+
+    #[test]
+    fn matcher_float_lenient_keeps_ranges() {
+        for lenient in [false, true] {
+            let mut lexx = Lexx::<512>::new(
+                Box::new(InputString::new(String::from("1..2"))),
+                vec![
+                    Box::new(FloatMatcher {
+                        lenient_trailing_dot: lenient,
+                        ..FloatMatcher::default()
+                    }),
+                    Box::new(IntegerMatcher::default()),
+                    Box::new(SymbolMatcher::default()),
+                ],
+            );
+            assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "1" && t.token_type == TOKEN_TYPE_INTEGER));
+            assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == ".." && t.token_type == TOKEN_TYPE_SYMBOL));
+            assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "2" && t.token_type == TOKEN_TYPE_INTEGER));
+            assert!(matches!(lexx.next_token(), Ok(None)));
+        }
+    }
+
+    #[test]
+    fn matcher_float_dot_followed_by_non_digit() {
+        use crate::matcher_word::WordMatcher;
+        use crate::token::TOKEN_TYPE_WORD;
+
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from(""))),
+            vec![
+                Box::new(FloatMatcher::default()),
+                Box::new(IntegerMatcher::default()),
+                Box::new(SymbolMatcher::default()),
+            ],
+        );
+        lexx.push_matcher(
+            Box::new(WordMatcher {
+                index: 0,
+                precedence: 0,
+                running: true,
+                trailing: &[],
+            }),
+            None,
+        );
+        lexx.set_input(Box::new(InputString::new(String::from("1.x;22.yz"))));
+
+        // the FloatMatcher read "1.x" before failing, the "." and "x" must go back to the cache
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "1" && t.token_type == TOKEN_TYPE_INTEGER && t.column == 1));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "." && t.token_type == TOKEN_TYPE_SYMBOL && t.column == 2));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "x" && t.token_type == TOKEN_TYPE_WORD && t.column == 3));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == ";" && t.column == 4));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "22" && t.token_type == TOKEN_TYPE_INTEGER && t.column == 5));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "." && t.token_type == TOKEN_TYPE_SYMBOL && t.column == 7));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "yz" && t.token_type == TOKEN_TYPE_WORD && t.column == 8));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+    }
+
+    #[test]
+    fn matcher_float_exponent() {
+        use crate::matcher_word::WordMatcher;
+        use crate::token::TOKEN_TYPE_WORD;
+
+        let floats = |text: &str, integer_mantissa: bool| {
+            let mut lexx = Lexx::<512>::new(
+                Box::new(InputString::new(String::from(text))),
+                vec![
+                    Box::new(FloatMatcher::with_exponent(integer_mantissa, 0)),
+                    Box::new(IntegerMatcher::default()),
+                    Box::new(SymbolMatcher::with_max_len(1, 0)),
+                    Box::new(WordMatcher::default()),
+                ],
+            );
+            let tokens: Vec<(String, u16)> = lexx
+                .by_ref()
+                .filter(|t| t.value != ";")
+                .map(|t| (t.value, t.token_type))
+                .collect();
+            tokens
+        };
+        let float = |v: &str| (String::from(v), TOKEN_TYPE_FLOAT);
+        let other = |v: &str, t: u16| (String::from(v), t);
+
+        assert_eq!(
+            floats("1.5e10;6.022E23;1.5e-3;2.0e+7;1.5e;1.5e+x", false),
+            vec![
+                float("1.5e10"),
+                float("6.022E23"),
+                float("1.5e-3"),
+                float("2.0e+7"),
+                // no digits in the exponent
+                float("1.5"),
+                other("e", TOKEN_TYPE_WORD),
+                float("1.5"),
+                other("e", TOKEN_TYPE_WORD),
+                other("+", TOKEN_TYPE_SYMBOL),
+                other("x", TOKEN_TYPE_WORD),
+            ]
+        );
+        // an integer mantissa needs the flag
+        assert_eq!(
+            floats("1e10", false),
+            vec![
+                other("1", TOKEN_TYPE_INTEGER),
+                other("e", TOKEN_TYPE_WORD),
+                other("10", TOKEN_TYPE_INTEGER),
+            ]
+        );
+        assert_eq!(
+            floats("1e10;1E-2;1e;7", true),
+            vec![
+                float("1e10"),
+                float("1E-2"),
+                other("1", TOKEN_TYPE_INTEGER),
+                other("e", TOKEN_TYPE_WORD),
+                other("7", TOKEN_TYPE_INTEGER),
+            ]
+        );
+        // the exponent has no dot and there's just the one
+        assert_eq!(
+            floats("1.5e2.5;1e2e3", true),
+            vec![
+                float("1.5e2"),
+                other(".", TOKEN_TYPE_SYMBOL),
+                other("5", TOKEN_TYPE_INTEGER),
+                float("1e2"),
+                other("e", TOKEN_TYPE_WORD),
+                other("3", TOKEN_TYPE_INTEGER),
+            ]
+        );
+        // .9 and 4. are still not floats
+        assert_eq!(
+            floats(".9;4.", true),
+            vec![
+                other(".", TOKEN_TYPE_SYMBOL),
+                other("9", TOKEN_TYPE_INTEGER),
+                other("4", TOKEN_TYPE_INTEGER),
+                other(".", TOKEN_TYPE_SYMBOL),
+            ]
+        );
+    }
+
+    #[test]
+    fn matcher_float_default() {
+        let explicit = FloatMatcher {
+            index: 0,
+            precedence: 0,
+            dot: false,
+            float: false,
+            running: true,
+            lenient_trailing_dot: false,
+            exponent: false,
+            integer_mantissa: false,
+        };
+        assert_eq!(
+            format!("{:?}", FloatMatcher::default()),
+            format!("{:?}", explicit)
+        );
+    }
+}
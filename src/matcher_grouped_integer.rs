@@ -0,0 +1,177 @@
+use std::hash::BuildHasher;
+use crate::matcher::{Ctx, Matcher, MatcherResult};
+use crate::token::{Token, TOKEN_TYPE_INTEGER};
+
+/// The GroupedIntegerMatcher matches integers written with their digits grouped by single spaces,
+/// as accounting formats often write thousands, such as `1 234 567`. A space only belongs to the
+/// number when there is a digit on both sides of it, so a double space or a trailing space ends
+/// the number. The [Token] value has the spaces removed while its `len` still counts them.
+///
+/// # Example
+///
+/// ```rust
+/// use lexx::{Lexx, Lexxer};
+/// use lexx::token::{TOKEN_TYPE_INTEGER, TOKEN_TYPE_WHITESPACE};
+/// use lexx::input::InputString;
+/// use lexx::matcher_grouped_integer::GroupedIntegerMatcher;
+/// use lexx::matcher_whitespace::WhitespaceMatcher;
+///
+/// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+///     Box::new(InputString::new(String::from("1 234 567  89"))),
+///     vec![
+///         Box::new(GroupedIntegerMatcher { index: 0, precedence: 0, running: true, space: false }),
+///         Box::new(WhitespaceMatcher::with_predicate(char::is_whitespace, 0)),
+///     ]
+/// ));
+///
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "1234567" && t.token_type == TOKEN_TYPE_INTEGER && t.len == 9));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "  " && t.token_type == TOKEN_TYPE_WHITESPACE && t.column == 10));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "89" && t.token_type == TOKEN_TYPE_INTEGER && t.column == 12));
+/// assert!(matches!(lexx.next_token(), Ok(None)));
+/// ```
+#[derive(Clone, Debug, Copy)]
+pub struct GroupedIntegerMatcher {
+    /// Current size of the ongoing match.
+    pub index: usize,
+    /// This matchers precedence.
+    pub precedence: u8,
+    /// If the matcher is currently running.
+    pub running: bool,
+    /// If the last char was a space between digit groups.
+    pub space: bool,
+}
+
+impl<S: BuildHasher> Matcher<S> for GroupedIntegerMatcher {
+    fn reset(&mut self, _ctx: &mut Box<Ctx<S>>) {
+        Matcher::<S>::reset_light(self);
+    }
+
+    fn is_stateful(&self) -> bool {
+        false
+    }
+
+    fn reset_light(&mut self) {
+        self.index = 0;
+        self.space = false;
+        self.running = true;
+    }
+
+    fn find_match(
+        &mut self,
+        oc: Option<char>,
+        value: &[char],
+        _ctx: &mut Box<Ctx<S>>,
+    ) -> MatcherResult {
+        match oc {
+            Some(c) if c.is_numeric() => {
+                self.index += 1;
+                self.space = false;
+                MatcherResult::Running()
+            }
+            Some(' ') if self.index > 0 && !self.space => {
+                self.index += 1;
+                self.space = true;
+                MatcherResult::Running()
+            }
+            _ => {
+                // a space not followed by a digit isn't part of the number
+                if self.space {
+                    self.index -= 1;
+                }
+                self.generate_grouped_integer_token(value)
+            }
+        }
+    }
+    fn is_running(&self) -> bool {
+        self.running
+    }
+    fn precedence(&self) -> u8 {
+        self.precedence
+    }
+    fn describe(&self) -> String {
+        format!("GroupedIntegerMatcher {{ precedence: {} }}", self.precedence)
+    }
+}
+
+impl Default for GroupedIntegerMatcher {
+    /// A GroupedIntegerMatcher with a precedence of 0, ready to match.
+    fn default() -> Self {
+        GroupedIntegerMatcher {
+            index: 0,
+            precedence: 0,
+            running: true,
+            space: false,
+        }
+    }
+}
+
+impl GroupedIntegerMatcher {
+    #[inline(always)]
+    fn generate_grouped_integer_token(&mut self, value: &[char]) -> MatcherResult {
+        self.running = false;
+        if self.index > 0 {
+            MatcherResult::Matched(Token {
+                value: value[0..self.index].iter().filter(|c| **c != ' ').collect(),
+                token_type: TOKEN_TYPE_INTEGER,
+                len: self.index,
+                line: 0,
+                column: self.index,
+                precedence: self.precedence,
+                ..Default::default()
+            })
+        } else {
+            MatcherResult::Failed()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::InputString;
+    use crate::matcher_grouped_integer::GroupedIntegerMatcher;
+    use crate::matcher_whitespace::WhitespaceMatcher;
+    use crate::token::{TOKEN_TYPE_INTEGER, TOKEN_TYPE_WHITESPACE};
+    use crate::{Lexx, Lexxer};
+
+    #[test]
+    fn matcher_grouped_integer_groups() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("1 234 567"))),
+            vec![
+                Box::new(GroupedIntegerMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "1234567" && t.token_type == TOKEN_TYPE_INTEGER && t.len == 9 && t.end_byte == 9));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+    }
+
+    #[test]
+    fn matcher_grouped_integer_stops_at_double_space() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("1  234"))),
+            vec![
+                Box::new(GroupedIntegerMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "1" && t.token_type == TOKEN_TYPE_INTEGER && t.len == 1));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "  " && t.token_type == TOKEN_TYPE_WHITESPACE && t.column == 2));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "234" && t.column == 4));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+    }
+
+    #[test]
+    fn matcher_grouped_integer_stops_at_trailing_space() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("1 "))),
+            vec![
+                Box::new(GroupedIntegerMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "1" && t.token_type == TOKEN_TYPE_INTEGER && t.len == 1));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == " " && t.token_type == TOKEN_TYPE_WHITESPACE && t.column == 2));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+    }
+}
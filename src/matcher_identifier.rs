@@ -0,0 +1,185 @@
+use std::hash::BuildHasher;
+use crate::matcher::{Ctx, Matcher, MatcherResult};
+use crate::token::{Token, TOKEN_TYPE_IDENTIFIER};
+
+/// The IdentifierMatcher matches identifiers as most programming languages write them, such as
+/// `foo_bar2`. The first [char] must be alphabetic or `_` and the rest may be alphanumeric or `_`,
+/// so `_` on its own is an identifier. A [char] such as a digit that can't start an identifier
+/// doesn't start a match, so in `2foo` the `2` is left to the other matchers and `foo` is an
+/// identifier.
+///
+/// # Example
+///
+/// ```rust
+/// use lexx::{Lexx, Lexxer};
+/// use lexx::token::{TOKEN_TYPE_IDENTIFIER, TOKEN_TYPE_INTEGER, TOKEN_TYPE_SYMBOL};
+/// use lexx::input::InputString;
+/// use lexx::matcher_identifier::IdentifierMatcher;
+/// use lexx::matcher_integer::IntegerMatcher;
+/// use lexx::matcher_symbol::SymbolMatcher;
+///
+/// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+///     Box::new(InputString::new(String::from("foo_bar2=_+2foo"))),
+///     vec![
+///         Box::new(SymbolMatcher::with_max_len(1, 0)),
+///         Box::new(IntegerMatcher::default()),
+///         // after the SymbolMatcher so it wins the tie over "_"
+///         Box::new(IdentifierMatcher::build_identifier_matcher(0)),
+///     ]
+/// ));
+///
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "foo_bar2" && t.token_type == TOKEN_TYPE_IDENTIFIER));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "=" && t.token_type == TOKEN_TYPE_SYMBOL));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "_" && t.token_type == TOKEN_TYPE_IDENTIFIER));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "+" && t.token_type == TOKEN_TYPE_SYMBOL));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "2" && t.token_type == TOKEN_TYPE_INTEGER));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "foo" && t.token_type == TOKEN_TYPE_IDENTIFIER && t.column == 13));
+/// assert!(matches!(lexx.next_token(), Ok(None)));
+/// ```
+#[derive(Clone, Debug, Copy)]
+pub struct IdentifierMatcher {
+    /// Current size of the ongoing match.
+    pub index: usize,
+    /// This matchers precedence.
+    pub precedence: u8,
+    /// If the matcher is currently running.
+    pub running: bool,
+}
+
+impl<S: BuildHasher> Matcher<S> for IdentifierMatcher {
+    fn reset(&mut self, _ctx: &mut Box<Ctx<S>>) {
+        Matcher::<S>::reset_light(self);
+    }
+
+    fn is_stateful(&self) -> bool {
+        false
+    }
+
+    fn reset_light(&mut self) {
+        self.index = 0;
+        self.running = true;
+    }
+
+    fn find_match(
+        &mut self,
+        oc: Option<char>,
+        value: &[char],
+        _ctx: &mut Box<Ctx<S>>,
+    ) -> MatcherResult {
+        match oc {
+            Some(c) if c == '_' || c.is_alphabetic() || (self.index > 0 && c.is_alphanumeric()) => {
+                self.index += 1;
+                MatcherResult::Running()
+            }
+            _ => self.generate_identifier_token(value),
+        }
+    }
+    fn is_running(&self) -> bool {
+        self.running
+    }
+    fn precedence(&self) -> u8 {
+        self.precedence
+    }
+    fn describe(&self) -> String {
+        format!("IdentifierMatcher {{ precedence: {} }}", self.precedence)
+    }
+}
+
+impl Default for IdentifierMatcher {
+    /// An IdentifierMatcher with a precedence of 0, ready to match.
+    fn default() -> Self {
+        IdentifierMatcher::build_identifier_matcher(0)
+    }
+}
+
+impl IdentifierMatcher {
+    /// Build an identifier matcher
+    ///
+    /// # Arguments
+    ///
+    /// * `precedence` - the precedence for this matcher
+    ///
+    pub fn build_identifier_matcher(precedence: u8) -> IdentifierMatcher {
+        IdentifierMatcher {
+            index: 0,
+            precedence,
+            running: true,
+        }
+    }
+
+    #[inline(always)]
+    fn generate_identifier_token(&mut self, value: &[char]) -> MatcherResult {
+        self.running = false;
+        if self.index > 0 {
+            MatcherResult::Matched(Token {
+                value: value[0..self.index].iter().collect(),
+                token_type: TOKEN_TYPE_IDENTIFIER,
+                len: self.index,
+                line: 0,
+                column: self.index,
+                precedence: self.precedence,
+                ..Default::default()
+            })
+        } else {
+            MatcherResult::Failed()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::InputString;
+    use crate::matcher_identifier::IdentifierMatcher;
+    use crate::matcher_integer::IntegerMatcher;
+    use crate::matcher_whitespace::WhitespaceMatcher;
+    use crate::token::{TOKEN_TYPE_IDENTIFIER, TOKEN_TYPE_INTEGER, TOKEN_TYPE_WHITESPACE};
+    use crate::{Lexx, LexxError, Lexxer};
+
+    fn tokens(text: &str) -> Vec<(String, u16)> {
+        Lexx::<512>::new(
+            Box::new(InputString::new(String::from(text))),
+            vec![
+                Box::new(IdentifierMatcher::default()),
+                Box::new(IntegerMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+            ],
+        )
+        .filter(|t| t.token_type != TOKEN_TYPE_WHITESPACE)
+        .map(|t| (t.value, t.token_type))
+        .collect()
+    }
+
+    #[test]
+    fn matcher_identifier_matches() {
+        let identifier = |v: &str| (String::from(v), TOKEN_TYPE_IDENTIFIER);
+        assert_eq!(
+            tokens("foo_bar2 _ __init__ _1 x9y über"),
+            vec![
+                identifier("foo_bar2"),
+                identifier("_"),
+                identifier("__init__"),
+                identifier("_1"),
+                identifier("x9y"),
+                identifier("über"),
+            ]
+        );
+    }
+
+    #[test]
+    fn matcher_identifier_not_after_digit() {
+        assert_eq!(
+            tokens("2foo 42"),
+            vec![
+                (String::from("2"), TOKEN_TYPE_INTEGER),
+                (String::from("foo"), TOKEN_TYPE_IDENTIFIER),
+                (String::from("42"), TOKEN_TYPE_INTEGER),
+            ]
+        );
+
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("9"))),
+            vec![Box::new(IdentifierMatcher::default())],
+        );
+        assert!(matches!(lexx.next_token(), Err(LexxError::TokenNotFound(_))));
+    }
+}
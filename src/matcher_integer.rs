@@ -1,10 +1,16 @@
-use crate::matcher::{Matcher, MatcherResult};
+use std::hash::BuildHasher;
+use crate::matcher::{Ctx, Matcher, MatcherResult};
 use crate::token::{Token, TOKEN_TYPE_INTEGER};
-use std::collections::HashMap;
 
 /// The integer matcher matches integer numbers. To qualify as integer the numbers must
 /// start and end with a numeric digit.
 ///
+/// With [radix_prefixes](IntegerMatcher::radix_prefixes) set it also matches hexadecimal, binary
+/// and octal integers that start with `0x`, `0b` or `0o`, or their uppercase forms, such as `0xFF`.
+/// These may have single `_` separators between digits, as in `0xDE_AD`, which plain decimal
+/// integers may not. If there are no digits after the prefix, as in `0xG`, just the `0` is matched,
+/// and a trailing `_` is left out of the match.
+///
 /// # Example
 ///
 /// ```rust
@@ -19,10 +25,10 @@ use std::collections::HashMap;
 /// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
 /// Box::new(InputString::new(String::from("1.0 5 0.012345 .9 00.00 100.0 4."))),
 /// vec![
-///     Box::new(FloatMatcher{ index: 0, precedence: 0, dot: false, float:false, running: true }),
-///     Box::new(SymbolMatcher { index: 0, precedence: 0, running: true }),
-///     Box::new(IntegerMatcher { index: 0, precedence: 0, running: true }),
-///     Box::new(WhitespaceMatcher { index: 0, column: 0,line: 0,precedence: 0, running: true }),
+///     Box::new(FloatMatcher{ index: 0, precedence: 0, dot: false, float:false, running: true, lenient_trailing_dot: false, exponent: false, integer_mantissa: false }),
+///     Box::new(SymbolMatcher { index: 0, precedence: 0, running: true, max_len: 0 }),
+///     Box::new(IntegerMatcher { index: 0, precedence: 0, running: true, radix_prefixes: false }),
+///     Box::new(WhitespaceMatcher { index: 0, column: 0,line: 0,precedence: 0, running: true, predicate: char::is_whitespace, line_continuation: None, mixed_indent: false }),
 /// ]
 /// ));
 ///
@@ -51,10 +57,20 @@ pub struct IntegerMatcher {
     pub precedence: u8,
     /// If the matcher is currently running.
     pub running: bool,
+    /// If `0x`, `0b` and `0o` start hexadecimal, binary and octal integers.
+    pub radix_prefixes: bool,
 }
 
-impl Matcher for IntegerMatcher {
-    fn reset(&mut self, _ctx: &mut Box<HashMap<String, i32>>) {
+impl<S: BuildHasher> Matcher<S> for IntegerMatcher {
+    fn reset(&mut self, _ctx: &mut Box<Ctx<S>>) {
+        Matcher::<S>::reset_light(self);
+    }
+
+    fn is_stateful(&self) -> bool {
+        false
+    }
+
+    fn reset_light(&mut self) {
         self.index = 0;
         self.running = true;
     }
@@ -63,12 +79,22 @@ impl Matcher for IntegerMatcher {
         &mut self,
         oc: Option<char>,
         value: &[char],
-        _ctx: &mut Box<HashMap<String, i32>>,
+        _ctx: &mut Box<Ctx<S>>,
     ) -> MatcherResult {
         return match oc {
             None => self.generate_integer_token(value),
             Some(c) => {
-                if c.is_numeric() {
+                let more = match self.radix(value) {
+                    Some(radix) => {
+                        c.is_digit(radix)
+                            || (c == '_' && self.index > 2 && value[self.index - 1] != '_')
+                    }
+                    None if self.radix_prefixes && self.index == 1 && value[0] == '0' => {
+                        matches!(c, 'x' | 'X' | 'b' | 'B' | 'o' | 'O') || c.is_numeric()
+                    }
+                    None => c.is_numeric(),
+                };
+                if more {
                     self.index += 1;
                     MatcherResult::Running()
                 } else {
@@ -83,20 +109,78 @@ impl Matcher for IntegerMatcher {
     fn precedence(&self) -> u8 {
         self.precedence
     }
+    fn describe(&self) -> String {
+        format!(
+            "IntegerMatcher {{ precedence: {}, radix_prefixes: {} }}",
+            self.precedence, self.radix_prefixes
+        )
+    }
+}
+
+impl Default for IntegerMatcher {
+    /// An IntegerMatcher with a precedence of 0, ready to match.
+    fn default() -> Self {
+        IntegerMatcher {
+            index: 0,
+            precedence: 0,
+            running: true,
+            radix_prefixes: false,
+        }
+    }
 }
 
 impl IntegerMatcher {
+    /// An IntegerMatcher that also matches `0x`, `0b` and `0o` prefixed integers
+    ///
+    /// # Arguments
+    ///
+    /// * `precedence` - the precedence for this matcher
+    ///
+    pub fn with_radix_prefixes(precedence: u8) -> IntegerMatcher {
+        IntegerMatcher {
+            index: 0,
+            precedence,
+            running: true,
+            radix_prefixes: true,
+        }
+    }
+
+    /// The radix of the integer being matched if it has a prefix.
+    #[inline(always)]
+    fn radix(&self, value: &[char]) -> Option<u32> {
+        if !self.radix_prefixes || self.index < 2 || value[0] != '0' {
+            return None;
+        }
+        match value[1] {
+            'x' | 'X' => Some(16),
+            'b' | 'B' => Some(2),
+            'o' | 'O' => Some(8),
+            _ => None,
+        }
+    }
+
     #[inline(always)]
     fn generate_integer_token(&mut self, value: &[char]) -> MatcherResult {
         self.running = false;
-        if self.index > 0 {
+        let mut len = self.index;
+        if self.radix(value).is_some() {
+            if value[len - 1] == '_' {
+                len -= 1;
+            }
+            if len == 2 {
+                // a prefix with no digits is just a 0
+                len = 1;
+            }
+        }
+        if len > 0 {
             MatcherResult::Matched(Token {
-                value: value[0..self.index].into_iter().collect(),
+                value: value[0..len].iter().collect(),
                 token_type: TOKEN_TYPE_INTEGER,
-                len: self.index,
+                len,
                 line: 0,
-                column: self.index,
+                column: len,
                 precedence: self.precedence,
+                ..Default::default()
             })
         } else {
             MatcherResult::Failed()
@@ -107,7 +191,10 @@ impl IntegerMatcher {
 #[cfg(test)]
 mod tests {
     use crate::matcher_integer::IntegerMatcher;
+    use crate::matcher_symbol::SymbolMatcher;
     use crate::matcher_whitespace::WhitespaceMatcher;
+    use crate::matcher_word::WordMatcher;
+    use crate::token::{TOKEN_TYPE_INTEGER, TOKEN_TYPE_SYMBOL, TOKEN_TYPE_WHITESPACE, TOKEN_TYPE_WORD};
     use crate::{Lexx, LexxError, Lexxer};
     use crate::input::InputString;
 
@@ -119,6 +206,7 @@ mod tests {
                 index: 0,
                 precedence: 0,
                 running: true,
+                radix_prefixes: false,
             })],
         ));
 
@@ -127,7 +215,7 @@ mod tests {
                 LexxError::TokenNotFound(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
-                LexxError::Error(_) => {
+                LexxError::Error(_) | LexxError::BufferOverflow(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
             },
@@ -148,6 +236,7 @@ mod tests {
                 index: 0,
                 precedence: 0,
                 running: true,
+                radix_prefixes: false,
             })],
         );
 
@@ -156,7 +245,7 @@ mod tests {
                 LexxError::TokenNotFound(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
-                LexxError::Error(_) => {
+                LexxError::Error(_) | LexxError::BufferOverflow(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
             },
@@ -177,6 +266,7 @@ mod tests {
                 index: 0,
                 precedence: 0,
                 running: true,
+                radix_prefixes: false,
             })],
         );
 
@@ -185,7 +275,7 @@ mod tests {
                 LexxError::TokenNotFound(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
-                LexxError::Error(_) => {
+                LexxError::Error(_) | LexxError::BufferOverflow(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
             },
@@ -207,6 +297,7 @@ mod tests {
                     index: 0,
                     precedence: 0,
                     running: true,
+                    radix_prefixes: false,
                 }),
                 Box::new(WhitespaceMatcher {
                     index: 0,
@@ -214,6 +305,9 @@ mod tests {
                     line: 0,
                     precedence: 0,
                     running: true,
+                    predicate: char::is_whitespace,
+                    line_continuation: None,
+                    mixed_indent: false,
                 }),
             ],
         );
@@ -223,7 +317,7 @@ mod tests {
                 LexxError::TokenNotFound(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
-                LexxError::Error(_) => {
+                LexxError::Error(_) | LexxError::BufferOverflow(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
             },
@@ -236,7 +330,7 @@ mod tests {
         }
 
         assert!(
-            matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == crate::token::TOKEN_TYPE_WHITESPACE)
+            matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_WHITESPACE)
         );
 
         match lexx.next_token() {
@@ -244,7 +338,7 @@ mod tests {
                 LexxError::TokenNotFound(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
-                LexxError::Error(_) => {
+                LexxError::Error(_) | LexxError::BufferOverflow(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
             },
@@ -257,7 +351,7 @@ mod tests {
         }
 
         assert!(
-            matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == crate::token::TOKEN_TYPE_WHITESPACE)
+            matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_WHITESPACE)
         );
 
         match lexx.next_token() {
@@ -265,7 +359,7 @@ mod tests {
                 LexxError::TokenNotFound(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
-                LexxError::Error(_) => {
+                LexxError::Error(_) | LexxError::BufferOverflow(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
             },
@@ -277,4 +371,84 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn matcher_integer_default() {
+        let explicit = IntegerMatcher {
+            index: 0,
+            precedence: 0,
+            running: true,
+            radix_prefixes: false,
+        };
+        assert_eq!(
+            format!("{:?}", IntegerMatcher::default()),
+            format!("{:?}", explicit)
+        );
+    }
+
+    #[test]
+    fn matcher_integer_radix_prefixes() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from(
+                "0xFF 0Xff 0b1012 0o17 0O8 0xDE_AD 0xA__B 0x_1 0xB_ 0xG 0 07 1_0",
+            ))),
+            vec![
+                Box::new(IntegerMatcher::with_radix_prefixes(0)),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(WordMatcher::default()),
+                Box::new(SymbolMatcher::with_max_len(1, 0)),
+            ],
+        );
+        let tokens: Vec<(String, u16)> = lexx
+            .by_ref()
+            .filter(|t| t.token_type != TOKEN_TYPE_WHITESPACE)
+            .map(|t| (t.value, t.token_type))
+            .collect();
+        let integer = |v: &str| (String::from(v), TOKEN_TYPE_INTEGER);
+        let other = |v: &str, t: u16| (String::from(v), t);
+        assert_eq!(
+            tokens,
+            vec![
+                integer("0xFF"),
+                integer("0Xff"),
+                integer("0b101"),
+                integer("2"),
+                integer("0o17"),
+                // no octal digits after the prefix
+                integer("0"),
+                other("O", TOKEN_TYPE_WORD),
+                integer("8"),
+                integer("0xDE_AD"),
+                integer("0xA"),
+                other("_", TOKEN_TYPE_SYMBOL),
+                other("_", TOKEN_TYPE_SYMBOL),
+                other("B", TOKEN_TYPE_WORD),
+                integer("0"),
+                other("x", TOKEN_TYPE_WORD),
+                other("_", TOKEN_TYPE_SYMBOL),
+                integer("1"),
+                integer("0xB"),
+                other("_", TOKEN_TYPE_SYMBOL),
+                integer("0"),
+                other("xG", TOKEN_TYPE_WORD),
+                integer("0"),
+                integer("07"),
+                // decimal integers have no separators
+                integer("1"),
+                other("_", TOKEN_TYPE_SYMBOL),
+                integer("0"),
+            ]
+        );
+
+        // without the flag a prefix is not special
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("0xFF"))),
+            vec![
+                Box::new(IntegerMatcher::default()),
+                Box::new(WordMatcher::default()),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "0" && t.token_type == TOKEN_TYPE_INTEGER));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "xFF" && t.token_type == TOKEN_TYPE_WORD));
+    }
 }
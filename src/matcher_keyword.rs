@@ -1,6 +1,6 @@
-use crate::matcher::{Matcher, MatcherResult};
+use std::hash::BuildHasher;
+use crate::matcher::{Ctx, Matcher, MatcherResult};
 use crate::token::Token;
-use std::collections::HashMap;
 
 /// An exact keyword to be made
 #[derive(Clone, Debug)]
@@ -18,6 +18,10 @@ pub struct Target {
 /// "matches", "matchers" or "match1", "1matcher", "2match" etc.
 /// It will match "match ", " match." "---match---" and so on.
 ///
+/// What may follow a keyword can be changed with
+/// [build_matcher_keyword_with_boundary](KeywordMatcher::build_matcher_keyword_with_boundary),
+/// for example to not match a keyword that is followed by `(` because it's really a function call.
+///
 /// # Example
 ///
 /// ```rust
@@ -33,8 +37,8 @@ pub struct Target {
 /// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
 ///     Box::new(lexx_input),
 ///     vec![
-///         Box::new(WordMatcher { index: 0, precedence: 0, running: true }),
-///         Box::new(WhitespaceMatcher { index: 0, column: 0,line: 0,precedence: 0, running: true}),
+///         Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] }),
+///         Box::new(WhitespaceMatcher { index: 0, column: 0,line: 0,precedence: 0, running: true, predicate: char::is_whitespace, line_continuation: None, mixed_indent: false}),
 ///         // Note the precedence of 1 will cause the ExactMatcher to be be returned
 ///         // when the SymbolMatcher would have matched the same, or a longer thing.
 ///         Box::new(KeywordMatcher::build_matcher_keyword(vec!["match", "dog"], TOKEN_TYPE_KEYWORD, 1)),
@@ -66,10 +70,13 @@ pub struct KeywordMatcher {
     pub targets: Box<Vec<Target>>,
     /// What token type to return if a match is made.
     pub token_type: u16,
+    /// Given the [char] following a keyword, decides if the keyword matches. The end of input
+    /// always counts as a boundary.
+    pub boundary: fn(char) -> bool,
 }
 
-impl Matcher for KeywordMatcher {
-    fn reset(&mut self, _ctx: &mut Box<HashMap<String, i32>>) {
+impl<S: BuildHasher> Matcher<S> for KeywordMatcher {
+    fn reset(&mut self, _ctx: &mut Box<Ctx<S>>) {
         for t in self.targets.iter_mut() {
             t.matching = true
         }
@@ -82,7 +89,7 @@ impl Matcher for KeywordMatcher {
         &mut self,
         oc: Option<char>,
         _value: &[char],
-        _ctx: &mut Box<HashMap<String, i32>>,
+        _ctx: &mut Box<Ctx<S>>,
     ) -> MatcherResult {
         return match oc {
             None => {
@@ -104,7 +111,7 @@ impl Matcher for KeywordMatcher {
                         match target.target.get(self.index) {
                             None => {
                                 target.matching = false;
-                                if self.index > 0 && !c.is_alphabetic() {
+                                if self.index > 0 && (self.boundary)(c) {
                                     self.found = Some(i);
                                 }
                             }
@@ -134,6 +141,28 @@ impl Matcher for KeywordMatcher {
     fn precedence(&self) -> u8 {
         self.precedence
     }
+    fn describe(&self) -> String {
+        let targets: Vec<String> = self
+            .targets
+            .iter()
+            .map(|t| format!("{:?}", t.target.iter().collect::<String>()))
+            .collect();
+        format!(
+            "KeywordMatcher {{ precedence: {}, token_type: {}, targets: [{}] }}",
+            self.precedence,
+            self.token_type,
+            targets.join(", ")
+        )
+    }
+    fn validate(&self) -> Result<(), String> {
+        if self.targets.is_empty() {
+            return Err("KeywordMatcher has no targets".to_string());
+        }
+        if self.targets.iter().any(|t| t.target.is_empty()) {
+            return Err("KeywordMatcher has an empty target".to_string());
+        }
+        Ok(())
+    }
 }
 
 impl KeywordMatcher {
@@ -149,6 +178,52 @@ impl KeywordMatcher {
         matches: Vec<&str>,
         token_type: u16,
         precedence: u8,
+    ) -> KeywordMatcher {
+        KeywordMatcher::build_matcher_keyword_with_boundary(
+            matches,
+            token_type,
+            precedence,
+            |c| !c.is_alphabetic(),
+        )
+    }
+
+    /// Build an keyword matcher with a custom rule for what may follow a keyword
+    ///
+    /// # Arguments
+    ///
+    /// * `matches` - a [vec] of [&str](std::str)s that will be matched
+    /// * `token_type` - the token type to produce
+    /// * `precedence` - the precedence for this matcher
+    /// * `boundary` - given the [char] following a keyword returns if the keyword matches, the
+    ///   default is `|c| !c.is_alphabetic()`
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lexx::{Lexx, Lexxer};
+    /// use lexx::token::{TOKEN_TYPE_KEYWORD, TOKEN_TYPE_WORD};
+    /// use lexx::input::InputString;
+    /// use lexx::matcher_keyword::KeywordMatcher;
+    /// use lexx::matcher_symbol::SymbolMatcher;
+    /// use lexx::matcher_word::WordMatcher;
+    ///
+    /// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+    ///     Box::new(InputString::new(String::from("print("))),
+    ///     vec![
+    ///         Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] }),
+    ///         Box::new(SymbolMatcher { index: 0, precedence: 0, running: true, max_len: 0 }),
+    ///         Box::new(KeywordMatcher::build_matcher_keyword_with_boundary(
+    ///             vec!["print"], TOKEN_TYPE_KEYWORD, 1, |c| !c.is_alphabetic() && c != '(')),
+    ///     ]
+    /// ));
+    ///
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "print" && t.token_type == TOKEN_TYPE_WORD));
+    /// ```
+    pub fn build_matcher_keyword_with_boundary(
+        matches: Vec<&str>,
+        token_type: u16,
+        precedence: u8,
+        boundary: fn(char) -> bool,
     ) -> KeywordMatcher {
         let mut targets: Box<Vec<Target>> = Box::new(vec![]);
         for m in matches {
@@ -168,6 +243,7 @@ impl KeywordMatcher {
             running: true,
             targets,
             token_type,
+            boundary,
         }
     }
 
@@ -187,6 +263,7 @@ impl KeywordMatcher {
                     line: 0,
                     column: len,
                     precedence: self.precedence,
+                    ..Default::default()
                 })
             }
         }
@@ -217,7 +294,7 @@ mod tests {
                 LexxError::TokenNotFound(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
-                LexxError::Error(_) => {
+                LexxError::Error(_) | LexxError::BufferOverflow(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
             },
@@ -249,6 +326,9 @@ mod tests {
                     line: 0,
                     precedence: 0,
                     running: true,
+                    predicate: char::is_whitespace,
+                    line_continuation: None,
+                    mixed_indent: false,
                 }),
             ],
         );
@@ -258,7 +338,7 @@ mod tests {
                 LexxError::TokenNotFound(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
-                LexxError::Error(_) => {
+                LexxError::Error(_) | LexxError::BufferOverflow(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
             },
@@ -277,7 +357,7 @@ mod tests {
                 LexxError::TokenNotFound(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
-                LexxError::Error(_) => {
+                LexxError::Error(_) | LexxError::BufferOverflow(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
             },
@@ -296,7 +376,7 @@ mod tests {
                 LexxError::TokenNotFound(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
-                LexxError::Error(_) => {
+                LexxError::Error(_) | LexxError::BufferOverflow(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
             },
@@ -315,7 +395,7 @@ mod tests {
                 LexxError::TokenNotFound(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
-                LexxError::Error(_) => {
+                LexxError::Error(_) | LexxError::BufferOverflow(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
             },
@@ -334,7 +414,7 @@ mod tests {
                 LexxError::TokenNotFound(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
-                LexxError::Error(_) => {
+                LexxError::Error(_) | LexxError::BufferOverflow(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
             },
@@ -371,6 +451,9 @@ mod tests {
                     line: 0,
                     precedence: 0,
                     running: true,
+                    predicate: char::is_whitespace,
+                    line_continuation: None,
+                    mixed_indent: false,
                 }),
             ],
         );
@@ -396,14 +479,14 @@ mod tests {
                 LexxError::TokenNotFound(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
-                LexxError::Error(_) => {
+                LexxError::Error(_) | LexxError::BufferOverflow(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
             },
             Ok(Some(t)) => {
                 assert_eq!(t.value, "dog");
-                assert_eq!(t.line, 2);
-                assert_eq!(t.column, 26);
+                assert_eq!(t.line, 4);
+                assert_eq!(t.column, 15);
             }
             Ok(None) => {
                 assert!(false, "Should not hit None");
@@ -427,7 +510,7 @@ mod tests {
                 LexxError::TokenNotFound(e) => {
                     assert_eq!(e, "Could not resolve token at 1, 1: 'Some('n')'.");
                 }
-                LexxError::Error(_) => {
+                LexxError::Error(_) | LexxError::BufferOverflow(_) => {
                     assert!(false, "Should not throw error");
                 }
             },
@@ -440,4 +523,53 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn matcher_keyword_custom_boundary() {
+        use crate::matcher_symbol::SymbolMatcher;
+        use crate::matcher_whitespace::WhitespaceMatcher;
+        use crate::matcher_word::WordMatcher;
+        use crate::token::{TOKEN_TYPE_SYMBOL, TOKEN_TYPE_WHITESPACE, TOKEN_TYPE_WORD};
+
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("print x print(x)"))),
+            vec![
+                Box::new(WordMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    trailing: &[],
+                }),
+                Box::new(SymbolMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    max_len: 0,
+                }),
+                Box::new(WhitespaceMatcher {
+                    index: 0,
+                    column: 0,
+                    line: 0,
+                    precedence: 0,
+                    running: true,
+                    predicate: char::is_whitespace,
+                    line_continuation: None,
+                    mixed_indent: false,
+                }),
+                Box::new(KeywordMatcher::build_matcher_keyword_with_boundary(
+                    vec!["print"],
+                    TOKEN_TYPE_KEYWORD,
+                    1,
+                    |c| !c.is_alphabetic() && c != '(',
+                )),
+            ],
+        );
+
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "print" && t.token_type == TOKEN_TYPE_KEYWORD));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_WHITESPACE));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "x" && t.token_type == TOKEN_TYPE_WORD));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_WHITESPACE));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "print" && t.token_type == TOKEN_TYPE_WORD));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "(" && t.token_type == TOKEN_TYPE_SYMBOL));
+    }
 }
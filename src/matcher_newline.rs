@@ -0,0 +1,218 @@
+use std::hash::BuildHasher;
+use crate::matcher::{Ctx, Matcher, MatcherResult};
+use crate::token::{Token, TOKEN_TYPE_BLANK_LINE, TOKEN_TYPE_NEWLINE};
+
+/// The NewlineMatcher matches a single `\n` or `\r\n` line ending. A line ending that comes
+/// directly after another one, ending an empty line, is a [TOKEN_TYPE_BLANK_LINE] instead of a
+/// [TOKEN_TYPE_NEWLINE]. Line and column tracking works the same as for the
+/// [WhitespaceMatcher](crate::matcher_whitespace::WhitespaceMatcher).
+///
+/// To tell the two apart the matcher remembers if the last [Token] was its own, so it needs to
+/// win every match it makes. Give it a higher precedence than any other matcher that can match a
+/// line ending, and use a [WhitespaceMatcher](crate::matcher_whitespace::WhitespaceMatcher) made
+/// [with a predicate](crate::matcher_whitespace::WhitespaceMatcher::with_predicate) that leaves
+/// out `\n` and `\r` so runs of spaces don't swallow the line endings.
+///
+/// # Example
+///
+/// ```rust
+/// use lexx::{Lexx, Lexxer};
+/// use lexx::token::{TOKEN_TYPE_BLANK_LINE, TOKEN_TYPE_NEWLINE, TOKEN_TYPE_WHITESPACE, TOKEN_TYPE_WORD};
+/// use lexx::input::InputString;
+/// use lexx::matcher_newline::NewlineMatcher;
+/// use lexx::matcher_whitespace::WhitespaceMatcher;
+/// use lexx::matcher_word::WordMatcher;
+///
+/// let lexx_input = InputString::new(String::from("a\n\n  b"));
+///
+/// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+///     Box::new(lexx_input),
+///     vec![
+///         Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] }),
+///         Box::new(WhitespaceMatcher::with_predicate(|c| c.is_whitespace() && c != '\n' && c != '\r', 0)),
+///         Box::new(NewlineMatcher::build_newline_matcher(1)),
+///     ]
+/// ));
+///
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "a" && t.token_type == TOKEN_TYPE_WORD));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_NEWLINE && t.line == 1));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_BLANK_LINE && t.line == 2));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_WHITESPACE && t.line == 3));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "b" && t.line == 3 && t.column == 3));
+/// assert!(matches!(lexx.next_token(), Ok(None)));
+/// ```
+#[derive(Clone, Debug, Copy)]
+pub struct NewlineMatcher {
+    /// Current size of the ongoing match.
+    pub index: usize,
+    /// This matchers precedence.
+    pub precedence: u8,
+    /// If the matcher is currently running.
+    pub running: bool,
+    /// If this matcher produced a [Token] during the current match.
+    pub matched: bool,
+    /// If the previous [Token] was a line ending from this matcher.
+    pub after_newline: bool,
+}
+
+impl<S: BuildHasher> Matcher<S> for NewlineMatcher {
+    fn reset(&mut self, _ctx: &mut Box<Ctx<S>>) {
+        self.after_newline = self.matched;
+        self.matched = false;
+        self.index = 0;
+        self.running = true;
+    }
+
+    fn find_match(
+        &mut self,
+        oc: Option<char>,
+        value: &[char],
+        _ctx: &mut Box<Ctx<S>>,
+    ) -> MatcherResult {
+        self.running = false;
+        match oc {
+            Some('\r') if self.index == 0 => {
+                self.index += 1;
+                self.running = true;
+                MatcherResult::Running()
+            }
+            Some('\n') => {
+                self.index += 1;
+                self.matched = true;
+                MatcherResult::Matched(Token {
+                    value: value[0..self.index].iter().collect(),
+                    token_type: if self.after_newline {
+                        TOKEN_TYPE_BLANK_LINE
+                    } else {
+                        TOKEN_TYPE_NEWLINE
+                    },
+                    len: self.index,
+                    line: 1,
+                    column: 1,
+                    precedence: self.precedence,
+                    ..Default::default()
+                })
+            }
+            _ => MatcherResult::Failed(),
+        }
+    }
+    fn is_running(&self) -> bool {
+        self.running
+    }
+    fn precedence(&self) -> u8 {
+        self.precedence
+    }
+    fn describe(&self) -> String {
+        format!("NewlineMatcher {{ precedence: {} }}", self.precedence)
+    }
+}
+
+impl Default for NewlineMatcher {
+    /// A NewlineMatcher with a precedence of 0, ready to match.
+    fn default() -> Self {
+        NewlineMatcher {
+            index: 0,
+            precedence: 0,
+            running: true,
+            matched: false,
+            after_newline: false,
+        }
+    }
+}
+
+impl NewlineMatcher {
+    /// Build a newline matcher
+    ///
+    /// # Arguments
+    ///
+    /// * `precedence` - the precedence for this matcher, it should be higher than any other
+    ///   matcher that can match a line ending
+    ///
+    pub fn build_newline_matcher(precedence: u8) -> NewlineMatcher {
+        NewlineMatcher {
+            index: 0,
+            precedence,
+            running: true,
+            matched: false,
+            after_newline: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::InputString;
+    use crate::matcher_newline::NewlineMatcher;
+    use crate::matcher_whitespace::WhitespaceMatcher;
+    use crate::matcher_word::WordMatcher;
+    use crate::token::{TOKEN_TYPE_BLANK_LINE, TOKEN_TYPE_NEWLINE, TOKEN_TYPE_WORD};
+    use crate::{Lexx, Lexxer};
+
+    #[test]
+    fn matcher_newline_blank_lines() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("a\n\n\nb"))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(WhitespaceMatcher::with_predicate(
+                    |c| c.is_whitespace() && c != '\n' && c != '\r',
+                    0,
+                )),
+                Box::new(NewlineMatcher::build_newline_matcher(1)),
+            ],
+        );
+
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "a" && t.token_type == TOKEN_TYPE_WORD));
+        assert!(
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "\n" && t.token_type == TOKEN_TYPE_NEWLINE && t.line == 1 && t.column == 2)
+        );
+        assert!(
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "\n" && t.token_type == TOKEN_TYPE_BLANK_LINE && t.line == 2 && t.column == 1)
+        );
+        assert!(
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "\n" && t.token_type == TOKEN_TYPE_BLANK_LINE && t.line == 3 && t.column == 1)
+        );
+        assert!(
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "b" && t.token_type == TOKEN_TYPE_WORD && t.line == 4 && t.column == 1)
+        );
+        assert!(matches!(lexx.next_token(), Ok(None)));
+    }
+
+    #[test]
+    fn matcher_newline_crlf_and_spaces() {
+        // a line of only spaces isn't blank
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("a\r\n \r\n\r\nb"))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(WhitespaceMatcher::with_predicate(
+                    |c| c.is_whitespace() && c != '\n' && c != '\r',
+                    0,
+                )),
+                Box::new(NewlineMatcher::build_newline_matcher(1)),
+            ],
+        );
+
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "a"));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "\r\n" && t.token_type == TOKEN_TYPE_NEWLINE && t.len == 2));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == " " && t.line == 2));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "\r\n" && t.token_type == TOKEN_TYPE_NEWLINE));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "\r\n" && t.token_type == TOKEN_TYPE_BLANK_LINE && t.line == 3));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "b" && t.line == 4 && t.column == 1));
+    }
+
+    #[test]
+    fn matcher_newline_default() {
+        let explicit = NewlineMatcher {
+            index: 0,
+            precedence: 0,
+            running: true,
+            matched: false,
+            after_newline: false,
+        };
+        assert_eq!(
+            format!("{:?}", NewlineMatcher::default()),
+            format!("{:?}", explicit)
+        );
+    }
+}
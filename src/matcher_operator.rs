@@ -0,0 +1,296 @@
+use std::hash::BuildHasher;
+use crate::matcher::{Ctx, Matcher, MatcherResult};
+use crate::matcher_exact::Target;
+use crate::token::{Token, TOKEN_TYPE_SYMBOL};
+
+/// The OperatorMatcher matches operators from a table, each with its own token type, so every
+/// operator comes out as one canonical [Token] however the operators around it are written. It
+/// uses maximal munch, the longest operator in the table that the input starts with wins, so
+/// with `<`, `<=`, `<<` and `<<=` in the table `<<=` is one [Token] and `<<<` is `<<` then `<`.
+///
+/// Unlike the [SymbolMatcher](crate::matcher_symbol::SymbolMatcher) it never merges a run of
+/// symbols into one [Token], and unlike the [ExactMatcher](crate::matcher_exact::ExactMatcher) it
+/// doesn't fail on a symbol that isn't an operator. A char that is not whitespace or alphanumeric
+/// and doesn't start an operator in the table is matched on its own as [TOKEN_TYPE_SYMBOL], so
+/// it can replace the [SymbolMatcher](crate::matcher_symbol::SymbolMatcher) when lexing code.
+///
+/// # Example
+///
+/// ```rust
+/// use lexx::{Lexx, Lexxer};
+/// use lexx::token::TOKEN_TYPE_SYMBOL;
+/// use lexx::input::InputString;
+/// use lexx::matcher_operator::OperatorMatcher;
+///
+/// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+///     Box::new(InputString::new(String::from("!===>@"))),
+///     vec![Box::new(OperatorMatcher::build_operator_matcher(
+///         &[("!", 20), ("!=", 21), ("==", 22), ("===", 23), ("=>", 24)],
+///         0,
+///     ))],
+/// ));
+///
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "!=" && t.token_type == 21));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "==" && t.token_type == 22));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == ">" && t.token_type == TOKEN_TYPE_SYMBOL));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "@" && t.token_type == TOKEN_TYPE_SYMBOL));
+/// assert!(matches!(lexx.next_token(), Ok(None)));
+/// ```
+#[derive(Clone, Debug)]
+pub struct OperatorMatcher {
+    /// Current size of the ongoing match.
+    pub index: usize,
+    /// This matchers precedence.
+    pub precedence: u8,
+    /// If the matcher is currently running.
+    pub running: bool,
+    /// The index of the longest operator matched so far.
+    pub found: Option<usize>,
+    /// The operators to match, each with its own token type.
+    pub operators: Vec<Target>,
+}
+
+impl<S: BuildHasher> Matcher<S> for OperatorMatcher {
+    fn reset(&mut self, _ctx: &mut Box<Ctx<S>>) {
+        Matcher::<S>::reset_light(self);
+    }
+
+    fn is_stateful(&self) -> bool {
+        false
+    }
+
+    fn reset_light(&mut self) {
+        for o in self.operators.iter_mut() {
+            o.matching = true;
+        }
+        self.found = None;
+        self.index = 0;
+        self.running = true;
+    }
+
+    fn find_match(
+        &mut self,
+        oc: Option<char>,
+        value: &[char],
+        _ctx: &mut Box<Ctx<S>>,
+    ) -> MatcherResult {
+        let c = match oc {
+            None => return self.generate_operator_token(value),
+            Some(c) => c,
+        };
+        let mut running = false;
+        for (i, o) in self.operators.iter_mut().enumerate() {
+            if !o.matching {
+                continue;
+            }
+            if o.target.get(self.index) == Some(&c) {
+                if o.target.len() == self.index + 1 {
+                    self.found = Some(i);
+                }
+                running = true;
+            } else {
+                o.matching = false;
+            }
+        }
+        self.index += 1;
+        if running {
+            MatcherResult::Running()
+        } else {
+            self.generate_operator_token(value)
+        }
+    }
+    fn is_running(&self) -> bool {
+        self.running
+    }
+    fn precedence(&self) -> u8 {
+        self.precedence
+    }
+    fn describe(&self) -> String {
+        let operators: Vec<String> = self
+            .operators
+            .iter()
+            .map(|o| {
+                let operator: String = o.target.iter().collect();
+                format!(
+                    "{:?} as {}",
+                    operator,
+                    o.token_type.unwrap_or(TOKEN_TYPE_SYMBOL)
+                )
+            })
+            .collect();
+        format!(
+            "OperatorMatcher {{ precedence: {}, operators: [{}] }}",
+            self.precedence,
+            operators.join(", ")
+        )
+    }
+    fn validate(&self) -> Result<(), String> {
+        if self.operators.iter().any(|o| o.target.is_empty()) {
+            return Err("OperatorMatcher has an empty operator".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl OperatorMatcher {
+    /// Build an operator matcher
+    ///
+    /// # Arguments
+    ///
+    /// * `operators` - the operators that will be matched with the token type to produce for
+    ///   each, give two spellings of the same operator the same token type to make them one
+    /// * `precedence` - the precedence for this matcher
+    ///
+    pub fn build_operator_matcher(operators: &[(&str, u16)], precedence: u8) -> OperatorMatcher {
+        OperatorMatcher {
+            index: 0,
+            precedence,
+            running: true,
+            found: None,
+            operators: operators
+                .iter()
+                .map(|(operator, token_type)| Target {
+                    matching: true,
+                    target: Box::new(operator.chars().collect()),
+                    token_type: Some(*token_type),
+                })
+                .collect(),
+        }
+    }
+
+    #[inline(always)]
+    fn generate_operator_token(&mut self, value: &[char]) -> MatcherResult {
+        self.running = false;
+        let (value, token_type): (String, u16) = match self.found {
+            Some(i) => {
+                let operator = &self.operators[i];
+                (
+                    operator.target.iter().collect(),
+                    operator.token_type.unwrap_or(TOKEN_TYPE_SYMBOL),
+                )
+            }
+            // anything else that isn't a word, number or whitespace is a symbol on its own
+            None => match value.first() {
+                Some(c) if !c.is_whitespace() && !c.is_alphanumeric() => {
+                    (c.to_string(), TOKEN_TYPE_SYMBOL)
+                }
+                _ => return MatcherResult::Failed(),
+            },
+        };
+        let len = value.chars().count();
+        MatcherResult::Matched(Token {
+            value,
+            token_type,
+            len,
+            line: 0,
+            column: len,
+            precedence: self.precedence,
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::InputString;
+    use crate::matcher::Matcher;
+    use crate::matcher_operator::OperatorMatcher;
+    use crate::matcher_whitespace::WhitespaceMatcher;
+    use crate::matcher_word::WordMatcher;
+    use crate::token::{TOKEN_TYPE_SYMBOL, TOKEN_TYPE_WORD};
+    use crate::{Lexx, LexxError, Lexxer};
+
+    const LT: u16 = 20;
+    const LE: u16 = 21;
+    const SHL: u16 = 22;
+    const SHL_ASSIGN: u16 = 23;
+
+    #[test]
+    fn matcher_operator_maximal_munch() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("a<=b<<=c<<<d≤e"))),
+            vec![
+                Box::new(OperatorMatcher::build_operator_matcher(
+                    &[
+                        ("<", LT),
+                        ("<=", LE),
+                        ("<<", SHL),
+                        ("<<=", SHL_ASSIGN),
+                        ("≤", LE),
+                    ],
+                    0,
+                )),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(WordMatcher::default()),
+            ],
+        );
+
+        let tokens: Vec<(String, u16)> = lexx.by_ref().map(|t| (t.value, t.token_type)).collect();
+        assert_eq!(
+            tokens,
+            vec![
+                (String::from("a"), TOKEN_TYPE_WORD),
+                (String::from("<="), LE),
+                (String::from("b"), TOKEN_TYPE_WORD),
+                (String::from("<<="), SHL_ASSIGN),
+                (String::from("c"), TOKEN_TYPE_WORD),
+                (String::from("<<"), SHL),
+                (String::from("<"), LT),
+                (String::from("d"), TOKEN_TYPE_WORD),
+                // both spellings are the same operator
+                (String::from("≤"), LE),
+                (String::from("e"), TOKEN_TYPE_WORD),
+            ]
+        );
+    }
+
+    #[test]
+    fn matcher_operator_unknown_falls_back_to_symbol() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("a §§<"))),
+            vec![
+                Box::new(OperatorMatcher::build_operator_matcher(
+                    &[
+                        ("<", LT),
+                        ("<=", LE),
+                        ("<<", SHL),
+                        ("<<=", SHL_ASSIGN),
+                        ("≤", LE),
+                    ],
+                    0,
+                )),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(WordMatcher::default()),
+            ],
+        );
+
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "a"));
+        lexx.next_token().unwrap();
+        assert!(
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "§" && t.token_type == TOKEN_TYPE_SYMBOL && t.len == 1 && t.column == 3)
+        );
+        assert!(
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "§" && t.token_type == TOKEN_TYPE_SYMBOL && t.column == 4)
+        );
+        assert!(
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "<" && t.token_type == LT && t.column == 5)
+        );
+        assert!(matches!(lexx.next_token(), Ok(None)));
+
+        // words are left to the other matchers
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("a"))),
+            vec![Box::new(OperatorMatcher::build_operator_matcher(
+                &[("<", LT)],
+                0,
+            ))],
+        );
+        assert!(matches!(
+            lexx.next_token(),
+            Err(LexxError::TokenNotFound(_))
+        ));
+
+        let matcher = OperatorMatcher::build_operator_matcher(&[("", LT)], 0);
+        assert!(<OperatorMatcher as Matcher>::validate(&matcher).is_err());
+    }
+}
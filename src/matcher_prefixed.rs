@@ -0,0 +1,278 @@
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::BuildHasher;
+use crate::matcher::{Ctx, Matcher, MatcherResult};
+use crate::token::Token;
+
+/// The PrefixedMatcher matches a prefix [char], such as the sigil of `@name` or `#tag`, followed
+/// by whatever its sub-matcher matches, producing a single [Token] of its own type for both. If
+/// the sub-matcher fails after the prefix the whole match fails. The [Token] has the
+/// PrefixedMatcher's precedence, the sub-matcher's precedence is never used.
+///
+/// # Example
+///
+/// ```rust
+/// use lexx::{Lexx, Lexxer};
+/// use lexx::token::{TOKEN_TYPE_SYMBOL, TOKEN_TYPE_WORD};
+/// use lexx::input::InputString;
+/// use lexx::matcher_prefixed::PrefixedMatcher;
+/// use lexx::matcher_symbol::SymbolMatcher;
+/// use lexx::matcher_word::WordMatcher;
+///
+/// let lexx_input = InputString::new(String::from("#tag!"));
+///
+/// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+///     Box::new(lexx_input),
+///     vec![
+///         Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] }),
+///         Box::new(SymbolMatcher { index: 0, precedence: 0, running: true, max_len: 0 }),
+///         Box::new(PrefixedMatcher::build_prefixed_matcher(
+///             '#',
+///             Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] }),
+///             20,
+///             1,
+///         )),
+///     ]
+/// ));
+///
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "#tag" && t.token_type == 20 && t.len == 4));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "!" && t.token_type == TOKEN_TYPE_SYMBOL && t.column == 5));
+/// assert!(matches!(lexx.next_token(), Ok(None)));
+/// ```
+pub struct PrefixedMatcher<S = RandomState> {
+    /// Current size of the ongoing match.
+    pub index: usize,
+    /// This matchers precedence.
+    pub precedence: u8,
+    /// If the matcher is currently running.
+    pub running: bool,
+    /// The [char] that must start the match.
+    pub prefix: char,
+    /// The [Matcher] for everything after the prefix.
+    pub sub_matcher: Box<dyn Matcher<S>>,
+    /// What token type to return if a match is made.
+    pub token_type: u16,
+}
+
+// not derived, that would need the hasher to be Debug
+impl<S> fmt::Debug for PrefixedMatcher<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PrefixedMatcher")
+            .field("index", &self.index)
+            .field("precedence", &self.precedence)
+            .field("running", &self.running)
+            .field("prefix", &self.prefix)
+            .field("sub_matcher", &self.sub_matcher)
+            .field("token_type", &self.token_type)
+            .finish()
+    }
+}
+
+impl<S: BuildHasher> Matcher<S> for PrefixedMatcher<S> {
+    fn reset(&mut self, ctx: &mut Box<Ctx<S>>) {
+        self.index = 0;
+        self.running = true;
+        self.sub_matcher.reset(ctx);
+    }
+
+    fn find_match(
+        &mut self,
+        oc: Option<char>,
+        value: &[char],
+        ctx: &mut Box<Ctx<S>>,
+    ) -> MatcherResult {
+        if self.index == 0 {
+            return if oc == Some(self.prefix) {
+                self.index += 1;
+                MatcherResult::Running()
+            } else {
+                self.running = false;
+                MatcherResult::Failed()
+            };
+        }
+        match self.sub_matcher.find_match(oc, &value[1..], ctx) {
+            MatcherResult::Running() => {
+                self.index += 1;
+                MatcherResult::Running()
+            }
+            MatcherResult::Failed() => {
+                self.running = false;
+                MatcherResult::Failed()
+            }
+            MatcherResult::Matched(token) => {
+                self.running = false;
+                // the sub matcher gets its say before the prefix is added
+                let Some(token) = self.sub_matcher.on_match(token) else {
+                    return MatcherResult::Failed();
+                };
+                MatcherResult::Matched(Token {
+                    value: format!("{}{}", self.prefix, token.value),
+                    token_type: self.token_type,
+                    len: token.len + 1,
+                    line: token.line,
+                    column: if token.line > 0 {
+                        token.column
+                    } else {
+                        token.column + 1
+                    },
+                    precedence: self.precedence,
+                    ..Default::default()
+                })
+            }
+        }
+    }
+    fn is_running(&self) -> bool {
+        self.running
+    }
+    fn precedence(&self) -> u8 {
+        self.precedence
+    }
+    fn describe(&self) -> String {
+        format!(
+            "PrefixedMatcher {{ precedence: {}, prefix: {:?}, token_type: {}, sub_matcher: {} }}",
+            self.precedence,
+            self.prefix,
+            self.token_type,
+            self.sub_matcher.describe()
+        )
+    }
+    fn validate(&self) -> Result<(), String> {
+        self.sub_matcher.validate()
+    }
+}
+
+impl<S> PrefixedMatcher<S> {
+    /// Build a prefixed matcher
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - the [char] that must start the match
+    /// * `sub_matcher` - the [Matcher] that must match what follows the prefix
+    /// * `token_type` - the token type to produce
+    /// * `precedence` - the precedence for this matcher
+    ///
+    pub fn build_prefixed_matcher(
+        prefix: char,
+        sub_matcher: Box<dyn Matcher<S>>,
+        token_type: u16,
+        precedence: u8,
+    ) -> PrefixedMatcher<S> {
+        PrefixedMatcher {
+            index: 0,
+            precedence,
+            running: true,
+            prefix,
+            sub_matcher,
+            token_type,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::InputString;
+    use crate::matcher_integer::IntegerMatcher;
+    use crate::matcher_prefixed::PrefixedMatcher;
+    use crate::matcher_symbol::SymbolMatcher;
+    use crate::matcher_word::WordMatcher;
+    use crate::token::{TOKEN_TYPE_INTEGER, TOKEN_TYPE_SYMBOL};
+    use crate::{Lexx, Lexxer};
+
+    #[test]
+    fn matcher_prefixed_matches() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("@foo@bar"))),
+            vec![
+                Box::new(IntegerMatcher::default()),
+                Box::new(SymbolMatcher::with_max_len(1, 0)),
+                Box::new(PrefixedMatcher::build_prefixed_matcher(
+                    '@',
+                    Box::new(WordMatcher::default()),
+                    20,
+                    1,
+                )),
+            ],
+        );
+
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "@foo" && t.token_type == 20 && t.len == 4 && t.column == 1));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "@bar" && t.token_type == 20 && t.column == 5));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+    }
+
+    #[test]
+    fn matcher_prefixed_fails_without_sub_match() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("@1"))),
+            vec![
+                Box::new(IntegerMatcher::default()),
+                Box::new(SymbolMatcher::with_max_len(1, 0)),
+                Box::new(PrefixedMatcher::build_prefixed_matcher(
+                    '@',
+                    Box::new(WordMatcher::default()),
+                    20,
+                    1,
+                )),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "@" && t.token_type == TOKEN_TYPE_SYMBOL));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "1" && t.token_type == TOKEN_TYPE_INTEGER));
+
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("@"))),
+            vec![
+                Box::new(IntegerMatcher::default()),
+                Box::new(SymbolMatcher::with_max_len(1, 0)),
+                Box::new(PrefixedMatcher::build_prefixed_matcher(
+                    '@',
+                    Box::new(WordMatcher::default()),
+                    20,
+                    1,
+                )),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "@" && t.token_type == TOKEN_TYPE_SYMBOL));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+    }
+
+    #[test]
+    fn matcher_prefixed_precedence() {
+        use crate::matcher_exact::ExactMatcher;
+        use crate::token::{TOKEN_TYPE_EXACT, TOKEN_TYPE_WORD};
+
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("@foo"))),
+            vec![
+                Box::new(PrefixedMatcher::build_prefixed_matcher(
+                    '@',
+                    Box::new(WordMatcher {
+                        precedence: 5,
+                        ..WordMatcher::default()
+                    }),
+                    20,
+                    2,
+                )),
+                Box::new(ExactMatcher::build_exact_matcher(vec!["@f"], TOKEN_TYPE_EXACT, 3)),
+                Box::new(WordMatcher::default()),
+            ],
+        );
+
+        // the shorter exact match wins since the prefixed match has a precedence of 2, not 5
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "@f" && t.token_type == TOKEN_TYPE_EXACT));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "oo" && t.token_type == TOKEN_TYPE_WORD));
+
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("@foo"))),
+            vec![
+                Box::new(IntegerMatcher::default()),
+                Box::new(SymbolMatcher::with_max_len(1, 0)),
+                Box::new(PrefixedMatcher::build_prefixed_matcher(
+                    '@',
+                    Box::new(WordMatcher::default()),
+                    20,
+                    1,
+                )),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "@foo" && t.precedence == 1));
+    }
+}
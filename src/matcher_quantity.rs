@@ -0,0 +1,237 @@
+use std::hash::BuildHasher;
+use crate::matcher::{Ctx, Matcher, MatcherResult};
+use crate::token::{Token, TOKEN_TYPE_QUANTITY};
+
+/// The key in [Lexx::ctx](crate::Lexx::ctx) the [QuantityMatcher] stores the length, in [char]s,
+/// of the number part of its last match under. Everything after it in the [Token] value is the
+/// unit.
+pub const QUANTITY_NUMBER_LEN: &str = "quantity_number_len";
+
+/// The QuantityMatcher matches a number immediately followed by one of a set of units, such as
+/// `5ms` or `2.5kg`, as a single [TOKEN_TYPE_QUANTITY] token. The number is an integer or a
+/// float like the [FloatMatcher](crate::matcher_float::FloatMatcher) accepts. A number that isn't
+/// followed by a known unit, or one followed by more letters or digits, does not match, leaving
+/// it for the other number matchers.
+///
+/// Whenever it matches, the length of the number part is stored in the `ctx` under
+/// [QUANTITY_NUMBER_LEN] so the value can be split into number and unit.
+///
+/// # Example
+///
+/// ```rust
+/// use lexx::{Lexx, Lexxer};
+/// use lexx::token::{TOKEN_TYPE_INTEGER, TOKEN_TYPE_QUANTITY};
+/// use lexx::input::InputString;
+/// use lexx::matcher_integer::IntegerMatcher;
+/// use lexx::matcher_quantity::{QuantityMatcher, QUANTITY_NUMBER_LEN};
+/// use lexx::matcher_whitespace::WhitespaceMatcher;
+///
+/// let mut lexx = Lexx::<512>::new(
+///     Box::new(InputString::new(String::from("2.5kg 5"))),
+///     vec![
+///         Box::new(IntegerMatcher { index: 0, precedence: 0, running: true, radix_prefixes: false }),
+///         Box::new(WhitespaceMatcher::with_predicate(char::is_whitespace, 0)),
+///         Box::new(QuantityMatcher::build_quantity_matcher(vec!["g", "kg", "ms"], 0)),
+///     ]
+/// );
+///
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "2.5kg" && t.token_type == TOKEN_TYPE_QUANTITY));
+/// assert_eq!(lexx.ctx.get(QUANTITY_NUMBER_LEN), Some(&3));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == " "));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "5" && t.token_type == TOKEN_TYPE_INTEGER));
+/// ```
+#[derive(Clone, Debug)]
+pub struct QuantityMatcher {
+    /// Current size of the ongoing match.
+    pub index: usize,
+    /// This matchers precedence.
+    pub precedence: u8,
+    /// If the matcher is currently running.
+    pub running: bool,
+    /// The length of the number part, 0 while still in the number.
+    pub number_len: usize,
+    /// If the dot of the number has been seen or not.
+    pub dot: bool,
+    /// The units that can follow the number.
+    pub units: Vec<String>,
+}
+
+impl<S: BuildHasher> Matcher<S> for QuantityMatcher {
+    fn reset(&mut self, _ctx: &mut Box<Ctx<S>>) {
+        self.index = 0;
+        self.number_len = 0;
+        self.dot = false;
+        self.running = true;
+    }
+
+    fn find_match(
+        &mut self,
+        oc: Option<char>,
+        value: &[char],
+        ctx: &mut Box<Ctx<S>>,
+    ) -> MatcherResult {
+        if self.number_len == 0 {
+            match oc {
+                Some(c) if c.is_ascii_digit() => {
+                    self.index += 1;
+                    return MatcherResult::Running();
+                }
+                Some('.') if !self.dot && self.index > 0 => {
+                    self.dot = true;
+                    self.index += 1;
+                    return MatcherResult::Running();
+                }
+                // the number must end with a digit
+                _ if self.index == 0 || value[self.index - 1] == '.' => {
+                    self.running = false;
+                    return MatcherResult::Failed();
+                }
+                _ => self.number_len = self.index,
+            }
+        }
+        let unit: String = value[self.number_len..self.index].iter().collect();
+        if let Some(c) = oc {
+            let mut extended = unit.clone();
+            extended.push(c);
+            if self.units.iter().any(|u| u.starts_with(&extended)) {
+                self.index += 1;
+                return MatcherResult::Running();
+            }
+        }
+        self.running = false;
+        if !self.units.contains(&unit) || matches!(oc, Some(c) if c.is_alphanumeric()) {
+            return MatcherResult::Failed();
+        }
+        ctx.insert(QUANTITY_NUMBER_LEN.to_string(), self.number_len as i32);
+        MatcherResult::Matched(Token {
+            value: value[0..self.index].iter().collect(),
+            token_type: TOKEN_TYPE_QUANTITY,
+            len: self.index,
+            line: 0,
+            column: self.index,
+            precedence: self.precedence,
+            ..Default::default()
+        })
+    }
+    fn is_running(&self) -> bool {
+        self.running
+    }
+    fn precedence(&self) -> u8 {
+        self.precedence
+    }
+    fn describe(&self) -> String {
+        format!(
+            "QuantityMatcher {{ precedence: {}, units: {:?} }}",
+            self.precedence, self.units
+        )
+    }
+    fn validate(&self) -> Result<(), String> {
+        if self.units.is_empty() {
+            return Err("QuantityMatcher has no units".to_string());
+        }
+        if self.units.iter().any(|u| u.is_empty()) {
+            return Err("QuantityMatcher has an empty unit".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl QuantityMatcher {
+    /// Build a quantity matcher
+    ///
+    /// # Arguments
+    ///
+    /// * `units` - a [vec] of the units that may follow a number
+    /// * `precedence` - the precedence for this matcher
+    ///
+    pub fn build_quantity_matcher(units: Vec<&str>, precedence: u8) -> QuantityMatcher {
+        QuantityMatcher {
+            index: 0,
+            precedence,
+            running: true,
+            number_len: 0,
+            dot: false,
+            units: units.into_iter().map(String::from).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::InputString;
+    use crate::matcher_float::FloatMatcher;
+    use crate::matcher_integer::IntegerMatcher;
+    use crate::matcher_quantity::{QuantityMatcher, QUANTITY_NUMBER_LEN};
+    use crate::matcher_whitespace::WhitespaceMatcher;
+    use crate::matcher_word::WordMatcher;
+    use crate::token::{TOKEN_TYPE_FLOAT, TOKEN_TYPE_INTEGER, TOKEN_TYPE_QUANTITY, TOKEN_TYPE_WORD};
+    use crate::{Lexx, Lexxer};
+
+    #[test]
+    fn matcher_quantity_matches_units() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("5ms 10kg 1.5m 50%"))),
+            vec![
+                Box::new(IntegerMatcher::default()),
+                Box::new(FloatMatcher::default()),
+                Box::new(WordMatcher::default()),
+                Box::new(WhitespaceMatcher::with_predicate(char::is_whitespace, 0)),
+                Box::new(QuantityMatcher::build_quantity_matcher(
+                    vec!["m", "ms", "kg", "%"],
+                    0,
+                )),
+            ],
+        );
+
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "5ms" && t.token_type == TOKEN_TYPE_QUANTITY && t.len == 3));
+        assert_eq!(lexx.ctx.get(QUANTITY_NUMBER_LEN), Some(&1));
+        lexx.next_token().unwrap();
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "10kg" && t.token_type == TOKEN_TYPE_QUANTITY && t.column == 5));
+        assert_eq!(lexx.ctx.get(QUANTITY_NUMBER_LEN), Some(&2));
+        lexx.next_token().unwrap();
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "1.5m" && t.token_type == TOKEN_TYPE_QUANTITY));
+        assert_eq!(lexx.ctx.get(QUANTITY_NUMBER_LEN), Some(&3));
+        lexx.next_token().unwrap();
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "50%" && t.token_type == TOKEN_TYPE_QUANTITY));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+    }
+
+    #[test]
+    fn matcher_quantity_needs_a_unit() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("5"))),
+            vec![
+                Box::new(IntegerMatcher::default()),
+                Box::new(FloatMatcher::default()),
+                Box::new(WordMatcher::default()),
+                Box::new(WhitespaceMatcher::with_predicate(char::is_whitespace, 0)),
+                Box::new(QuantityMatcher::build_quantity_matcher(
+                    vec!["m", "ms", "kg", "%"],
+                    0,
+                )),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "5" && t.token_type == TOKEN_TYPE_INTEGER));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("2.5 5kgs"))),
+            vec![
+                Box::new(IntegerMatcher::default()),
+                Box::new(FloatMatcher::default()),
+                Box::new(WordMatcher::default()),
+                Box::new(WhitespaceMatcher::with_predicate(char::is_whitespace, 0)),
+                Box::new(QuantityMatcher::build_quantity_matcher(
+                    vec!["m", "ms", "kg", "%"],
+                    0,
+                )),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "2.5" && t.token_type == TOKEN_TYPE_FLOAT));
+        lexx.next_token().unwrap();
+        // an unknown unit
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "5" && t.token_type == TOKEN_TYPE_INTEGER));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "kgs" && t.token_type == TOKEN_TYPE_WORD));
+        assert_eq!(lexx.ctx.get(QUANTITY_NUMBER_LEN), None);
+    }
+}
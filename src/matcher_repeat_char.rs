@@ -0,0 +1,213 @@
+use std::hash::BuildHasher;
+use crate::matcher::{Ctx, Matcher, MatcherResult};
+use crate::token::Token;
+
+/// The RepeatCharMatcher matches a run of a single given character, such as the `====` or `----`
+/// underlines of Markdown setext headers. The run must be at least `min` characters long, the
+/// `len` of the [Token] is the length of the run.
+///
+/// # Example
+///
+/// ```rust
+/// use lexx::{Lexx, Lexxer};
+/// use lexx::token::{TOKEN_TYPE_SYMBOL, TOKEN_TYPE_WHITESPACE};
+/// use lexx::input::InputString;
+/// use lexx::matcher_repeat_char::RepeatCharMatcher;
+/// use lexx::matcher_symbol::SymbolMatcher;
+/// use lexx::matcher_whitespace::WhitespaceMatcher;
+///
+/// let lexx_input = InputString::new(String::from("==== = ==+"));
+///
+/// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+///     Box::new(lexx_input),
+///     vec![
+///         Box::new(SymbolMatcher { index: 0, precedence: 0, running: true, max_len: 0 }),
+///         Box::new(WhitespaceMatcher { index: 0, column: 0, line: 0, precedence: 0, running: true, predicate: char::is_whitespace, line_continuation: None, mixed_indent: false }),
+///         // Note the precedence of 1 will cause the RepeatCharMatcher to be be returned
+///         // when the SymbolMatcher would have matched the same thing.
+///         Box::new(RepeatCharMatcher::build_repeat_char_matcher('=', 2, 20, 1)),
+///     ]
+/// ));
+///
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "====" && t.token_type == 20 && t.len == 4));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_WHITESPACE));
+/// // a single `=` is shorter than the minimum of 2
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "=" && t.token_type == TOKEN_TYPE_SYMBOL));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_WHITESPACE));
+/// // the SymbolMatcher would match "==+" but the RepeatCharMatcher has a higher precedence
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "==" && t.token_type == 20));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "+" && t.token_type == TOKEN_TYPE_SYMBOL));
+/// assert!(matches!(lexx.next_token(), Ok(None)));
+/// ```
+#[derive(Clone, Debug, Copy)]
+pub struct RepeatCharMatcher {
+    /// Current size of the ongoing match.
+    pub index: usize,
+    /// This matchers precedence.
+    pub precedence: u8,
+    /// If the matcher is currently running.
+    pub running: bool,
+    /// The character that is repeated.
+    pub target: char,
+    /// The minimum length of the run for it to match.
+    pub min: usize,
+    /// What token type to return if a match is made.
+    pub token_type: u16,
+}
+
+impl<S: BuildHasher> Matcher<S> for RepeatCharMatcher {
+    fn reset(&mut self, _ctx: &mut Box<Ctx<S>>) {
+        Matcher::<S>::reset_light(self);
+    }
+
+    fn is_stateful(&self) -> bool {
+        false
+    }
+
+    fn reset_light(&mut self) {
+        self.index = 0;
+        self.running = true;
+    }
+
+    fn find_match(
+        &mut self,
+        oc: Option<char>,
+        value: &[char],
+        _ctx: &mut Box<Ctx<S>>,
+    ) -> MatcherResult {
+        match oc {
+            Some(c) if c == self.target => {
+                self.index += 1;
+                MatcherResult::Running()
+            }
+            _ => self.generate_repeat_char_token(value),
+        }
+    }
+    fn is_running(&self) -> bool {
+        self.running
+    }
+    fn precedence(&self) -> u8 {
+        self.precedence
+    }
+    fn describe(&self) -> String {
+        format!(
+            "RepeatCharMatcher {{ precedence: {}, target: {:?}, min: {}, token_type: {} }}",
+            self.precedence, self.target, self.min, self.token_type
+        )
+    }
+    fn validate(&self) -> Result<(), String> {
+        if self.min == 0 {
+            return Err("RepeatCharMatcher min must be at least 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl RepeatCharMatcher {
+    /// Build a repeat char matcher
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - the [char] to match runs of
+    /// * `min` - the minimum length of a run, runs shorter than this are not matched
+    /// * `token_type` - the token type to produce
+    /// * `precedence` - the precedence for this matcher
+    ///
+    pub fn build_repeat_char_matcher(
+        target: char,
+        min: usize,
+        token_type: u16,
+        precedence: u8,
+    ) -> RepeatCharMatcher {
+        RepeatCharMatcher {
+            index: 0,
+            precedence,
+            running: true,
+            target,
+            min,
+            token_type,
+        }
+    }
+
+    #[inline(always)]
+    fn generate_repeat_char_token(&mut self, value: &[char]) -> MatcherResult {
+        self.running = false;
+        if self.index > 0 && self.index >= self.min {
+            MatcherResult::Matched(Token {
+                value: value[0..self.index].iter().collect(),
+                token_type: self.token_type,
+                len: self.index,
+                line: 0,
+                column: self.index,
+                precedence: self.precedence,
+                ..Default::default()
+            })
+        } else {
+            MatcherResult::Failed()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::InputString;
+    use crate::matcher_repeat_char::RepeatCharMatcher;
+    use crate::matcher_whitespace::WhitespaceMatcher;
+    use crate::token::TOKEN_TYPE_WHITESPACE;
+    use crate::{Lexx, LexxError, Lexxer};
+
+    #[test]
+    fn matcher_repeat_char_matches_run() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("====\n---"))),
+            vec![
+                Box::new(RepeatCharMatcher::build_repeat_char_matcher('=', 2, 20, 0)),
+                Box::new(RepeatCharMatcher::build_repeat_char_matcher('-', 2, 21, 0)),
+                Box::new(WhitespaceMatcher {
+                    index: 0,
+                    column: 0,
+                    line: 0,
+                    precedence: 0,
+                    running: true,
+                    predicate: char::is_whitespace,
+                    line_continuation: None,
+                    mixed_indent: false,
+                }),
+            ],
+        );
+
+        assert!(
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "====" && t.token_type == 20 && t.len == 4 && t.line == 1 && t.column == 1)
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_WHITESPACE));
+        assert!(
+            matches!(lexx.next_token(), Ok(Some(t)) if t.value == "---" && t.token_type == 21 && t.len == 3 && t.line == 2 && t.column == 1)
+        );
+        assert!(matches!(lexx.next_token(), Ok(None)));
+    }
+
+    #[test]
+    fn matcher_repeat_char_rejects_short_run() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("=x"))),
+            vec![Box::new(RepeatCharMatcher::build_repeat_char_matcher(
+                '=', 2, 20, 0,
+            ))],
+        );
+
+        assert!(matches!(
+            lexx.next_token(),
+            Err(LexxError::TokenNotFound(_))
+        ));
+
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("=="))),
+            vec![Box::new(RepeatCharMatcher::build_repeat_char_matcher(
+                '=', 2, 20, 0,
+            ))],
+        );
+
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "==" && t.len == 2));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+    }
+}
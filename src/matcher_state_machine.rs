@@ -0,0 +1,274 @@
+use std::hash::BuildHasher;
+use crate::matcher::{line_column_delta, Ctx, Matcher, MatcherResult};
+use crate::token::Token;
+
+/// Returns `true` for the [char]s in a class, such as [char::is_alphabetic].
+pub type CharClass = fn(char) -> bool;
+
+/// A move from one state to another, taken when the next [char] is in the `class`.
+#[derive(Clone, Debug, Copy)]
+pub struct Transition {
+    /// The state this move starts from.
+    pub from: usize,
+    /// Returns `true` for the [char]s this move is taken on.
+    pub class: CharClass,
+    /// The state this move ends in.
+    pub to: usize,
+}
+
+/// The StateMachineMatcher runs a deterministic state machine over the [char]s, for token shapes
+/// that none of the other matchers fit without writing a whole [Matcher]. It starts in state 0
+/// and follows the first [Transition] out of the current state whose `class` has the [char].
+/// When there is no [Transition] to follow it stops and matches everything up to the last time
+/// it was in one of the accepting states, or fails if it never was.
+///
+/// # Example
+///
+/// ```rust
+/// use lexx::{Lexx, Lexxer};
+/// use lexx::input::InputString;
+/// use lexx::matcher_state_machine::StateMachineMatcher;
+/// use lexx::matcher_whitespace::WhitespaceMatcher;
+///
+/// // a hex color such as #a0f or #aa00ff, 3 or 6 hex digits after a #
+/// let hex = |c: char| c.is_ascii_hexdigit();
+/// let color = StateMachineMatcher::build_state_machine_matcher(
+///     vec![
+///         (0, |c| c == '#', 1),
+///         (1, hex, 2),
+///         (2, hex, 3),
+///         (3, hex, 4),
+///         (4, hex, 5),
+///         (5, hex, 6),
+///         (6, hex, 7),
+///     ],
+///     &[4, 7],
+///     200,
+///     0,
+/// );
+///
+/// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+///     Box::new(InputString::new(String::from("#a0f #aa00ff"))),
+///     vec![
+///         Box::new(color),
+///         Box::new(WhitespaceMatcher::with_predicate(char::is_whitespace, 0)),
+///     ]
+/// ));
+///
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "#a0f" && t.token_type == 200));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == " "));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "#aa00ff" && t.token_type == 200 && t.column == 6));
+/// assert!(matches!(lexx.next_token(), Ok(None)));
+/// ```
+#[derive(Clone, Debug)]
+pub struct StateMachineMatcher {
+    /// Current size of the ongoing match.
+    pub index: usize,
+    /// This matchers precedence.
+    pub precedence: u8,
+    /// If the matcher is currently running.
+    pub running: bool,
+    /// The state the matcher is in.
+    pub state: usize,
+    /// The moves between states.
+    pub transitions: Vec<Transition>,
+    /// The states a match can end in.
+    pub accepting: Vec<usize>,
+    /// What token type to return if a match is made.
+    pub token_type: u16,
+    /// The length of the match the last time an accepting state was reached, 0 if none.
+    pub found: usize,
+}
+
+impl<S: BuildHasher> Matcher<S> for StateMachineMatcher {
+    fn reset(&mut self, _ctx: &mut Box<Ctx<S>>) {
+        Matcher::<S>::reset_light(self);
+    }
+
+    fn is_stateful(&self) -> bool {
+        false
+    }
+
+    fn reset_light(&mut self) {
+        self.index = 0;
+        self.state = 0;
+        self.found = 0;
+        self.running = true;
+    }
+
+    fn find_match(
+        &mut self,
+        oc: Option<char>,
+        value: &[char],
+        _ctx: &mut Box<Ctx<S>>,
+    ) -> MatcherResult {
+        let next = oc.and_then(|c| {
+            self.transitions
+                .iter()
+                .find(|t| t.from == self.state && (t.class)(c))
+        });
+        match next {
+            Some(t) => {
+                self.state = t.to;
+                self.index += 1;
+                if self.accepting.contains(&self.state) {
+                    self.found = self.index;
+                }
+                MatcherResult::Running()
+            }
+            None => self.generate_state_machine_token(value),
+        }
+    }
+    fn is_running(&self) -> bool {
+        self.running
+    }
+    fn precedence(&self) -> u8 {
+        self.precedence
+    }
+    fn describe(&self) -> String {
+        format!(
+            "StateMachineMatcher {{ precedence: {}, token_type: {}, transitions: {}, accepting: {:?} }}",
+            self.precedence,
+            self.token_type,
+            self.transitions.len(),
+            self.accepting
+        )
+    }
+    fn validate(&self) -> Result<(), String> {
+        if self.accepting.is_empty() {
+            return Err("StateMachineMatcher has no accepting states".to_string());
+        }
+        if self.accepting.contains(&0) {
+            return Err(
+                "StateMachineMatcher state 0 is accepting, it would match nothing".to_string(),
+            );
+        }
+        if !self.transitions.iter().any(|t| t.from == 0) {
+            return Err("StateMachineMatcher has no transitions from state 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl StateMachineMatcher {
+    /// Build a state machine matcher
+    ///
+    /// # Arguments
+    ///
+    /// * `transitions` - the `(from, class, to)` moves between states, the first one out of a
+    ///   state that has the [char] in its `class` is taken
+    /// * `accepting` - the states a match can end in
+    /// * `token_type` - the token type for the [Token]s this matcher makes
+    /// * `precedence` - the precedence for this matcher
+    ///
+    pub fn build_state_machine_matcher(
+        transitions: Vec<(usize, CharClass, usize)>,
+        accepting: &[usize],
+        token_type: u16,
+        precedence: u8,
+    ) -> StateMachineMatcher {
+        StateMachineMatcher {
+            index: 0,
+            precedence,
+            running: true,
+            state: 0,
+            transitions: transitions
+                .into_iter()
+                .map(|(from, class, to)| Transition { from, class, to })
+                .collect(),
+            accepting: accepting.to_vec(),
+            token_type,
+            found: 0,
+        }
+    }
+
+    #[inline(always)]
+    fn generate_state_machine_token(&mut self, value: &[char]) -> MatcherResult {
+        self.running = false;
+        if self.found == 0 {
+            return MatcherResult::Failed();
+        }
+        let matched = &value[0..self.found];
+        let (line, column) = line_column_delta(matched);
+        MatcherResult::Matched(Token {
+            value: matched.iter().collect(),
+            token_type: self.token_type,
+            len: self.found,
+            line,
+            column,
+            precedence: self.precedence,
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::InputString;
+    use crate::matcher::Matcher;
+    use crate::matcher_integer::IntegerMatcher;
+    use crate::matcher_state_machine::StateMachineMatcher;
+    use crate::matcher_symbol::SymbolMatcher;
+    use crate::matcher_whitespace::WhitespaceMatcher;
+    use crate::matcher_word::WordMatcher;
+    use crate::token::{TOKEN_TYPE_FLOAT, TOKEN_TYPE_INTEGER, TOKEN_TYPE_SYMBOL, TOKEN_TYPE_WORD};
+    use crate::{Lexx, LexxError, Lexxer};
+
+    fn float_machine() -> StateMachineMatcher {
+        let digit = |c: char| c.is_ascii_digit();
+        // 0 -digit-> 1 -'.'-> 2 -digit-> 3, with more digits looping on 1 and 3
+        StateMachineMatcher::build_state_machine_matcher(
+            vec![
+                (0, digit, 1),
+                (1, digit, 1),
+                (1, |c| c == '.', 2),
+                (2, digit, 3),
+                (3, digit, 3),
+            ],
+            &[3],
+            TOKEN_TYPE_FLOAT,
+            0,
+        )
+    }
+
+    #[test]
+    fn matcher_state_machine_float() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("1.5 12.25.7 x"))),
+            vec![
+                Box::new(float_machine()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(SymbolMatcher::default()),
+                Box::new(WordMatcher::default()),
+                Box::new(IntegerMatcher::default()),
+            ],
+        );
+
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "1.5" && t.token_type == TOKEN_TYPE_FLOAT && t.len == 3));
+        lexx.next_token().unwrap();
+        // there's no move for the second '.' so the match ends before it
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "12.25" && t.token_type == TOKEN_TYPE_FLOAT && t.column == 5));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "." && t.token_type == TOKEN_TYPE_SYMBOL));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "7" && t.token_type == TOKEN_TYPE_INTEGER));
+        lexx.next_token().unwrap();
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "x" && t.token_type == TOKEN_TYPE_WORD));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+    }
+
+    #[test]
+    fn matcher_state_machine_not_accepting() {
+        // "12." never reaches the accepting state, so nothing is matched
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("12.x"))),
+            vec![Box::new(float_machine())],
+        );
+        assert!(matches!(lexx.next_token(), Err(LexxError::TokenNotFound(_))));
+
+        let mut matcher = float_machine();
+        assert!(<StateMachineMatcher as Matcher>::validate(&matcher).is_ok());
+        matcher.accepting.clear();
+        assert!(<StateMachineMatcher as Matcher>::validate(&matcher).is_err());
+        matcher.accepting.push(0);
+        assert!(<StateMachineMatcher as Matcher>::validate(&matcher).is_err());
+    }
+}
@@ -0,0 +1,339 @@
+use std::hash::BuildHasher;
+use crate::matcher::{line_column_delta, Ctx, Matcher, MatcherResult};
+use crate::token::{Token, TOKEN_TYPE_STRING};
+
+/// The StringLiteralMatcher matches quoted strings such as `"a string"`, which may hold
+/// whitespace, symbols and line breaks. A `\` escapes the [char] after it, so `\"` doesn't end
+/// the string and `\\` is a backslash that doesn't escape the quote after it. Escapes are not
+/// decoded, the [Token] `value` is the raw text, quotes and backslashes and all. A string that is
+/// still open at the end of the input doesn't match.
+///
+/// With [no_escape](StringLiteralMatcher::no_escape) set a `\` is just another [char] and the
+/// string always ends at the next quote, as in shell single quoted strings, so `'it''s'` is the
+/// two strings `'it'` and `'s'`.
+///
+/// # Example
+///
+/// ```rust
+/// use lexx::{Lexx, Lexxer};
+/// use lexx::token::{TOKEN_TYPE_STRING, TOKEN_TYPE_WHITESPACE, TOKEN_TYPE_WORD};
+/// use lexx::input::InputString;
+/// use lexx::matcher_string::StringLiteralMatcher;
+/// use lexx::matcher_whitespace::WhitespaceMatcher;
+/// use lexx::matcher_word::WordMatcher;
+///
+/// let lexx_input = InputString::new(String::from(r#"say "a \"b\" c" 'd'"#));
+///
+/// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+///     Box::new(lexx_input),
+///     vec![
+///         Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] }),
+///         Box::new(WhitespaceMatcher::with_predicate(char::is_whitespace, 0)),
+///         Box::new(StringLiteralMatcher::build_string_literal_matcher('"', 0)),
+///         Box::new(StringLiteralMatcher::build_string_literal_matcher('\'', 0)),
+///     ]
+/// ));
+///
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "say" && t.token_type == TOKEN_TYPE_WORD));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_WHITESPACE));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == r#""a \"b\" c""# && t.token_type == TOKEN_TYPE_STRING && t.column == 5));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_WHITESPACE));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "'d'" && t.token_type == TOKEN_TYPE_STRING));
+/// assert!(matches!(lexx.next_token(), Ok(None)));
+/// ```
+#[derive(Clone, Debug, Copy)]
+pub struct StringLiteralMatcher {
+    /// Current size of the ongoing match.
+    pub index: usize,
+    /// This matchers precedence.
+    pub precedence: u8,
+    /// If the matcher is currently running.
+    pub running: bool,
+    /// The [char] that starts and ends a string, such as `"`.
+    pub quote: char,
+    /// If the last [char] was a `\` that escapes this one.
+    pub escaped: bool,
+    /// If a `\` is an ordinary [char] rather than an escape.
+    pub no_escape: bool,
+    /// If the closing quote has been seen.
+    pub closed: bool,
+}
+
+impl<S: BuildHasher> Matcher<S> for StringLiteralMatcher {
+    fn reset(&mut self, _ctx: &mut Box<Ctx<S>>) {
+        Matcher::<S>::reset_light(self);
+    }
+
+    fn is_stateful(&self) -> bool {
+        false
+    }
+
+    fn reset_light(&mut self) {
+        self.index = 0;
+        self.escaped = false;
+        self.closed = false;
+        self.running = true;
+    }
+
+    fn find_match(
+        &mut self,
+        oc: Option<char>,
+        value: &[char],
+        _ctx: &mut Box<Ctx<S>>,
+    ) -> MatcherResult {
+        if self.closed {
+            return self.generate_string_token(value);
+        }
+        let c = match oc {
+            Some(c) if self.index > 0 || c == self.quote => c,
+            // not a string, or the input ended before the closing quote
+            _ => {
+                self.running = false;
+                return MatcherResult::Failed();
+            }
+        };
+        if self.index > 0 {
+            if self.escaped {
+                self.escaped = false;
+            } else if c == '\\' && !self.no_escape {
+                self.escaped = true;
+            } else if c == self.quote {
+                self.closed = true;
+            }
+        }
+        self.index += 1;
+        MatcherResult::Running()
+    }
+    fn is_running(&self) -> bool {
+        self.running
+    }
+    fn precedence(&self) -> u8 {
+        self.precedence
+    }
+    fn describe(&self) -> String {
+        format!(
+            "StringLiteralMatcher {{ precedence: {}, quote: {:?}, no_escape: {} }}",
+            self.precedence, self.quote, self.no_escape
+        )
+    }
+}
+
+impl Default for StringLiteralMatcher {
+    /// A StringLiteralMatcher for `"` with a precedence of 0, ready to match.
+    fn default() -> Self {
+        StringLiteralMatcher::build_string_literal_matcher('"', 0)
+    }
+}
+
+impl StringLiteralMatcher {
+    /// Build a string literal matcher
+    ///
+    /// # Arguments
+    ///
+    /// * `quote` - the [char] that starts and ends a string, such as `"`
+    /// * `precedence` - the precedence for this matcher
+    ///
+    pub fn build_string_literal_matcher(quote: char, precedence: u8) -> StringLiteralMatcher {
+        StringLiteralMatcher {
+            index: 0,
+            precedence,
+            running: true,
+            quote,
+            escaped: false,
+            no_escape: false,
+            closed: false,
+        }
+    }
+
+    #[inline(always)]
+    fn generate_string_token(&mut self, value: &[char]) -> MatcherResult {
+        self.running = false;
+        let matched = &value[0..self.index];
+        let (line, column) = line_column_delta(matched);
+        MatcherResult::Matched(Token {
+            value: matched.iter().collect(),
+            token_type: TOKEN_TYPE_STRING,
+            len: self.index,
+            line,
+            column,
+            precedence: self.precedence,
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::input::{InputReader, InputString};
+    use crate::matcher_string::StringLiteralMatcher;
+    use crate::matcher_symbol::SymbolMatcher;
+    use crate::matcher_whitespace::WhitespaceMatcher;
+    use crate::matcher_word::WordMatcher;
+    use crate::token::{
+        TOKEN_TYPE_STRING, TOKEN_TYPE_SYMBOL, TOKEN_TYPE_WHITESPACE, TOKEN_TYPE_WORD,
+    };
+    use crate::{Lexx, LexxError, Lexxer};
+
+    #[test]
+    fn matcher_string_empty() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from(r#"""x"#))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(SymbolMatcher::with_max_len(1, 0)),
+                Box::new(StringLiteralMatcher::default()),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == r#""""# && t.token_type == TOKEN_TYPE_STRING && t.len == 2));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "x" && t.token_type == TOKEN_TYPE_WORD && t.column == 3));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+    }
+
+    #[test]
+    fn matcher_string_escapes() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from(r#""a\"b" "c\\" "d\n
+e";"#))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(SymbolMatcher::with_max_len(1, 0)),
+                Box::new(StringLiteralMatcher::default()),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == r#""a\"b""# && t.token_type == TOKEN_TYPE_STRING));
+        lexx.next_token().unwrap();
+        // the backslash is escaped so the quote after it ends the string
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == r#""c\\""# && t.column == 8));
+        lexx.next_token().unwrap();
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "\"d\\n\ne\"" && t.line == 1 && t.column == 14));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == ";" && t.token_type == TOKEN_TYPE_SYMBOL && t.line == 2 && t.column == 3));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+    }
+
+    #[test]
+    fn matcher_string_line_breaks() {
+        // a lone \r is a line break, as it is for the WhitespaceMatcher
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("\"a\rb\" x\r\n\"c\r\nd\";"))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(SymbolMatcher::with_max_len(1, 0)),
+                Box::new(StringLiteralMatcher::default()),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "\"a\rb\"" && t.line == 1 && t.column == 1));
+        lexx.next_token().unwrap();
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "x" && t.line == 2 && t.column == 4));
+        lexx.next_token().unwrap();
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "\"c\r\nd\"" && t.line == 3 && t.column == 1));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == ";" && t.line == 4 && t.column == 3));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+    }
+
+    #[test]
+    fn matcher_string_unterminated() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from(r#"a "b"#))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(StringLiteralMatcher::default()),
+            ],
+        );
+        lexx.next_token().unwrap();
+        lexx.next_token().unwrap();
+        assert!(matches!(lexx.next_token(), Err(LexxError::TokenNotFound(_))));
+
+        // an escaped quote doesn't close the string either
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from(r#""b\""#))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(StringLiteralMatcher::default()),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Err(LexxError::TokenNotFound(_))));
+
+        // with a symbol matcher the quote is just a symbol
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from(r#""b"#))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(SymbolMatcher::with_max_len(1, 0)),
+                Box::new(StringLiteralMatcher::default()),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "\"" && t.token_type == TOKEN_TYPE_SYMBOL));
+    }
+
+    #[test]
+    fn matcher_string_no_escape() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from(r"'a\'b'"))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(SymbolMatcher::with_max_len(1, 0)),
+                Box::new(StringLiteralMatcher::build_string_literal_matcher('\'', 0)),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == r"'a\'b'" && t.token_type == TOKEN_TYPE_STRING));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from(r"'a\'b'"))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(SymbolMatcher::with_max_len(1, 0)),
+                Box::new(StringLiteralMatcher {
+                    no_escape: true,
+                    ..StringLiteralMatcher::build_string_literal_matcher('\'', 0)
+                }),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == r"'a\'" && t.token_type == TOKEN_TYPE_STRING));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "b" && t.token_type == TOKEN_TYPE_WORD));
+        // the last quote is never closed
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "'" && t.token_type == TOKEN_TYPE_SYMBOL));
+
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("'it''s'"))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(SymbolMatcher::with_max_len(1, 0)),
+                Box::new(StringLiteralMatcher {
+                    no_escape: true,
+                    ..StringLiteralMatcher::build_string_literal_matcher('\'', 0)
+                }),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "'it'" && t.token_type == TOKEN_TYPE_STRING));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "'s'" && t.token_type == TOKEN_TYPE_STRING && t.column == 5));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+    }
+
+    #[test]
+    fn matcher_string_across_reads() {
+        // the string starts in one page of the reader's buffer and ends in the next
+        let text = format!("{}\"{}\" x", "abc ".repeat(250), "b c ".repeat(25));
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputReader::new(Cursor::new(text.into_bytes()))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(SymbolMatcher::with_max_len(1, 0)),
+                Box::new(StringLiteralMatcher::default()),
+            ],
+        );
+        let string = lexx.by_ref().find(|t| t.token_type != TOKEN_TYPE_WORD && t.token_type != TOKEN_TYPE_WHITESPACE);
+        assert!(matches!(string, Some(t) if t.token_type == TOKEN_TYPE_STRING && t.len == 102 && t.column == 1001));
+        lexx.next_token().unwrap();
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "x" && t.column == 1104));
+    }
+}
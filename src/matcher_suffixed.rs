@@ -0,0 +1,324 @@
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::BuildHasher;
+use crate::matcher::{Ctx, Matcher, MatcherResult};
+use crate::token::Token;
+
+/// The key in [Lexx::ctx](crate::Lexx::ctx) the [SuffixedMatcher] stores the length, in [char]s,
+/// of the suffix of its last match under, 0 if there was no suffix. Everything before it in the
+/// [Token] value is what the sub-matcher matched. It is written whether or not that match is the
+/// [Token] returned, see [SuffixedMatcher].
+pub const SUFFIX_LEN: &str = "suffix_len";
+
+/// The SuffixedMatcher matches whatever its sub-matcher matches, usually an
+/// [IntegerMatcher](crate::matcher_integer::IntegerMatcher) or a
+/// [FloatMatcher](crate::matcher_float::FloatMatcher), optionally followed by one of a set of
+/// suffixes, producing a single [Token] for both such as the `100u8` or `3.14f32` of Rust. The
+/// [Token] keeps the sub-matcher's token type but has the SuffixedMatcher's precedence, the
+/// sub-matcher's precedence is never used.
+///
+/// A suffix must immediately follow the match and must not be followed by a letter, digit or `_`,
+/// so neither the `x` of `100x` nor the `u8` of `100u8x` is taken. When there is no suffix the
+/// [Token] is the sub-matcher's match on its own. The longest suffix that fits wins.
+///
+/// Whenever it matches, the length of the suffix is stored in the `ctx` under [SUFFIX_LEN]. That
+/// happens as the match is made, before [Lexx](crate::Lexx) picks the winner, so when another
+/// [Matcher] wins, or another SuffixedMatcher matches later, it describes a match that wasn't
+/// returned. To split a returned [Token] into the number and its suffix use
+/// [split_suffix](crate::token::split_suffix) with the same suffixes.
+///
+/// # Example
+///
+/// ```rust
+/// use lexx::{Lexx, Lexxer};
+/// use lexx::token::{TOKEN_TYPE_FLOAT, TOKEN_TYPE_INTEGER};
+/// use lexx::input::InputString;
+/// use lexx::matcher_float::FloatMatcher;
+/// use lexx::matcher_integer::IntegerMatcher;
+/// use lexx::matcher_suffixed::{SuffixedMatcher, SUFFIX_LEN};
+/// use lexx::matcher_whitespace::WhitespaceMatcher;
+/// use lexx::token::split_suffix;
+///
+/// let mut lexx = Lexx::<512>::new(
+///     Box::new(InputString::new(String::from("100u8 3.14f32"))),
+///     vec![
+///         Box::new(SuffixedMatcher::build_suffixed_matcher(
+///             Box::new(IntegerMatcher::default()),
+///             vec!["u8", "i64"],
+///             0,
+///         )),
+///         Box::new(SuffixedMatcher::build_suffixed_matcher(
+///             Box::new(FloatMatcher::default()),
+///             vec!["f32", "f64"],
+///             0,
+///         )),
+///         Box::new(WhitespaceMatcher::with_predicate(char::is_whitespace, 0)),
+///     ]
+/// );
+///
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "100u8" && t.token_type == TOKEN_TYPE_INTEGER));
+/// assert_eq!(lexx.ctx.get(SUFFIX_LEN), Some(&2));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == " "));
+/// let float = lexx.next_token().unwrap().unwrap();
+/// assert!(float.value == "3.14f32" && float.token_type == TOKEN_TYPE_FLOAT);
+/// let (number, suffix) = split_suffix(float, &["f32", "f64"]);
+/// assert_eq!((number.value.as_str(), suffix.unwrap().value.as_str()), ("3.14", "f32"));
+/// ```
+pub struct SuffixedMatcher<S = RandomState> {
+    /// This matchers precedence.
+    pub precedence: u8,
+    /// If the matcher is currently running.
+    pub running: bool,
+    /// The [Matcher] for everything before the suffix.
+    pub sub_matcher: Box<dyn Matcher<S>>,
+    /// The suffixes that can follow the sub-matcher's match.
+    pub suffixes: Vec<Vec<char>>,
+    /// The sub-matcher's match, once it has made one.
+    pub matched: Option<Token>,
+}
+
+// not derived, that would need the hasher to be Debug
+impl<S> fmt::Debug for SuffixedMatcher<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SuffixedMatcher")
+            .field("precedence", &self.precedence)
+            .field("running", &self.running)
+            .field("sub_matcher", &self.sub_matcher)
+            .field("suffixes", &self.suffixes)
+            .field("matched", &self.matched)
+            .finish()
+    }
+}
+
+impl<S: BuildHasher> Matcher<S> for SuffixedMatcher<S> {
+    fn reset(&mut self, ctx: &mut Box<Ctx<S>>) {
+        self.running = true;
+        self.matched = None;
+        self.sub_matcher.reset(ctx);
+    }
+
+    fn find_match(
+        &mut self,
+        oc: Option<char>,
+        value: &[char],
+        ctx: &mut Box<Ctx<S>>,
+    ) -> MatcherResult {
+        if self.matched.is_none() {
+            match self.sub_matcher.find_match(oc, value, ctx) {
+                MatcherResult::Running() => return MatcherResult::Running(),
+                MatcherResult::Failed() => {
+                    self.running = false;
+                    return MatcherResult::Failed();
+                }
+                MatcherResult::Matched(token) => {
+                    let Some(token) = self.sub_matcher.on_match(token) else {
+                        self.running = false;
+                        return MatcherResult::Failed();
+                    };
+                    self.matched = Some(token);
+                }
+            }
+        }
+        let len = self.matched.as_ref().unwrap().len;
+        let tail = &value[len.min(value.len())..];
+        if oc.is_some()
+            && self
+                .suffixes
+                .iter()
+                .any(|s| s.len() >= tail.len() && s.starts_with(tail))
+        {
+            return MatcherResult::Running();
+        }
+        self.running = false;
+        let found = self
+            .suffixes
+            .iter()
+            .filter(|s| match tail.get(s.len()) {
+                Some(c) => tail.starts_with(s) && !(c.is_alphanumeric() || *c == '_'),
+                None => oc.is_none() && tail == s.as_slice(),
+            })
+            .map(|s| s.len())
+            .max()
+            .unwrap_or(0);
+        let token = self.matched.take().unwrap();
+        ctx.insert(SUFFIX_LEN.to_string(), found as i32);
+        MatcherResult::Matched(Token {
+            value: value[0..len + found].iter().collect(),
+            token_type: token.token_type,
+            len: len + found,
+            line: token.line,
+            column: token.column + found,
+            precedence: self.precedence,
+            ..Default::default()
+        })
+    }
+    fn is_running(&self) -> bool {
+        self.running
+    }
+    fn precedence(&self) -> u8 {
+        self.precedence
+    }
+    fn describe(&self) -> String {
+        let suffixes: Vec<String> = self.suffixes.iter().map(|s| s.iter().collect()).collect();
+        format!(
+            "SuffixedMatcher {{ precedence: {}, suffixes: {:?}, sub_matcher: {} }}",
+            self.precedence,
+            suffixes,
+            self.sub_matcher.describe()
+        )
+    }
+    fn validate(&self) -> Result<(), String> {
+        if self.suffixes.iter().any(|s| s.is_empty()) {
+            return Err("SuffixedMatcher has an empty suffix".to_string());
+        }
+        self.sub_matcher.validate()
+    }
+}
+
+impl<S> SuffixedMatcher<S> {
+    /// Build a suffixed matcher
+    ///
+    /// # Arguments
+    ///
+    /// * `sub_matcher` - the [Matcher] that must match what comes before the suffix
+    /// * `suffixes` - a [vec] of the suffixes that may follow its match
+    /// * `precedence` - the precedence for this matcher
+    ///
+    pub fn build_suffixed_matcher(
+        sub_matcher: Box<dyn Matcher<S>>,
+        suffixes: Vec<&str>,
+        precedence: u8,
+    ) -> SuffixedMatcher<S> {
+        SuffixedMatcher {
+            precedence,
+            running: true,
+            sub_matcher,
+            suffixes: suffixes.into_iter().map(|s| s.chars().collect()).collect(),
+            matched: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::InputString;
+    use crate::matcher::Matcher;
+    use crate::matcher_float::FloatMatcher;
+    use crate::matcher_integer::IntegerMatcher;
+    use crate::matcher_suffixed::{SuffixedMatcher, SUFFIX_LEN};
+    use crate::matcher_symbol::SymbolMatcher;
+    use crate::matcher_whitespace::WhitespaceMatcher;
+    use crate::matcher_word::WordMatcher;
+    use crate::token::{TOKEN_TYPE_FLOAT, TOKEN_TYPE_INTEGER, TOKEN_TYPE_SYMBOL, TOKEN_TYPE_WORD};
+    use crate::{Lexx, Lexxer};
+
+    const INTEGER_SUFFIXES: [&str; 5] = ["u8", "u16", "i64", "u", "usize"];
+
+    #[test]
+    fn matcher_suffixed_integer() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("100u8 0xFFi64 7u 8usize 9u16;"))),
+            vec![
+                Box::new(SuffixedMatcher::build_suffixed_matcher(
+                    Box::new(IntegerMatcher::with_radix_prefixes(0)),
+                    INTEGER_SUFFIXES.to_vec(),
+                    0,
+                )),
+                Box::new(SuffixedMatcher::build_suffixed_matcher(
+                    Box::new(FloatMatcher::default()),
+                    vec!["f32", "f64"],
+                    0,
+                )),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(WordMatcher::default()),
+                Box::new(SymbolMatcher::with_max_len(1, 0)),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "100u8" && t.token_type == TOKEN_TYPE_INTEGER && t.len == 5));
+        assert_eq!(lexx.ctx.get(SUFFIX_LEN), Some(&2));
+        lexx.next_token().unwrap();
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "0xFFi64" && t.token_type == TOKEN_TYPE_INTEGER && t.column == 7));
+        lexx.next_token().unwrap();
+        // the longest suffix that fits wins
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "7u"));
+        lexx.next_token().unwrap();
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "8usize"));
+        lexx.next_token().unwrap();
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "9u16" && t.column == 25));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == ";" && t.token_type == TOKEN_TYPE_SYMBOL && t.column == 29));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+    }
+
+    #[test]
+    fn matcher_suffixed_float() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("3.14f32 2.5f64"))),
+            vec![
+                Box::new(SuffixedMatcher::build_suffixed_matcher(
+                    Box::new(IntegerMatcher::with_radix_prefixes(0)),
+                    INTEGER_SUFFIXES.to_vec(),
+                    0,
+                )),
+                Box::new(SuffixedMatcher::build_suffixed_matcher(
+                    Box::new(FloatMatcher::default()),
+                    vec!["f32", "f64"],
+                    0,
+                )),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(WordMatcher::default()),
+                Box::new(SymbolMatcher::with_max_len(1, 0)),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "3.14f32" && t.token_type == TOKEN_TYPE_FLOAT));
+        assert_eq!(lexx.ctx.get(SUFFIX_LEN), Some(&3));
+        lexx.next_token().unwrap();
+        // a suffix at the end of the input
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "2.5f64" && t.token_type == TOKEN_TYPE_FLOAT));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+    }
+
+    #[test]
+    fn matcher_suffixed_unknown_suffix() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("100x 5u8x 6u1 3.5f 7"))),
+            vec![
+                Box::new(SuffixedMatcher::build_suffixed_matcher(
+                    Box::new(IntegerMatcher::with_radix_prefixes(0)),
+                    INTEGER_SUFFIXES.to_vec(),
+                    0,
+                )),
+                Box::new(SuffixedMatcher::build_suffixed_matcher(
+                    Box::new(FloatMatcher::default()),
+                    vec!["f32", "f64"],
+                    0,
+                )),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(WordMatcher::default()),
+                Box::new(SymbolMatcher::with_max_len(1, 0)),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "100" && t.token_type == TOKEN_TYPE_INTEGER));
+        assert_eq!(lexx.ctx.get(SUFFIX_LEN), Some(&0));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "x" && t.token_type == TOKEN_TYPE_WORD && t.column == 4));
+        lexx.next_token().unwrap();
+        // a suffix can't run into more letters or digits
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "5"));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "u" && t.token_type == TOKEN_TYPE_WORD));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "8"));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "x"));
+        lexx.next_token().unwrap();
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "6"));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "u"));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "1"));
+        lexx.next_token().unwrap();
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "3.5" && t.token_type == TOKEN_TYPE_FLOAT));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "f"));
+        lexx.next_token().unwrap();
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "7" && t.token_type == TOKEN_TYPE_INTEGER));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+
+        let matcher: SuffixedMatcher =
+            SuffixedMatcher::build_suffixed_matcher(Box::new(IntegerMatcher::default()), vec![""], 0);
+        assert!(<SuffixedMatcher as Matcher>::validate(&matcher).is_err());
+    }
+}
@@ -1,11 +1,17 @@
-use crate::matcher::{Matcher, MatcherResult};
+use std::hash::BuildHasher;
+use crate::matcher::{Ctx, Matcher, MatcherResult};
 use crate::token::{Token, TOKEN_TYPE_SYMBOL};
-use std::collections::HashMap;
 
 /// The SymbolMatcher matches any series of characters that do NOT match `is_whitespace()` or
 /// `c.is_alphanumeric()`. That is, any character that is not a number, letter or whitespace
 /// will be matched by this matcher.
 ///
+/// Runs of symbols are matched greedily, so adjacent operators like `!===` come out as one
+/// [Token]. Setting [SymbolMatcher::max_len], for example with
+/// [with_max_len](SymbolMatcher::with_max_len), breaks runs into [Token]s of at most that many
+/// [char]s instead, which is useful for operators that aren't handled by an
+/// [ExactMatcher](crate::matcher_exact::ExactMatcher).
+///
 /// # Example
 ///
 /// ```rust
@@ -20,7 +26,7 @@ use std::collections::HashMap;
 /// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
 ///     Box::new(lexx_input),
 ///     vec![
-///         Box::new(SymbolMatcher { index: 0, precedence: 0, running: true }),
+///         Box::new(SymbolMatcher { index: 0, precedence: 0, running: true, max_len: 0 }),
 ///         // Note the precedence of 1 will cause the ExactMatcher to be be returned
 ///         // when the SymbolMatcher would have matched the same, or a longer thing.
 ///         Box::new(ExactMatcher::build_exact_matcher(vec!["^", "$gxv ", "gxv ", "llj)9", "d$rrr"], TOKEN_TYPE_EXACT, 1)),
@@ -49,10 +55,20 @@ pub struct SymbolMatcher {
     pub precedence: u8,
     /// If the matcher is currently running.
     pub running: bool,
+    /// The most [char]s matched in one [Token], 0 for no limit.
+    pub max_len: usize,
 }
 
-impl Matcher for SymbolMatcher {
-    fn reset(&mut self, _ctx: &mut Box<HashMap<String, i32>>) {
+impl<S: BuildHasher> Matcher<S> for SymbolMatcher {
+    fn reset(&mut self, _ctx: &mut Box<Ctx<S>>) {
+        Matcher::<S>::reset_light(self);
+    }
+
+    fn is_stateful(&self) -> bool {
+        false
+    }
+
+    fn reset_light(&mut self) {
         self.index = 0;
         self.running = true;
     }
@@ -61,12 +77,14 @@ impl Matcher for SymbolMatcher {
         &mut self,
         oc: Option<char>,
         value: &[char],
-        _ctx: &mut Box<HashMap<String, i32>>,
+        _ctx: &mut Box<Ctx<S>>,
     ) -> MatcherResult {
         return match oc {
             None => self.generate_symbol_token(value),
             Some(c) => {
-                if !c.is_whitespace() && !c.is_alphanumeric() {
+                if self.max_len > 0 && self.index == self.max_len {
+                    self.generate_symbol_token(value)
+                } else if !c.is_whitespace() && !c.is_alphanumeric() {
                     self.index += 1;
                     MatcherResult::Running()
                 } else {
@@ -81,9 +99,43 @@ impl Matcher for SymbolMatcher {
     fn precedence(&self) -> u8 {
         self.precedence
     }
+    fn describe(&self) -> String {
+        format!(
+            "SymbolMatcher {{ precedence: {}, max_len: {} }}",
+            self.precedence, self.max_len
+        )
+    }
+}
+
+impl Default for SymbolMatcher {
+    /// A SymbolMatcher with a precedence of 0 with no limit on the length of a match, ready to match.
+    fn default() -> Self {
+        SymbolMatcher {
+            index: 0,
+            precedence: 0,
+            running: true,
+            max_len: 0,
+        }
+    }
 }
 
 impl SymbolMatcher {
+    /// Build a symbol matcher that matches at most `max_len` [char]s at a time
+    ///
+    /// # Arguments
+    ///
+    /// * `max_len` - the most [char]s to match in one [Token], 0 for no limit
+    /// * `precedence` - the precedence for this matcher
+    ///
+    pub fn with_max_len(max_len: usize, precedence: u8) -> SymbolMatcher {
+        SymbolMatcher {
+            index: 0,
+            precedence,
+            running: true,
+            max_len,
+        }
+    }
+
     #[inline(always)]
     fn generate_symbol_token(&mut self, value: &[char]) -> MatcherResult {
         self.running = false;
@@ -95,9 +147,63 @@ impl SymbolMatcher {
                 line: 0,
                 column: self.index,
                 precedence: self.precedence,
+                ..Default::default()
             })
         } else {
             MatcherResult::Failed()
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::input::InputString;
+    use crate::matcher_symbol::SymbolMatcher;
+    use crate::matcher_word::WordMatcher;
+    use crate::token::{TOKEN_TYPE_SYMBOL, TOKEN_TYPE_WORD};
+    use crate::{Lexx, Lexxer};
+
+    #[test]
+    fn matcher_symbol_max_len() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("a!===b!"))),
+            vec![
+                Box::new(WordMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    trailing: &[],
+                }),
+                Box::new(SymbolMatcher::with_max_len(2, 0)),
+            ],
+        );
+
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "a" && t.token_type == TOKEN_TYPE_WORD));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "!=" && t.token_type == TOKEN_TYPE_SYMBOL && t.column == 2));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "==" && t.token_type == TOKEN_TYPE_SYMBOL && t.column == 4));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "b" && t.token_type == TOKEN_TYPE_WORD));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "!" && t.token_type == TOKEN_TYPE_SYMBOL));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+
+        // no limit
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("!===+"))),
+            vec![Box::new(SymbolMatcher::with_max_len(0, 0))],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "!===+"));
+    }
+
+    #[test]
+    fn matcher_symbol_default() {
+        let explicit = SymbolMatcher {
+            index: 0,
+            precedence: 0,
+            running: true,
+            max_len: 0,
+        };
+        assert_eq!(
+            format!("{:?}", SymbolMatcher::default()),
+            format!("{:?}", explicit)
+        );
+    }
+}
@@ -0,0 +1,211 @@
+use std::hash::BuildHasher;
+use crate::matcher::{line_column_delta, Ctx, Matcher, MatcherResult};
+use crate::token::{Token, TOKEN_TYPE_TAG};
+
+/// The TagMatcher matches an XML or HTML style tag such as `<div>`, `</div>` or `<br/>` as a
+/// single [TOKEN_TYPE_TAG] [Token]. It runs from a `<` to the next `>`, the attributes are not
+/// parsed, the [Token] value is just the raw text of the tag. The `<` must be followed directly
+/// by something other than whitespace, so `a < b` is not the start of a tag, and there can't be
+/// another `<` before the `>`. If the input ends before the `>` nothing is matched.
+///
+/// A tag may span lines, the line and column after it are tracked.
+///
+/// # Example
+///
+/// ```rust
+/// use lexx::{Lexx, Lexxer};
+/// use lexx::token::{TOKEN_TYPE_TAG, TOKEN_TYPE_WORD};
+/// use lexx::input::InputString;
+/// use lexx::matcher_tag::TagMatcher;
+/// use lexx::matcher_word::WordMatcher;
+///
+/// let lexx_input = InputString::new(String::from("<p class=\"x\">Hi<br/></p>"));
+///
+/// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+///     Box::new(lexx_input),
+///     vec![
+///         Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] }),
+///         Box::new(TagMatcher { index: 0, precedence: 0, running: true }),
+///     ]
+/// ));
+///
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "<p class=\"x\">" && t.token_type == TOKEN_TYPE_TAG));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "Hi" && t.token_type == TOKEN_TYPE_WORD));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "<br/>" && t.token_type == TOKEN_TYPE_TAG));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "</p>" && t.token_type == TOKEN_TYPE_TAG && t.column == 21));
+/// assert!(matches!(lexx.next_token(), Ok(None)));
+/// ```
+#[derive(Clone, Debug, Copy)]
+pub struct TagMatcher {
+    /// Current size of the ongoing match.
+    pub index: usize,
+    /// This matchers precedence.
+    pub precedence: u8,
+    /// If the matcher is currently running.
+    pub running: bool,
+}
+
+impl<S: BuildHasher> Matcher<S> for TagMatcher {
+    fn reset(&mut self, _ctx: &mut Box<Ctx<S>>) {
+        Matcher::<S>::reset_light(self);
+    }
+
+    fn is_stateful(&self) -> bool {
+        false
+    }
+
+    fn reset_light(&mut self) {
+        self.index = 0;
+        self.running = true;
+    }
+
+    fn find_match(
+        &mut self,
+        oc: Option<char>,
+        value: &[char],
+        _ctx: &mut Box<Ctx<S>>,
+    ) -> MatcherResult {
+        match oc {
+            Some('<') if self.index == 0 => {
+                self.index += 1;
+                MatcherResult::Running()
+            }
+            Some(c) if self.index == 1 && !c.is_whitespace() && c != '<' && c != '>' => {
+                self.index += 1;
+                MatcherResult::Running()
+            }
+            Some('>') if self.index > 1 => {
+                self.index += 1;
+                self.generate_tag_token(value)
+            }
+            Some(c) if self.index > 1 && c != '<' => {
+                self.index += 1;
+                MatcherResult::Running()
+            }
+            _ => {
+                self.running = false;
+                MatcherResult::Failed()
+            }
+        }
+    }
+    fn is_running(&self) -> bool {
+        self.running
+    }
+    fn precedence(&self) -> u8 {
+        self.precedence
+    }
+    fn describe(&self) -> String {
+        format!("TagMatcher {{ precedence: {} }}", self.precedence)
+    }
+}
+
+impl Default for TagMatcher {
+    /// A TagMatcher with a precedence of 0, ready to match.
+    fn default() -> Self {
+        TagMatcher {
+            index: 0,
+            precedence: 0,
+            running: true,
+        }
+    }
+}
+
+impl TagMatcher {
+    #[inline(always)]
+    fn generate_tag_token(&mut self, value: &[char]) -> MatcherResult {
+        self.running = false;
+        let tag = &value[0..self.index];
+        let (line, column) = line_column_delta(tag);
+        MatcherResult::Matched(Token {
+            value: tag.iter().collect(),
+            token_type: TOKEN_TYPE_TAG,
+            len: self.index,
+            line,
+            column,
+            precedence: self.precedence,
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::InputString;
+    use crate::matcher_symbol::SymbolMatcher;
+    use crate::matcher_tag::TagMatcher;
+    use crate::matcher_whitespace::WhitespaceMatcher;
+    use crate::matcher_word::WordMatcher;
+    use crate::token::{TOKEN_TYPE_SYMBOL, TOKEN_TYPE_TAG, TOKEN_TYPE_WORD};
+    use crate::{Lexx, LexxError, Lexxer};
+
+    #[test]
+    fn matcher_tag_tags() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("<div>a</div> <br/>"))),
+            vec![
+                Box::new(TagMatcher::default()),
+                Box::new(WordMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "<div>" && t.token_type == TOKEN_TYPE_TAG && t.len == 5));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "a" && t.token_type == TOKEN_TYPE_WORD && t.column == 6));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "</div>" && t.token_type == TOKEN_TYPE_TAG && t.column == 7));
+        lexx.next_token().unwrap();
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "<br/>" && t.token_type == TOKEN_TYPE_TAG && t.column == 14));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+
+        // the position is tracked across lines
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("<a\n  href=x>b"))),
+            vec![
+                Box::new(TagMatcher::default()),
+                Box::new(WordMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "<a\n  href=x>" && t.line == 1 && t.column == 1));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "b" && t.line == 2 && t.column == 10));
+    }
+
+    #[test]
+    fn matcher_tag_unterminated() {
+        // the input ends before the >, so nothing matches what is left
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("<div"))),
+            vec![
+                Box::new(TagMatcher::default()),
+                Box::new(WordMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Err(LexxError::TokenNotFound(_))));
+
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("<div<p>"))),
+            vec![
+                Box::new(TagMatcher::default()),
+                Box::new(WordMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Err(LexxError::TokenNotFound(_))));
+
+        // not a tag, no name after the < and another < before the >
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("< a<b<c>"))),
+            vec![
+                Box::new(TagMatcher::default()),
+                Box::new(WordMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(SymbolMatcher::default()),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "<" && t.token_type == TOKEN_TYPE_SYMBOL));
+        lexx.next_token().unwrap();
+        lexx.next_token().unwrap();
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "<" && t.token_type == TOKEN_TYPE_SYMBOL));
+        lexx.next_token().unwrap();
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "<c>" && t.token_type == TOKEN_TYPE_TAG));
+    }
+}
@@ -0,0 +1,288 @@
+use std::hash::BuildHasher;
+use crate::matcher::{Ctx, Matcher, MatcherResult};
+use crate::token::{Token, TOKEN_TYPE_URL};
+
+/// The UrlMatcher matches URLs such as `https://example.com/a%20b?q=1`. A URL is a scheme of
+/// alphanumerics and `+.-` starting with a letter, then `://`, then at least one of the
+/// characters a URL may contain. A `%` must be followed by two hex digits, a malformed
+/// percent-encoding such as `%2` or `%ZZ` ends the URL just before the `%`.
+///
+/// This is NOT a full RFC 3986 parser, it is meant to find the common forms of URLs in text. Like
+/// the [EmailMatcher](crate::matcher_email::EmailMatcher) punctuation at the end, such as the `.`
+/// ending a sentence, is not included in the match.
+///
+/// # Example
+///
+/// ```rust
+/// use lexx::{Lexx, Lexxer};
+/// use lexx::token::{TOKEN_TYPE_SYMBOL, TOKEN_TYPE_URL, TOKEN_TYPE_WHITESPACE, TOKEN_TYPE_WORD};
+/// use lexx::input::InputString;
+/// use lexx::matcher_symbol::SymbolMatcher;
+/// use lexx::matcher_url::UrlMatcher;
+/// use lexx::matcher_whitespace::WhitespaceMatcher;
+/// use lexx::matcher_word::WordMatcher;
+///
+/// let lexx_input = InputString::new(String::from("see https://a.com/%20b?q=1."));
+///
+/// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+///     Box::new(lexx_input),
+///     vec![
+///         Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] }),
+///         Box::new(WhitespaceMatcher::with_predicate(char::is_whitespace, 0)),
+///         Box::new(SymbolMatcher { index: 0, precedence: 0, running: true, max_len: 0 }),
+///         Box::new(UrlMatcher::build_url_matcher(0)),
+///     ]
+/// ));
+///
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "see" && t.token_type == TOKEN_TYPE_WORD));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_WHITESPACE));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "https://a.com/%20b?q=1" && t.token_type == TOKEN_TYPE_URL && t.column == 5));
+/// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "." && t.token_type == TOKEN_TYPE_SYMBOL));
+/// assert!(matches!(lexx.next_token(), Ok(None)));
+/// ```
+#[derive(Clone, Debug, Copy)]
+pub struct UrlMatcher {
+    /// Current size of the ongoing match.
+    pub index: usize,
+    /// This matchers precedence.
+    pub precedence: u8,
+    /// If the matcher is currently running.
+    pub running: bool,
+    /// How many chars of the `://` after the scheme have been seen.
+    pub separator: usize,
+    /// How many hex digits are still needed to finish a `%` escape.
+    pub escape: usize,
+    /// The length of the longest valid URL seen so far, 0 if none.
+    pub found: usize,
+}
+
+impl<S: BuildHasher> Matcher<S> for UrlMatcher {
+    fn reset(&mut self, _ctx: &mut Box<Ctx<S>>) {
+        Matcher::<S>::reset_light(self);
+    }
+
+    fn is_stateful(&self) -> bool {
+        false
+    }
+
+    fn reset_light(&mut self) {
+        self.index = 0;
+        self.separator = 0;
+        self.escape = 0;
+        self.found = 0;
+        self.running = true;
+    }
+
+    fn find_match(
+        &mut self,
+        oc: Option<char>,
+        value: &[char],
+        _ctx: &mut Box<Ctx<S>>,
+    ) -> MatcherResult {
+        let c = match oc {
+            None => return self.generate_url_token(value),
+            Some(c) => c,
+        };
+        match self.separator {
+            0 => {
+                if c == ':' && self.index > 0 {
+                    self.separator = 1;
+                } else if !(c.is_ascii_alphabetic()
+                    || (self.index > 0 && (c.is_ascii_digit() || "+.-".contains(c))))
+                {
+                    return self.generate_url_token(value);
+                }
+            }
+            1 | 2 => {
+                if c != '/' {
+                    return self.generate_url_token(value);
+                }
+                self.separator += 1;
+            }
+            _ => {
+                if self.escape > 0 {
+                    if !c.is_ascii_hexdigit() {
+                        return self.generate_url_token(value);
+                    }
+                    self.escape -= 1;
+                    if self.escape == 0 {
+                        self.found = self.index + 1;
+                    }
+                } else if c == '%' {
+                    self.escape = 2;
+                } else if c.is_ascii_alphanumeric() || "-_~/#[]@$&'()*+=".contains(c) {
+                    self.found = self.index + 1;
+                } else if !".,;:!?".contains(c) {
+                    return self.generate_url_token(value);
+                }
+            }
+        }
+        self.index += 1;
+        MatcherResult::Running()
+    }
+    fn is_running(&self) -> bool {
+        self.running
+    }
+    fn precedence(&self) -> u8 {
+        self.precedence
+    }
+    fn describe(&self) -> String {
+        format!("UrlMatcher {{ precedence: {} }}", self.precedence)
+    }
+}
+
+impl Default for UrlMatcher {
+    /// A UrlMatcher with a precedence of 0, ready to match.
+    fn default() -> Self {
+        UrlMatcher::build_url_matcher(0)
+    }
+}
+
+impl UrlMatcher {
+    /// Build a url matcher
+    ///
+    /// # Arguments
+    ///
+    /// * `precedence` - the precedence for this matcher
+    ///
+    pub fn build_url_matcher(precedence: u8) -> UrlMatcher {
+        UrlMatcher {
+            index: 0,
+            precedence,
+            running: true,
+            separator: 0,
+            escape: 0,
+            found: 0,
+        }
+    }
+
+    #[inline(always)]
+    fn generate_url_token(&mut self, value: &[char]) -> MatcherResult {
+        self.running = false;
+        if self.found > 0 {
+            MatcherResult::Matched(Token {
+                value: value[0..self.found].iter().collect(),
+                token_type: TOKEN_TYPE_URL,
+                len: self.found,
+                line: 0,
+                column: self.found,
+                precedence: self.precedence,
+                ..Default::default()
+            })
+        } else {
+            MatcherResult::Failed()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::InputString;
+    use crate::matcher_integer::IntegerMatcher;
+    use crate::matcher_symbol::SymbolMatcher;
+    use crate::matcher_url::UrlMatcher;
+    use crate::matcher_whitespace::WhitespaceMatcher;
+    use crate::matcher_word::WordMatcher;
+    use crate::token::{TOKEN_TYPE_SYMBOL, TOKEN_TYPE_URL, TOKEN_TYPE_WORD};
+    use crate::{Lexx, Lexxer};
+
+    #[test]
+    fn matcher_url_percent_encoding() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("http://a.com/%20b"))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(IntegerMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(SymbolMatcher::default()),
+                Box::new(UrlMatcher::default()),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "http://a.com/%20b" && t.token_type == TOKEN_TYPE_URL && t.len == 17));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("ftp://a.com/%aF."))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(IntegerMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(SymbolMatcher::default()),
+                Box::new(UrlMatcher::default()),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "ftp://a.com/%aF" && t.token_type == TOKEN_TYPE_URL));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "." && t.token_type == TOKEN_TYPE_SYMBOL));
+    }
+
+    #[test]
+    fn matcher_url_malformed_percent_encoding() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("http://a.com/%2"))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(IntegerMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(SymbolMatcher::default()),
+                Box::new(UrlMatcher::default()),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "http://a.com/" && t.token_type == TOKEN_TYPE_URL));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "%" && t.token_type == TOKEN_TYPE_SYMBOL && t.column == 14));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "2" && t.column == 15));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("http://a.com/b%ZZ"))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(IntegerMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(SymbolMatcher::default()),
+                Box::new(UrlMatcher::default()),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "http://a.com/b" && t.token_type == TOKEN_TYPE_URL));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "%" && t.token_type == TOKEN_TYPE_SYMBOL));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "ZZ" && t.token_type == TOKEN_TYPE_WORD));
+    }
+
+    #[test]
+    fn matcher_url_needs_scheme_and_separator() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("http:/a"))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(IntegerMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(SymbolMatcher::default()),
+                Box::new(UrlMatcher::default()),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "http" && t.token_type == TOKEN_TYPE_WORD));
+
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("http:// a"))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(IntegerMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(SymbolMatcher::default()),
+                Box::new(UrlMatcher::default()),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "http" && t.token_type == TOKEN_TYPE_WORD));
+
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("://a"))),
+            vec![
+                Box::new(WordMatcher::default()),
+                Box::new(IntegerMatcher::default()),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(SymbolMatcher::default()),
+                Box::new(UrlMatcher::default()),
+            ],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "://" && t.token_type == TOKEN_TYPE_SYMBOL));
+    }
+}
@@ -1,8 +1,30 @@
-use crate::matcher::{Matcher, MatcherResult};
+use std::hash::BuildHasher;
+use crate::matcher::{Ctx, Matcher, MatcherResult};
 pub use crate::token::{Token, TOKEN_TYPE_WHITESPACE};
-use std::collections::HashMap;
 
-/// The WhitespaceMatcher matches any series of characters that are `is_whitespace()`.
+/// The key in [Lexx::ctx](crate::Lexx::ctx) a [WhitespaceMatcher] with
+/// [mixed_indent](WhitespaceMatcher::mixed_indent) set stores whether the indentation in its last
+/// match mixed tabs and spaces under, 1 if it did and 0 if it didn't.
+pub const WHITESPACE_MIXED_INDENT: &str = "whitespace_mixed_indent";
+
+/// The WhitespaceMatcher matches any series of characters that are `is_whitespace()`, or that
+/// pass its [predicate](WhitespaceMatcher::predicate) if it was made with
+/// [with_predicate](WhitespaceMatcher::with_predicate). Either way `\n` and `\r` are used to
+/// keep track of lines and columns, `\n`, `\r\n` and a lone `\r` each end a line.
+///
+/// If a [line_continuation](WhitespaceMatcher::line_continuation) char is set then that char
+/// followed by a line break, such as the `\` at the end of a line in a shell script or Makefile,
+/// is matched as whitespace too, even if the predicate doesn't match the line break itself. So
+/// with a predicate that leaves line breaks to a
+/// [NewlineMatcher](crate::matcher_newline::NewlineMatcher) a continued line stays one logical
+/// line. The line and column of later [Token]s still count the physical lines. The char on its
+/// own is not whitespace.
+///
+/// If [mixed_indent](WhitespaceMatcher::mixed_indent) is set then, whenever it matches, whether
+/// the indentation mixes `\t` and ` ` is stored in the `ctx` under [WHITESPACE_MIXED_INDENT], for
+/// linters. The indentation is the part of the match after its last line break. A match without
+/// a line break is checked whole since the matcher can't tell if it starts a line, it is only
+/// indentation if the [Token] is at column 1.
 ///
 /// # Example
 ///
@@ -18,8 +40,8 @@ use std::collections::HashMap;
 /// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
 ///     Box::new(lexx_input),
 ///     vec![
-///         Box::new(WhitespaceMatcher { index: 0, column: 0,line: 0,precedence: 0, running: true}),
-///         Box::new(WordMatcher { index: 0, precedence: 0, running: true }),
+///         Box::new(WhitespaceMatcher { index: 0, column: 0,line: 0,precedence: 0, running: true, predicate: char::is_whitespace, line_continuation: None, mixed_indent: false}),
+///         Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] }),
 ///     ]
 /// ));
 ///
@@ -43,10 +65,17 @@ pub struct WhitespaceMatcher {
     pub precedence: u8,
     /// If the matcher is currently running.
     pub running: bool,
+    /// Decides which chars count as whitespace, usually `char::is_whitespace`.
+    pub predicate: fn(char) -> bool,
+    /// The char that, right before a line break, continues the line, such as `\`.
+    pub line_continuation: Option<char>,
+    /// If whether the indentation mixes tabs and spaces is stored in the `ctx`, see
+    /// [WHITESPACE_MIXED_INDENT].
+    pub mixed_indent: bool,
 }
 
-impl Matcher for WhitespaceMatcher {
-    fn reset(&mut self, _ctx: &mut Box<HashMap<String, i32>>) {
+impl<S: BuildHasher> Matcher<S> for WhitespaceMatcher {
+    fn reset(&mut self, _ctx: &mut Box<Ctx<S>>) {
         self.index = 0;
         self.line = 0;
         self.column = 0;
@@ -57,27 +86,46 @@ impl Matcher for WhitespaceMatcher {
         &mut self,
         oc: Option<char>,
         value: &[char],
-        _ctx: &mut Box<HashMap<String, i32>>,
+        ctx: &mut Box<Ctx<S>>,
     ) -> MatcherResult {
         return match oc {
             None => {
                 self.running = false;
-                self.generate_whitspace_token(value)
+                self.generate_whitspace_token(value, ctx)
             }
             Some(c) => {
-                if c.is_whitespace() {
+                // chars seen since the last one matched are a possible line continuation
+                let pending = value.len() - 1 - self.index;
+                if pending > 0 {
+                    if c == '\n' {
+                        self.index += pending + 1;
+                        self.column = 1;
+                        self.line = self.line.saturating_add(1);
+                        return MatcherResult::Running();
+                    }
+                    if c == '\r' && pending == 1 {
+                        return MatcherResult::Running();
+                    }
+                    self.running = false;
+                    return self.generate_whitspace_token(value, ctx);
+                }
+                if (self.predicate)(c) {
+                    // the line was already counted at the \r of a \r\n
+                    let after_cr = self.index > 0 && value[value.len() - 2] == '\r';
                     self.index += 1;
-                    self.column += 1;
-                    if c == '\r' {
+                    self.column = self.column.saturating_add(1);
+                    if c == '\r' || (c == '\n' && !after_cr) {
                         self.column = 1;
+                        self.line = self.line.saturating_add(1);
                     } else if c == '\n' {
                         self.column = 1;
-                        self.line += 1;
                     }
                     MatcherResult::Running()
+                } else if self.line_continuation == Some(c) {
+                    MatcherResult::Running()
                 } else {
                     self.running = false;
-                    self.generate_whitspace_token(value)
+                    self.generate_whitspace_token(value, ctx)
                 }
             }
         };
@@ -88,12 +136,85 @@ impl Matcher for WhitespaceMatcher {
     fn precedence(&self) -> u8 {
         self.precedence
     }
+    fn describe(&self) -> String {
+        format!(
+            "WhitespaceMatcher {{ precedence: {}, line_continuation: {:?}, mixed_indent: {} }}",
+            self.precedence, self.line_continuation, self.mixed_indent
+        )
+    }
+}
+
+impl Default for WhitespaceMatcher {
+    /// A WhitespaceMatcher with a precedence of 0 using `char::is_whitespace`, ready to match.
+    fn default() -> Self {
+        WhitespaceMatcher {
+            index: 0,
+            column: 0,
+            line: 0,
+            precedence: 0,
+            running: true,
+            predicate: char::is_whitespace,
+            line_continuation: None,
+            mixed_indent: false,
+        }
+    }
 }
 
 impl WhitespaceMatcher {
+    /// Build a whitespace matcher that uses `predicate` to decide what counts as whitespace, for
+    /// example to treat the `,` field separators of a format as trivia.
+    ///
+    /// # Arguments
+    ///
+    /// * `predicate` - returns `true` for the chars that should be matched
+    /// * `precedence` - the precedence for this matcher
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lexx::{Lexx, Lexxer};
+    /// use lexx::token::{TOKEN_TYPE_WHITESPACE, TOKEN_TYPE_WORD};
+    /// use lexx::input::InputString;
+    /// use lexx::matcher_word::WordMatcher;
+    /// use lexx::matcher_whitespace::WhitespaceMatcher;
+    ///
+    /// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+    ///     Box::new(InputString::new(String::from("a, b"))),
+    ///     vec![
+    ///         Box::new(WhitespaceMatcher::with_predicate(|c| c.is_whitespace() || c == ',', 0)),
+    ///         Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] }),
+    ///     ]
+    /// ));
+    ///
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "a" && t.token_type == TOKEN_TYPE_WORD));
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == ", " && t.token_type == TOKEN_TYPE_WHITESPACE));
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "b" && t.token_type == TOKEN_TYPE_WORD));
+    /// ```
+    pub fn with_predicate(predicate: fn(char) -> bool, precedence: u8) -> WhitespaceMatcher {
+        WhitespaceMatcher {
+            index: 0,
+            column: 0,
+            line: 0,
+            precedence,
+            running: true,
+            predicate,
+            line_continuation: None,
+            mixed_indent: false,
+        }
+    }
+
     #[inline(always)]
-    fn generate_whitspace_token(&mut self, value: &[char]) -> MatcherResult {
+    fn generate_whitspace_token<S: BuildHasher>(&mut self, value: &[char], ctx: &mut Box<Ctx<S>>) -> MatcherResult {
         if self.index > 0 {
+            if self.mixed_indent {
+                let matched = &value[0..self.index];
+                let indent = match matched.iter().rposition(|c| *c == '\n' || *c == '\r') {
+                    Some(i) => &matched[i + 1..],
+                    None => matched,
+                };
+                let mixed = indent.contains(&'\t') && indent.contains(&' ');
+                ctx.insert(WHITESPACE_MIXED_INDENT.to_string(), mixed as i32);
+            }
             MatcherResult::Matched(Token {
                 value: value[0..self.index].into_iter().collect(),
                 token_type: TOKEN_TYPE_WHITESPACE,
@@ -101,9 +222,168 @@ impl WhitespaceMatcher {
                 line: self.line,
                 column: self.column,
                 precedence: self.precedence,
+                ..Default::default()
             })
         } else {
             MatcherResult::Failed()
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::input::InputString;
+    use crate::matcher_whitespace::WhitespaceMatcher;
+    use crate::matcher_word::WordMatcher;
+    use crate::token::{TOKEN_TYPE_WHITESPACE, TOKEN_TYPE_WORD};
+    use crate::{Lexx, LexxError, Lexxer};
+
+    #[test]
+    fn matcher_whitespace_with_predicate() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("a,,b\n,c"))),
+            vec![
+                Box::new(WhitespaceMatcher::with_predicate(
+                    |c| c.is_whitespace() || c == ',',
+                    0,
+                )),
+                Box::new(WordMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    trailing: &[],
+                }),
+            ],
+        );
+
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "a" && t.token_type == TOKEN_TYPE_WORD));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == ",," && t.token_type == TOKEN_TYPE_WHITESPACE));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "b" && t.token_type == TOKEN_TYPE_WORD && t.column == 4));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "\n," && t.token_type == TOKEN_TYPE_WHITESPACE));
+        // lines and columns still follow the newline
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "c" && t.line == 2 && t.column == 2));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+    }
+
+    #[test]
+    fn matcher_whitespace_default() {
+        let matcher = WhitespaceMatcher::default();
+        assert_eq!(matcher.index, 0);
+        assert_eq!(matcher.column, 0);
+        assert_eq!(matcher.line, 0);
+        assert_eq!(matcher.precedence, 0);
+        assert!(matcher.running);
+        assert!((matcher.predicate)('\n') && (matcher.predicate)('\u{3000}'));
+        assert!(!(matcher.predicate)('a'));
+        assert_eq!(matcher.line_continuation, None);
+
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from(" \t\n"))),
+            vec![Box::new(WhitespaceMatcher::default())],
+        );
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == " \t\n"));
+    }
+
+    #[test]
+    fn matcher_whitespace_mixed_indent() {
+        use crate::matcher_whitespace::WHITESPACE_MIXED_INDENT;
+
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from(" \tx\n\t\ty\n\t \n  z"))),
+            vec![
+                Box::new(WhitespaceMatcher {
+                    mixed_indent: true,
+                    ..WhitespaceMatcher::default()
+                }),
+                Box::new(WordMatcher::default()),
+            ],
+        );
+
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == " \t" && t.column == 1));
+        assert_eq!(lexx.ctx.get(WHITESPACE_MIXED_INDENT), Some(&1));
+        lexx.next_token().unwrap();
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "\n\t\t"));
+        assert_eq!(lexx.ctx.get(WHITESPACE_MIXED_INDENT), Some(&0));
+        lexx.next_token().unwrap();
+        // only the indentation of the last line counts
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "\n\t \n  "));
+        assert_eq!(lexx.ctx.get(WHITESPACE_MIXED_INDENT), Some(&0));
+
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from(" \tx"))),
+            vec![
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(WordMatcher::default()),
+            ],
+        );
+        lexx.next_token().unwrap();
+        assert_eq!(lexx.ctx.get(WHITESPACE_MIXED_INDENT), None);
+    }
+
+    #[test]
+    fn matcher_whitespace_lone_carriage_return() {
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("a\rb\r\nc\r\rd"))),
+            vec![
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(WordMatcher::default()),
+            ],
+        );
+
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "a" && t.line == 1 && t.column == 1));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "\r" && t.token_type == TOKEN_TYPE_WHITESPACE));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "b" && t.line == 2 && t.column == 1));
+        lexx.next_token().unwrap();
+        // \r\n is a single line break
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "c" && t.line == 3 && t.column == 1));
+        lexx.next_token().unwrap();
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "d" && t.line == 5 && t.column == 1));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+    }
+
+    #[test]
+    fn matcher_whitespace_line_saturates() {
+        use crate::matcher::{Ctx, Matcher, MatcherResult};
+
+        let mut matcher = WhitespaceMatcher {
+            line: usize::MAX,
+            column: usize::MAX,
+            ..WhitespaceMatcher::default()
+        };
+        let mut ctx: Box<Ctx> = Box::default();
+        assert!(matches!(matcher.find_match(Some(' '), &[' '], &mut ctx), MatcherResult::Running()));
+        assert_eq!(matcher.column, usize::MAX);
+        assert!(matches!(matcher.find_match(Some('\n'), &[' ', '\n'], &mut ctx), MatcherResult::Running()));
+        assert_eq!(matcher.line, usize::MAX);
+        assert_eq!(matcher.column, 1);
+    }
+
+    #[test]
+    fn matcher_whitespace_line_continuation() {
+        use crate::matcher_newline::NewlineMatcher;
+        use crate::token::TOKEN_TYPE_NEWLINE;
+
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("a \\\n b\nc \\\r\nd\\e"))),
+            vec![
+                Box::new(WhitespaceMatcher {
+                    line_continuation: Some('\\'),
+                    ..WhitespaceMatcher::with_predicate(|c| c == ' ', 0)
+                }),
+                Box::new(NewlineMatcher::build_newline_matcher(1)),
+                Box::new(WordMatcher::default()),
+            ],
+        );
+
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "a"));
+        // the continued line break is whitespace, not a newline
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == " \\\n " && t.token_type == TOKEN_TYPE_WHITESPACE && t.line == 1 && t.column == 2));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "b" && t.line == 2 && t.column == 2));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.token_type == TOKEN_TYPE_NEWLINE));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "c" && t.line == 3));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == " \\\r\n" && t.token_type == TOKEN_TYPE_WHITESPACE));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "d" && t.line == 4 && t.column == 1));
+        // without a line break after it the char isn't whitespace
+        assert!(matches!(lexx.next_token(), Err(LexxError::TokenNotFound(_))));
+    }
+}
@@ -1,7 +1,7 @@
 
-use crate::matcher::{Matcher, MatcherResult};
+use std::hash::BuildHasher;
+use crate::matcher::{Ctx, Matcher, MatcherResult};
 use crate::token::{Token, TOKEN_TYPE_WORD};
-use std::collections::HashMap;
 
 /// The SymbolMatcher matches any series of characters that do NOT match `is_whitespace()` or
 /// `c.is_alphanumeric()`. That is, any character that is not a number, letter or whitespace
@@ -21,7 +21,7 @@ use std::collections::HashMap;
 /// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
 ///     Box::new(lexx_input),
 ///     vec![
-///         Box::new(SymbolMatcher { index: 0, precedence: 0, running: true }),
+///         Box::new(SymbolMatcher { index: 0, precedence: 0, running: true, max_len: 0 }),
 ///         // Note the precedence of 1 will cause the ExactMatcher to be be returned
 ///         // when the SymbolMatcher would have matched the same, or a longer thing.
 ///         Box::new(ExactMatcher::build_exact_matcher(vec!["^", "$gxv ", "gxv ", "llj)9", "d$rrr"], TOKEN_TYPE_EXACT, 1)),
@@ -50,10 +50,21 @@ pub struct WordMatcher {
     pub precedence: u8,
     /// If the matcher is currently running.
     pub running: bool,
+    /// [char]s that may end a word, such as the `?` and `!` of Ruby's `empty?` and `save!`. Only
+    /// one is matched, directly after the letters of the word. Usually empty.
+    pub trailing: &'static [char],
 }
 
-impl Matcher for WordMatcher {
-    fn reset(&mut self, _ctx: &mut Box<HashMap<String, i32>>) {
+impl<S: BuildHasher> Matcher<S> for WordMatcher {
+    fn reset(&mut self, _ctx: &mut Box<Ctx<S>>) {
+        Matcher::<S>::reset_light(self);
+    }
+
+    fn is_stateful(&self) -> bool {
+        false
+    }
+
+    fn reset_light(&mut self) {
         self.index = 0;
         self.running = true;
     }
@@ -62,7 +73,7 @@ impl Matcher for WordMatcher {
         &mut self,
         oc: Option<char>,
         value: &[char],
-        _ctx: &mut Box<HashMap<String, i32>>,
+        _ctx: &mut Box<Ctx<S>>,
     ) -> MatcherResult {
         return match oc {
             None => {
@@ -73,6 +84,10 @@ impl Matcher for WordMatcher {
                 if c.is_alphabetic() {
                     self.index += 1;
                     MatcherResult::Running()
+                } else if self.index > 0 && self.trailing.contains(&c) {
+                    self.index += 1;
+                    self.running = false;
+                    self.generate_word_token(value)
                 } else {
                     self.running = false;
                     self.generate_word_token(value)
@@ -86,9 +101,63 @@ impl Matcher for WordMatcher {
     fn precedence(&self) -> u8 {
         self.precedence
     }
+    fn describe(&self) -> String {
+        format!(
+            "WordMatcher {{ precedence: {}, trailing: {:?} }}",
+            self.precedence, self.trailing
+        )
+    }
+}
+
+impl Default for WordMatcher {
+    /// A WordMatcher with a precedence of 0, ready to match.
+    fn default() -> Self {
+        WordMatcher {
+            index: 0,
+            precedence: 0,
+            running: true,
+            trailing: &[],
+        }
+    }
 }
 
 impl WordMatcher {
+    /// Build a word matcher that may end with one of the `trailing` [char]s.
+    ///
+    /// # Arguments
+    ///
+    /// * `trailing` - the [char]s that may end a word, such as `&['?', '!']`
+    /// * `precedence` - the precedence for this matcher
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lexx::{Lexx, Lexxer};
+    /// use lexx::input::InputString;
+    /// use lexx::matcher_symbol::SymbolMatcher;
+    /// use lexx::matcher_word::WordMatcher;
+    ///
+    /// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+    ///     Box::new(InputString::new(String::from("empty?.save!"))),
+    ///     vec![
+    ///         Box::new(WordMatcher::with_trailing(&['?', '!'], 0)),
+    ///         Box::new(SymbolMatcher { index: 0, precedence: 0, running: true, max_len: 1 }),
+    ///     ]
+    /// ));
+    ///
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "empty?"));
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "."));
+    /// assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "save!"));
+    /// ```
+    pub fn with_trailing(trailing: &'static [char], precedence: u8) -> WordMatcher {
+        WordMatcher {
+            index: 0,
+            precedence,
+            running: true,
+            trailing,
+        }
+    }
+
     #[inline(always)]
     fn generate_word_token(&mut self, value: &[char]) -> MatcherResult {
         if self.index > 0 {
@@ -99,6 +168,7 @@ impl WordMatcher {
                 line: 0,
                 column: self.index,
                 precedence: self.precedence,
+                ..Default::default()
             })
         } else {
             MatcherResult::Failed()
@@ -122,6 +192,7 @@ mod tests {
                 index: 0,
                 precedence: 0,
                 running: true,
+                trailing: &[],
             })],
         ));
 
@@ -130,7 +201,7 @@ mod tests {
                 LexxError::TokenNotFound(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
-                LexxError::Error(_) => {
+                LexxError::Error(_) | LexxError::BufferOverflow(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
             },
@@ -152,6 +223,7 @@ mod tests {
                 index: 0,
                 precedence: 0,
                 running: true,
+                trailing: &[],
             })],
         );
 
@@ -160,7 +232,7 @@ mod tests {
                 LexxError::TokenNotFound(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
-                LexxError::Error(_) => {
+                LexxError::Error(_) | LexxError::BufferOverflow(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
             },
@@ -182,6 +254,7 @@ mod tests {
                 index: 0,
                 precedence: 0,
                 running: true,
+                trailing: &[],
             })],
         );
 
@@ -190,7 +263,7 @@ mod tests {
                 LexxError::TokenNotFound(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
-                LexxError::Error(_) => {
+                LexxError::Error(_) | LexxError::BufferOverflow(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
             },
@@ -214,6 +287,7 @@ mod tests {
                     index: 0,
                     precedence: 0,
                     running: true,
+                    trailing: &[],
                 }),
                 Box::new(WhitespaceMatcher {
                     index: 0,
@@ -221,6 +295,9 @@ mod tests {
                     line: 0,
                     precedence: 0,
                     running: true,
+                    predicate: char::is_whitespace,
+                    line_continuation: None,
+                    mixed_indent: false,
                 }),
             ],
         );
@@ -239,7 +316,7 @@ mod tests {
                 LexxError::TokenNotFound(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
-                LexxError::Error(_) => {
+                LexxError::Error(_) | LexxError::BufferOverflow(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
             },
@@ -266,6 +343,7 @@ mod tests {
                     index: 0,
                     precedence: 0,
                     running: true,
+                    trailing: &[],
                 }),
                 Box::new(WhitespaceMatcher {
                     index: 0,
@@ -273,6 +351,9 @@ mod tests {
                     line: 0,
                     precedence: 0,
                     running: true,
+                    predicate: char::is_whitespace,
+                    line_continuation: None,
+                    mixed_indent: false,
                 }),
             ],
         );
@@ -298,13 +379,13 @@ mod tests {
                 LexxError::TokenNotFound(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
-                LexxError::Error(_) => {
+                LexxError::Error(_) | LexxError::BufferOverflow(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
             },
             Ok(Some(t)) => {
                 assert_eq!(t.value, "dog");
-                assert_eq!(t.line, 3);
+                assert_eq!(t.line, 4);
                 assert_eq!(t.column, 15);
             }
             Ok(None) => {
@@ -313,6 +394,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn matcher_word_trailing() {
+        use crate::matcher_symbol::SymbolMatcher;
+        use crate::token::TOKEN_TYPE_SYMBOL;
+        let mut lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from("empty? save! foo?? ?x"))),
+            vec![
+                Box::new(WordMatcher::with_trailing(&['?', '!'], 0)),
+                Box::new(WhitespaceMatcher::default()),
+                Box::new(SymbolMatcher::default()),
+            ],
+        );
+
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "empty?" && t.token_type == TOKEN_TYPE_WORD && t.len == 6));
+        lexx.next_token().unwrap();
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "save!" && t.token_type == TOKEN_TYPE_WORD));
+        lexx.next_token().unwrap();
+        // only one trailing char is part of the word
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "foo?" && t.token_type == TOKEN_TYPE_WORD));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "?" && t.token_type == TOKEN_TYPE_SYMBOL && t.column == 18));
+        lexx.next_token().unwrap();
+        // it has to follow the letters of a word
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "?" && t.token_type == TOKEN_TYPE_SYMBOL));
+        assert!(matches!(lexx.next_token(), Ok(Some(t)) if t.value == "x" && t.token_type == TOKEN_TYPE_WORD));
+        assert!(matches!(lexx.next_token(), Ok(None)));
+    }
+
     #[test]
     fn matcher_word_does_not_match_number() {
         let mut lexx = Lexx::<512>::new(
@@ -321,6 +429,7 @@ mod tests {
                 index: 0,
                 precedence: 0,
                 running: true,
+                trailing: &[],
             })],
         );
 
@@ -329,7 +438,7 @@ mod tests {
                 LexxError::TokenNotFound(e) => {
                     assert_eq!(e, "Could not resolve token at 1, 1: 'Some('5')'.");
                 }
-                LexxError::Error(_) => {
+                LexxError::Error(_) | LexxError::BufferOverflow(_) => {
                     assert!(false, "Should not get an error");
                 }
             },
@@ -347,6 +456,7 @@ mod tests {
                 index: 0,
                 precedence: 0,
                 running: true,
+                trailing: &[],
             })],
         );
 
@@ -355,7 +465,7 @@ mod tests {
                 LexxError::TokenNotFound(e) => {
                     assert_eq!(e, "Could not resolve token at 1, 1: 'Some(' ')'.");
                 }
-                LexxError::Error(_) => {
+                LexxError::Error(_) | LexxError::BufferOverflow(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
             },
@@ -373,6 +483,7 @@ mod tests {
                 index: 0,
                 precedence: 0,
                 running: true,
+                trailing: &[],
             })],
         );
 
@@ -381,7 +492,7 @@ mod tests {
                 LexxError::TokenNotFound(e) => {
                     assert_eq!(e, "Could not resolve token at 1, 1: 'Some('%')'.");
                 }
-                LexxError::Error(_) => {
+                LexxError::Error(_) | LexxError::BufferOverflow(_) => {
                     assert!(false, "Should not have failed parsing file");
                 }
             },
@@ -390,4 +501,18 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn matcher_word_default() {
+        let explicit = WordMatcher {
+            index: 0,
+            precedence: 0,
+            running: true,
+            trailing: &[],
+        };
+        assert_eq!(
+            format!("{:?}", WordMatcher::default()),
+            format!("{:?}", explicit)
+        );
+    }
 }
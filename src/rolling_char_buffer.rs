@@ -166,6 +166,27 @@ impl<const CAP: usize> RollingCharBuffer<CAP> {
     pub fn is_full(&self) -> bool {
         self.full
     }
+    /// Returns a copy of the [char]s in the buffer, front first, without removing them
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lexx::rolling_char_buffer::RollingCharBuffer;
+    ///
+    /// let mut buffer = RollingCharBuffer::<3>::new();
+    /// assert_eq!(buffer.extend(&vec!['a', 'b', 'c']), Ok(0)); // buffer is now ['a', 'b', 'c']
+    /// assert!(matches!(buffer.read(), Result::Ok(c) if c == 'a')); // buffer is now ['b', 'c']
+    /// assert_eq!(buffer.push('d'), Ok(())); // buffer is now ['b', 'c', 'd']
+    /// assert_eq!(buffer.to_vec(), vec!['b', 'c', 'd']);
+    /// assert_eq!(buffer.len(), 3);
+    /// ```
+    ///
+    pub fn to_vec(&self) -> Vec<char> {
+        (0..self.len())
+            .map(|i| self.buffer[(self.start + i) % self.cap])
+            .collect()
+    }
+
     /// Empties the buffer
     ///
     /// # Examples
@@ -1,3 +1,7 @@
+use std::collections::HashMap;
+
+use crate::matcher::line_column_delta;
+
 // Token types. These are not an enum so that they can be externally extended
 /// Token type Integer
 pub const TOKEN_TYPE_INTEGER: u16 = 1;
@@ -13,9 +17,35 @@ pub const TOKEN_TYPE_SYMBOL: u16 = 5;
 pub const TOKEN_TYPE_EXACT: u16 = 6;
 /// Token type Keyword
 pub const TOKEN_TYPE_KEYWORD: u16 = 7;
+/// Token type Email
+pub const TOKEN_TYPE_EMAIL: u16 = 8;
+/// Token type Newline
+pub const TOKEN_TYPE_NEWLINE: u16 = 9;
+/// Token type Blank Line
+pub const TOKEN_TYPE_BLANK_LINE: u16 = 10;
+/// Token type Quantity
+pub const TOKEN_TYPE_QUANTITY: u16 = 11;
+/// Token type Digit Group
+pub const TOKEN_TYPE_DIGIT_GROUP: u16 = 12;
+/// Token type Tag
+pub const TOKEN_TYPE_TAG: u16 = 13;
+/// Token type Url
+pub const TOKEN_TYPE_URL: u16 = 14;
+/// Token type Date Time
+pub const TOKEN_TYPE_DATETIME: u16 = 15;
+/// Token type Comment
+pub const TOKEN_TYPE_COMMENT: u16 = 16;
+/// Token type String
+pub const TOKEN_TYPE_STRING: u16 = 17;
+/// Token type Identifier
+pub const TOKEN_TYPE_IDENTIFIER: u16 = 18;
 
 /// The result of a successful match.
-#[derive(Eq, Debug)]
+///
+/// A [Matcher](crate::matcher::Matcher) only needs to fill in the fields up to
+/// [Token::precedence], the rest are set by [Lexx](crate::Lexx) and can be left to
+/// `..Default::default()`.
+#[derive(Eq, Debug, Default)]
 pub struct Token {
     /// The string value that was matched.
     pub value: String,
@@ -24,12 +54,222 @@ pub struct Token {
     pub token_type: u16,
     /// The length of the found [Token] in [char]s (so we don't have to do `.chars().count()`).
     pub len: usize,
-    /// The line in the total input source the [Token] was found on.
+    /// The line in the total input source the [Token] was found on, starting at 1. Inside a
+    /// [Matcher](crate::matcher::Matcher) this is the number of lines the match spans instead, so
+    /// it is usually 0 there, [Lexx](crate::Lexx) never emits a [Token] with a line of 0.
     pub line: usize,
-    /// The column in the total input source the [Token] was found at.
+    /// The column in the total input source the [Token] was found at, starting at 1. Like
+    /// [Token::line] this has a different meaning inside a [Matcher](crate::matcher::Matcher).
     pub column: usize,
-    /// The precedence of the [Matcher](crate::matcher::Matcher) that made this match.
+    /// The precedence of the [Matcher](crate::matcher::Matcher) that made this match. A matcher may
+    /// set this higher or lower than its own precedence for a single match, [Lexx](crate::Lexx)
+    /// uses this value, not the matcher's, when choosing between competing matches.
     pub precedence: u8,
+    /// How many [char]s of whitespace came directly before this [Token], this is the `len` of the
+    /// previous [Token] if it was [TOKEN_TYPE_WHITESPACE], otherwise 0. It is set by
+    /// [Lexx](crate::Lexx), [Matcher](crate::matcher::Matcher)s should leave it at 0.
+    pub prev_gap: usize,
+    /// The label given to the [Matcher](crate::matcher::Matcher) that made this match when it was
+    /// added with [Lexxer::push_matcher](crate::Lexxer::push_matcher), if any. It is set by
+    /// [Lexx](crate::Lexx), [Matcher](crate::matcher::Matcher)s should leave it as [None].
+    pub label: Option<&'static str>,
+    /// The byte offset in the total input source the [Token] starts at. It is set by
+    /// [Lexx](crate::Lexx), [Matcher](crate::matcher::Matcher)s should leave it at 0.
+    pub start_byte: usize,
+    /// The byte offset in the total input source just past the end of the [Token]. It is set by
+    /// [Lexx](crate::Lexx), [Matcher](crate::matcher::Matcher)s should leave it at 0.
+    pub end_byte: usize,
+    /// The values of the trivia [Token]s, such as whitespace, that came directly before this
+    /// [Token] when trivia is attached with
+    /// [Lexxer::set_attach_trivia](crate::Lexxer::set_attach_trivia), otherwise empty. It is set
+    /// by [Lexx](crate::Lexx), [Matcher](crate::matcher::Matcher)s should leave it empty.
+    pub leading_trivia: String,
+    /// The `value` as rewritten by the normalizer given to
+    /// [Lexxer::set_normalize](crate::Lexxer::set_normalize), for example lowercased, or [None]
+    /// if there is no normalizer. It is set by [Lexx](crate::Lexx),
+    /// [Matcher](crate::matcher::Matcher)s should leave it as [None].
+    pub normalized: Option<String>,
+    /// The id the [StringInterner](crate::interner::StringInterner) given to
+    /// [Lexxer::set_interner](crate::Lexxer::set_interner) has for the `value`, or [None] if
+    /// there is no interner or this token type isn't interned. It is set by [Lexx](crate::Lexx),
+    /// [Matcher](crate::matcher::Matcher)s should leave it as [None].
+    pub interned: Option<usize>,
+}
+
+impl Token {
+    /// Returns the slice of `src` this [Token] was matched from using [Token::start_byte] and
+    /// [Token::end_byte], or [None] if they are out of bounds for `src` or not on [char]
+    /// boundaries. `src` should be the same source the [Token] was lexed from.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lexx::{Lexx, Lexxer};
+    /// use lexx::input::InputString;
+    /// use lexx::matcher_whitespace::WhitespaceMatcher;
+    /// use lexx::matcher_word::WordMatcher;
+    ///
+    /// let src = "héllo wörld";
+    /// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+    ///     Box::new(InputString::new(String::from(src))),
+    ///     vec![
+    ///         Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] }),
+    ///         Box::new(WhitespaceMatcher { index: 0, column: 0, line: 0, precedence: 0, running: true, predicate: char::is_whitespace, line_continuation: None, mixed_indent: false }),
+    ///     ]
+    /// ));
+    ///
+    /// for token in lexx {
+    ///     assert_eq!(token.slice_from(src), Some(token.value.as_str()));
+    /// }
+    /// ```
+    pub fn slice_from<'a>(&self, src: &'a str) -> Option<&'a str> {
+        src.get(self.start_byte..self.end_byte)
+    }
+
+    /// If this is a [TOKEN_TYPE_INTEGER] or [TOKEN_TYPE_FLOAT] [Token].
+    pub fn is_numeric(&self) -> bool {
+        self.token_type == TOKEN_TYPE_INTEGER || self.token_type == TOKEN_TYPE_FLOAT
+    }
+
+    /// Parses the `value` of a [TOKEN_TYPE_INTEGER] [Token], ignoring any `_` digit separators.
+    /// Hexadecimal, binary and octal values with a `0x`, `0b` or `0o` prefix, in either case, are
+    /// parsed in their radix. Returns [None] for other token types or if the value doesn't fit in
+    /// an [i64].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lexx::{Lexx, Lexxer};
+    /// use lexx::input::InputString;
+    /// use lexx::matcher_float::FloatMatcher;
+    /// use lexx::matcher_integer::IntegerMatcher;
+    /// use lexx::matcher_whitespace::WhitespaceMatcher;
+    ///
+    /// let mut lexx: Box<dyn Lexxer> = Box::new(Lexx::<512>::new(
+    ///     Box::new(InputString::new(String::from("42 2.5"))),
+    ///     vec![
+    ///         Box::new(IntegerMatcher { index: 0, precedence: 0, running: true, radix_prefixes: false }),
+    ///         Box::new(FloatMatcher { index: 0, precedence: 0, dot: false, float: false, running: true, lenient_trailing_dot: false, exponent: false, integer_mantissa: false }),
+    ///         Box::new(WhitespaceMatcher::with_predicate(char::is_whitespace, 0)),
+    ///     ]
+    /// ));
+    ///
+    /// let integer = lexx.next_token().unwrap().unwrap();
+    /// assert_eq!(integer.as_i64(), Some(42));
+    /// let whitespace = lexx.next_token().unwrap().unwrap();
+    /// assert_eq!(whitespace.as_i64(), None);
+    /// let float = lexx.next_token().unwrap().unwrap();
+    /// assert_eq!(float.as_i64(), None);
+    /// assert_eq!(float.as_f64(), Some(2.5));
+    /// ```
+    pub fn as_i64(&self) -> Option<i64> {
+        if self.token_type != TOKEN_TYPE_INTEGER {
+            return None;
+        }
+        let value = self.value.replace('_', "");
+        let radix = match value.get(0..2) {
+            Some("0x" | "0X") => 16,
+            Some("0b" | "0B") => 2,
+            Some("0o" | "0O") => 8,
+            _ => return value.parse().ok(),
+        };
+        i64::from_str_radix(&value[2..], radix).ok()
+    }
+
+    /// Parses the `value` of a [TOKEN_TYPE_FLOAT] or [TOKEN_TYPE_INTEGER] [Token], ignoring any `_`
+    /// digit separators. Returns [None] for other token types or if the value can't be parsed.
+    pub fn as_f64(&self) -> Option<f64> {
+        if !self.is_numeric() {
+            return None;
+        }
+        self.value.replace('_', "").parse().ok()
+    }
+
+    /// Returns the `value` without the whitespace at its start and end, such as the text of a
+    /// comment without the spaces around it.
+    pub fn trimmed_value(&self) -> &str {
+        self.value.trim()
+    }
+
+    /// Returns how many [char]s of whitespace the `value` starts with, so the text from
+    /// [Token::trimmed_value] starts that many columns after the [Token] does.
+    pub fn leading_ws_len(&self) -> usize {
+        self.value.chars().take_while(|c| c.is_whitespace()).count()
+    }
+
+    /// Returns the span covering all of `tokens` as `(start_line, start_column, end_line,
+    /// end_column)`. The start is the position of the first [Token] and the end is the position
+    /// just past the last [char] of the last [Token], worked out from its `value` so a
+    /// [Token] spanning lines ends on the right line. The [Token]s should be in input order.
+    ///
+    /// # Panics
+    ///
+    /// If `tokens` is empty.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lexx::Lexx;
+    /// use lexx::input::InputString;
+    /// use lexx::token::Token;
+    ///
+    /// let lexx = Lexx::<512>::from_spec(
+    ///     "word,whitespace,symbol",
+    ///     Box::new(InputString::new(String::from("a = bc;"))),
+    /// ).unwrap();
+    /// let tokens: Vec<Token> = lexx.collect();
+    ///
+    /// // "a = bc" ends just before the ";" at column 7
+    /// assert_eq!(Token::merge_spans(&tokens[0..5]), (1, 1, 1, 7));
+    /// ```
+    pub fn merge_spans(tokens: &[Token]) -> (usize, usize, usize, usize) {
+        let first = tokens.first().expect("merge_spans needs at least one Token");
+        let last = &tokens[tokens.len() - 1];
+        let chars: Vec<char> = last.value.chars().collect();
+        let (end_line, end_column) = match line_column_delta(&chars) {
+            (0, _) => (last.line, last.column + last.len),
+            (lines, column) => (last.line + lines, column),
+        };
+        (first.line, first.column, end_line, end_column)
+    }
+
+    /// Returns the [GoldenToken] for this [Token], its token type and value without any positions.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lexx::Lexx;
+    /// use lexx::input::InputString;
+    /// use lexx::token::{GoldenToken, Token, TOKEN_TYPE_INTEGER, TOKEN_TYPE_WHITESPACE};
+    ///
+    /// let lexx = Lexx::<512>::from_spec(
+    ///     "whitespace,integer",
+    ///     Box::new(InputString::new(String::from("  42"))),
+    /// ).unwrap();
+    /// let golden: Vec<GoldenToken> = lexx.map(|t| t.normalize_for_golden()).collect();
+    ///
+    /// assert_eq!(golden, vec![
+    ///     GoldenToken { token_type: TOKEN_TYPE_WHITESPACE, value: String::from("  ") },
+    ///     GoldenToken { token_type: TOKEN_TYPE_INTEGER, value: String::from("42") },
+    /// ]);
+    /// ```
+    pub fn normalize_for_golden(&self) -> GoldenToken {
+        GoldenToken {
+            token_type: self.token_type,
+            value: self.value.clone(),
+        }
+    }
+}
+
+/// Just the token type and value of a [Token], leaving out its positions, so [Token]s can be
+/// compared to expected ones that don't break whenever the input around them moves. See
+/// [Token::normalize_for_golden].
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct GoldenToken {
+    /// The [Token::token_type].
+    pub token_type: u16,
+    /// The [Token::value].
+    pub value: String,
 }
 
 impl PartialEq for Token {
@@ -40,6 +280,13 @@ impl PartialEq for Token {
             && self.line == other.line
             && self.column == other.column
             && self.precedence == other.precedence
+            && self.prev_gap == other.prev_gap
+            && self.label == other.label
+            && self.start_byte == other.start_byte
+            && self.end_byte == other.end_byte
+            && self.leading_trivia == other.leading_trivia
+            && self.normalized == other.normalized
+            && self.interned == other.interned
     }
 }
 
@@ -52,6 +299,744 @@ impl Clone for Token {
             line: self.line,
             column: self.column,
             precedence: self.precedence,
+            prev_gap: self.prev_gap,
+            label: self.label,
+            start_byte: self.start_byte,
+            end_byte: self.end_byte,
+            leading_trivia: self.leading_trivia.clone(),
+            normalized: self.normalized.clone(),
+            interned: self.interned,
+        }
+    }
+}
+
+/// Rewrites the [Token::token_type] of each of the `tokens` in place using `map`, for example to
+/// merge two custom types after lexing. Types that aren't in `map` are left unchanged.
+///
+/// # Example
+///
+/// ```rust
+/// use std::collections::HashMap;
+/// use lexx::Lexx;
+/// use lexx::input::InputString;
+/// use lexx::matcher_whitespace::WhitespaceMatcher;
+/// use lexx::matcher_word::WordMatcher;
+/// use lexx::token::{remap_types, Token, TOKEN_TYPE_WHITESPACE, TOKEN_TYPE_WORD};
+///
+/// let lexx = Lexx::<512>::new(
+///     Box::new(InputString::new(String::from("The fox"))),
+///     vec![
+///         Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] }),
+///         Box::new(WhitespaceMatcher::with_predicate(char::is_whitespace, 0)),
+///     ]
+/// );
+/// let mut tokens: Vec<Token> = lexx.collect();
+///
+/// remap_types(&mut tokens, &HashMap::from([(TOKEN_TYPE_WORD, 100)]));
+/// assert_eq!(tokens[0].token_type, 100);
+/// assert_eq!(tokens[1].token_type, TOKEN_TYPE_WHITESPACE);
+/// assert_eq!(tokens[2].token_type, 100);
+/// ```
+pub fn remap_types(tokens: &mut [Token], map: &HashMap<u16, u16>) {
+    for token in tokens.iter_mut() {
+        if let Some(t) = map.get(&token.token_type) {
+            token.token_type = *t;
+        }
+    }
+}
+
+/// Splits the longest of the `suffixes` that `token` ends with off into a second [Token], for
+/// example `.txt` from `foo.txt`. The first [Token] keeps the rest of the value, the second holds
+/// the suffix with its `len`, `line`, `column`, `start_byte` and `end_byte` set to where it is in
+/// the input. Both keep the `token_type` of `token`, use [remap_types] or set it directly to
+/// tell them apart.
+///
+/// `token` is returned unchanged with [None] if it doesn't end with any of the `suffixes`, or if
+/// the suffix is the whole value. The `normalized` and `interned` values are dropped from both as
+/// they no longer match either.
+///
+/// # Example
+///
+/// ```rust
+/// use lexx::Lexx;
+/// use lexx::input::InputString;
+/// use lexx::matcher_whitespace::WhitespaceMatcher;
+/// use lexx::token::{split_suffix, Token};
+///
+/// // treat anything that isn't whitespace as one token
+/// let mut lexx = Lexx::<512>::new(
+///     Box::new(InputString::new(String::from("see foo.txt"))),
+///     vec![
+///         Box::new(WhitespaceMatcher::with_predicate(|c| !c.is_whitespace(), 0)),
+///         Box::new(WhitespaceMatcher::with_predicate(char::is_whitespace, 0)),
+///     ],
+/// );
+/// let token: Token = lexx.nth(2).unwrap();
+///
+/// let (name, suffix) = split_suffix(token, &[".txt", ".md"]);
+/// assert_eq!(name.value, "foo");
+/// let suffix = suffix.unwrap();
+/// assert_eq!(suffix.value, ".txt");
+/// assert_eq!(suffix.column, 8);
+/// ```
+pub fn split_suffix(token: Token, suffixes: &[&str]) -> (Token, Option<Token>) {
+    let Some(suffix) = suffixes
+        .iter()
+        .filter(|s| !s.is_empty() && s.len() < token.value.len() && token.value.ends_with(**s))
+        .max_by_key(|s| s.len())
+    else {
+        return (token, None);
+    };
+
+    let split = token.value.len() - suffix.len();
+    let head = &token.value[..split];
+    let head_chars: Vec<char> = head.chars().collect();
+    let head_len = head_chars.len();
+    let (line, column) = match line_column_delta(&head_chars) {
+        (0, _) => (token.line, token.column + head_len),
+        (lines, column) => (token.line + lines, column),
+    };
+
+    let tail = Token {
+        value: suffix.to_string(),
+        token_type: token.token_type,
+        len: token.len - head_len,
+        line,
+        column,
+        precedence: token.precedence,
+        label: token.label,
+        start_byte: token.start_byte + split,
+        end_byte: token.end_byte,
+        ..Default::default()
+    };
+    let head = Token {
+        value: head.to_string(),
+        len: head_len,
+        end_byte: token.start_byte + split,
+        normalized: None,
+        interned: None,
+        ..token
+    };
+    (head, Some(tail))
+}
+
+/// Renders the `value`s of `tokens` one after another, each wrapped in an ANSI terminal color
+/// picked by its token type, for quick visual debugging. Whitespace is left uncolored, the
+/// built in types get fixed colors and custom types get one of the six basic colors based on
+/// their number.
+///
+/// Only available with the `ansi` feature.
+///
+/// # Example
+///
+/// ```rust
+/// use lexx::Lexx;
+/// use lexx::input::InputString;
+/// use lexx::token::{format_stream_colored, Token};
+///
+/// let lexx = Lexx::<512>::from_spec(
+///     "word,whitespace,integer",
+///     Box::new(InputString::new(String::from("abc 123"))),
+/// ).unwrap();
+/// let tokens: Vec<Token> = lexx.collect();
+///
+/// assert_eq!(format_stream_colored(&tokens), "abc \x1b[33m123\x1b[0m");
+/// ```
+#[cfg(feature = "ansi")]
+pub fn format_stream_colored(tokens: &[Token]) -> String {
+    let mut out = String::new();
+    for t in tokens {
+        let color = match t.token_type {
+            TOKEN_TYPE_WHITESPACE | TOKEN_TYPE_NEWLINE | TOKEN_TYPE_BLANK_LINE | TOKEN_TYPE_WORD => {
+                None
+            }
+            TOKEN_TYPE_INTEGER | TOKEN_TYPE_FLOAT => Some(33),
+            TOKEN_TYPE_SYMBOL => Some(36),
+            TOKEN_TYPE_EXACT | TOKEN_TYPE_KEYWORD => Some(35),
+            TOKEN_TYPE_EMAIL => Some(34),
+            other => Some(31 + other % 6),
+        };
+        match color {
+            Some(c) => out.push_str(&format!("\x1b[{}m{}\x1b[0m", c, t.value)),
+            None => out.push_str(&t.value),
+        }
+    }
+    out
+}
+
+/// Formats `tokens` as the JSON used by golden files, an array with one
+/// `{"type": 4, "value": "The"}` object per [GoldenToken] on each line.
+///
+/// Only available with the `golden` feature.
+#[cfg(feature = "golden")]
+pub fn format_golden(tokens: &[Token]) -> String {
+    let mut out = String::from("[\n");
+    for (i, t) in tokens.iter().enumerate() {
+        out.push_str(&format!("  {{\"type\": {}, \"value\": \"", t.token_type));
+        for c in t.value.chars() {
+            match c {
+                '"' => out.push_str("\\\""),
+                '\\' => out.push_str("\\\\"),
+                '\n' => out.push_str("\\n"),
+                '\r' => out.push_str("\\r"),
+                '\t' => out.push_str("\\t"),
+                c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+                c => out.push(c),
+            }
         }
+        out.push_str(if i + 1 < tokens.len() { "\"},\n" } else { "\"}\n" });
+    }
+    out.push_str("]\n");
+    out
+}
+
+/// Asserts that `tokens` match the golden file at `path`, comparing only the token types and
+/// values as written by [format_golden]. Panics, naming the first line that differs, if they
+/// don't match or the file can't be read.
+///
+/// Set the `LEXX_UPDATE_GOLDEN` environment variable to write the golden file from `tokens`
+/// instead, to create it or to accept a change.
+///
+/// Only available with the `golden` feature.
+///
+/// # Example
+///
+/// ```rust
+/// use lexx::Lexx;
+/// use lexx::input::InputString;
+/// use lexx::token::{assert_golden, format_golden, Token};
+///
+/// let lexx = Lexx::<512>::from_spec(
+///     "word,whitespace",
+///     Box::new(InputString::new(String::from("The fox"))),
+/// ).unwrap();
+/// let tokens: Vec<Token> = lexx.collect();
+///
+/// let path = std::env::temp_dir().join("lexx_assert_golden_doc.json");
+/// std::fs::write(&path, format_golden(&tokens)).unwrap();
+/// assert_golden(&tokens, &path);
+/// ```
+#[cfg(feature = "golden")]
+pub fn assert_golden(tokens: &[Token], path: impl AsRef<std::path::Path>) {
+    let path = path.as_ref();
+    let actual = format_golden(tokens);
+    if std::env::var_os("LEXX_UPDATE_GOLDEN").is_some() {
+        if let Err(e) = std::fs::write(path, &actual) {
+            panic!("could not write golden file {}: {}", path.display(), e);
+        }
+        return;
+    }
+    let expected = match std::fs::read_to_string(path) {
+        Ok(expected) => expected,
+        Err(e) => panic!(
+            "could not read golden file {}: {}, set LEXX_UPDATE_GOLDEN to create it",
+            path.display(),
+            e
+        ),
+    };
+    if expected == actual {
+        return;
+    }
+    let mut expected_lines = expected.lines();
+    let mut actual_lines = actual.lines();
+    for line in 1.. {
+        let (e, a) = (expected_lines.next(), actual_lines.next());
+        if e != a {
+            panic!(
+                "tokens don't match golden file {} at line {}, expected {:?} but got {:?}",
+                path.display(),
+                line,
+                e.unwrap_or("the end"),
+                a.unwrap_or("the end")
+            );
+        }
+    }
+}
+
+/// Encodes `tokens` into a compact binary blob that [decode_tokens] can turn back into the same
+/// [Token]s, for caching lexer output. The format is a little endian `u32` count followed by each
+/// [Token] with its `value` as a `u32` byte length and the UTF-8 bytes, then its numeric fields
+/// and its `leading_trivia` encoded the same way as the `value`. Last is a `u8` that is 1 if
+/// there is a `normalized` value, which then follows encoded the same way, or 0 if there isn't.
+///
+/// [Token::label] is not encoded since it can't be restored as a `&'static str`, decoded [Token]s
+/// always have a `label` of [None]. Nor is [Token::interned], the id means nothing without the
+/// [StringInterner](crate::interner::StringInterner) it came from, decoded [Token]s always have
+/// an `interned` of [None].
+///
+/// Only available with the `token-dump` feature.
+///
+/// # Example
+///
+/// ```rust
+/// use lexx::Lexx;
+/// use lexx::input::InputString;
+/// use lexx::matcher_whitespace::WhitespaceMatcher;
+/// use lexx::matcher_word::WordMatcher;
+/// use lexx::token::{decode_tokens, encode_tokens, Token};
+///
+/// let lexx = Lexx::<512>::new(
+///     Box::new(InputString::new(String::from("The quick fox"))),
+///     vec![
+///         Box::new(WordMatcher { index: 0, precedence: 0, running: true, trailing: &[] }),
+///         Box::new(WhitespaceMatcher { index: 0, column: 0, line: 0, precedence: 0, running: true, predicate: char::is_whitespace, line_continuation: None, mixed_indent: false }),
+///     ]
+/// );
+/// let tokens: Vec<Token> = lexx.collect();
+///
+/// let blob = encode_tokens(&tokens);
+/// assert_eq!(decode_tokens(&blob), Ok(tokens));
+/// ```
+#[cfg(feature = "token-dump")]
+pub fn encode_tokens(tokens: &[Token]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(4 + tokens.len() * 64);
+    out.extend_from_slice(&(tokens.len() as u32).to_le_bytes());
+    for t in tokens {
+        out.extend_from_slice(&(t.value.len() as u32).to_le_bytes());
+        out.extend_from_slice(t.value.as_bytes());
+        out.extend_from_slice(&t.token_type.to_le_bytes());
+        out.push(t.precedence);
+        for n in [t.len, t.line, t.column, t.prev_gap, t.start_byte, t.end_byte] {
+            out.extend_from_slice(&(n as u64).to_le_bytes());
+        }
+        out.extend_from_slice(&(t.leading_trivia.len() as u32).to_le_bytes());
+        out.extend_from_slice(t.leading_trivia.as_bytes());
+        match &t.normalized {
+            Some(n) => {
+                out.push(1);
+                out.extend_from_slice(&(n.len() as u32).to_le_bytes());
+                out.extend_from_slice(n.as_bytes());
+            }
+            None => out.push(0),
+        }
+    }
+    out
+}
+
+/// Decodes a blob made by [encode_tokens] back into [Token]s. Returns a
+/// [LexxError::Error](crate::LexxError::Error) if the blob is truncated, has trailing bytes or
+/// a string that isn't valid UTF-8.
+///
+/// Only available with the `token-dump` feature.
+#[cfg(feature = "token-dump")]
+pub fn decode_tokens(bytes: &[u8]) -> Result<Vec<Token>, crate::LexxError> {
+    fn take<'a>(bytes: &'a [u8], pos: &mut usize, n: usize) -> Result<&'a [u8], crate::LexxError> {
+        let b = bytes
+            .get(*pos..*pos + n)
+            .ok_or_else(|| crate::LexxError::Error(format!("token dump truncated at byte {}", pos)))?;
+        *pos += n;
+        Ok(b)
+    }
+    fn take_u64(bytes: &[u8], pos: &mut usize) -> Result<usize, crate::LexxError> {
+        Ok(u64::from_le_bytes(take(bytes, pos, 8)?.try_into().unwrap()) as usize)
+    }
+    fn take_string(bytes: &[u8], pos: &mut usize) -> Result<String, crate::LexxError> {
+        let len = u32::from_le_bytes(take(bytes, pos, 4)?.try_into().unwrap()) as usize;
+        String::from_utf8(take(bytes, pos, len)?.to_vec()).map_err(|e| {
+            crate::LexxError::Error(format!("token dump has an invalid string: {}", e))
+        })
+    }
+
+    let mut pos = 0;
+    let count = u32::from_le_bytes(take(bytes, &mut pos, 4)?.try_into().unwrap()) as usize;
+    let mut tokens = Vec::with_capacity(count.min(bytes.len()));
+    for _ in 0..count {
+        let value = take_string(bytes, &mut pos)?;
+        let token_type = u16::from_le_bytes(take(bytes, &mut pos, 2)?.try_into().unwrap());
+        let precedence = take(bytes, &mut pos, 1)?[0];
+        tokens.push(Token {
+            value,
+            token_type,
+            len: take_u64(bytes, &mut pos)?,
+            line: take_u64(bytes, &mut pos)?,
+            column: take_u64(bytes, &mut pos)?,
+            precedence,
+            prev_gap: take_u64(bytes, &mut pos)?,
+            label: None,
+            start_byte: take_u64(bytes, &mut pos)?,
+            end_byte: take_u64(bytes, &mut pos)?,
+            leading_trivia: take_string(bytes, &mut pos)?,
+            normalized: match take(bytes, &mut pos, 1)?[0] {
+                0 => None,
+                _ => Some(take_string(bytes, &mut pos)?),
+            },
+            interned: None,
+        });
+    }
+    if pos != bytes.len() {
+        return Err(crate::LexxError::Error(format!(
+            "token dump has {} trailing bytes",
+            bytes.len() - pos
+        )));
+    }
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::InputString;
+    use crate::matcher_symbol::SymbolMatcher;
+    use crate::matcher_whitespace::WhitespaceMatcher;
+    use crate::matcher_word::WordMatcher;
+    use crate::token::Token;
+    use crate::Lexx;
+
+    /// A [Token] at the start of the input with `value` as all of its text.
+    fn token(value: &str, token_type: u16) -> Token {
+        Token {
+            value: value.to_string(),
+            token_type,
+            len: value.chars().count(),
+            line: 1,
+            column: 1,
+            precedence: 0,
+            end_byte: value.len(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn token_slice_from() {
+        let src = "The ünïcode\nfox.";
+        let lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from(src))),
+            vec![
+                Box::new(WordMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    trailing: &[],
+                }),
+                Box::new(WhitespaceMatcher {
+                    index: 0,
+                    column: 0,
+                    line: 0,
+                    precedence: 0,
+                    running: true,
+                    predicate: char::is_whitespace,
+                    line_continuation: None,
+                    mixed_indent: false,
+                }),
+                Box::new(SymbolMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    max_len: 0,
+                }),
+            ],
+        );
+
+        let tokens: Vec<Token> = lexx.collect();
+        assert_eq!(tokens.len(), 6);
+        for token in tokens.iter() {
+            assert_eq!(token.slice_from(src), Some(token.value.as_str()));
+        }
+        assert_eq!(tokens[2].start_byte, 4);
+        assert_eq!(tokens[2].end_byte, 13);
+        assert_eq!(tokens[5].slice_from(src), Some("."));
+
+        // out of bounds for a shorter source
+        assert_eq!(tokens[5].slice_from("The"), None);
+        // not on a char boundary
+        let mut t = tokens[2].clone();
+        t.start_byte = 5;
+        assert_eq!(t.slice_from(src), None);
+    }
+
+    #[test]
+    fn token_as_numbers() {
+        use crate::token::{TOKEN_TYPE_FLOAT, TOKEN_TYPE_INTEGER, TOKEN_TYPE_WORD};
+
+        assert_eq!(token("1234", TOKEN_TYPE_INTEGER).as_i64(), Some(1234));
+        assert_eq!(token("1_000_000", TOKEN_TYPE_INTEGER).as_i64(), Some(1_000_000));
+        assert_eq!(token("99999999999999999999", TOKEN_TYPE_INTEGER).as_i64(), None);
+        assert_eq!(token("0xFF", TOKEN_TYPE_INTEGER).as_i64(), Some(255));
+        assert_eq!(token("0XDE_AD", TOKEN_TYPE_INTEGER).as_i64(), Some(0xDEAD));
+        assert_eq!(token("0b101", TOKEN_TYPE_INTEGER).as_i64(), Some(5));
+        assert_eq!(token("0o17", TOKEN_TYPE_INTEGER).as_i64(), Some(15));
+        assert_eq!(token("0", TOKEN_TYPE_INTEGER).as_i64(), Some(0));
+        assert_eq!(token("0xFFFFFFFFFFFFFFFFF", TOKEN_TYPE_INTEGER).as_i64(), None);
+        assert_eq!(token("1234", TOKEN_TYPE_WORD).as_i64(), None);
+        assert_eq!(token("1.5", TOKEN_TYPE_FLOAT).as_i64(), None);
+
+        assert_eq!(token("1.5", TOKEN_TYPE_FLOAT).as_f64(), Some(1.5));
+        assert_eq!(token("1_000.25", TOKEN_TYPE_FLOAT).as_f64(), Some(1000.25));
+        assert_eq!(token("12", TOKEN_TYPE_INTEGER).as_f64(), Some(12.0));
+        assert_eq!(token("1.5", TOKEN_TYPE_WORD).as_f64(), None);
+
+        assert!(token("12", TOKEN_TYPE_INTEGER).is_numeric());
+        assert!(!token("x", TOKEN_TYPE_WORD).is_numeric());
+    }
+
+    #[test]
+    fn token_trimmed_value() {
+        use crate::token::TOKEN_TYPE_COMMENT;
+
+        let make = |value: &str| token(value, TOKEN_TYPE_COMMENT);
+
+        let comment = make("  a comment \t");
+        assert_eq!(comment.trimmed_value(), "a comment");
+        assert_eq!(comment.leading_ws_len(), 2);
+        // counted in chars, not bytes
+        assert_eq!(make("\u{3000}\nnote").leading_ws_len(), 2);
+        assert_eq!(make("note").leading_ws_len(), 0);
+        assert_eq!(make("note").trimmed_value(), "note");
+        assert_eq!(make(" \n ").trimmed_value(), "");
+        assert_eq!(make(" \n ").leading_ws_len(), 3);
+    }
+
+    #[cfg(feature = "ansi")]
+    #[test]
+    fn token_format_stream_colored() {
+        use crate::matcher_integer::IntegerMatcher;
+        use crate::token::format_stream_colored;
+
+        fn strip_escapes(s: &str) -> String {
+            let mut out = String::new();
+            let mut chars = s.chars();
+            while let Some(c) = chars.next() {
+                if c == '\x1b' {
+                    chars.by_ref().find(|c| *c == 'm');
+                } else {
+                    out.push(c);
+                }
+            }
+            out
+        }
+
+        let src = "The ünïcode fox, 12 + 7;\n  done.";
+        let lexx = Lexx::<512>::new(
+            Box::new(InputString::new(String::from(src))),
+            vec![
+                Box::new(WordMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    trailing: &[],
+                }),
+                Box::new(IntegerMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    radix_prefixes: false,
+                }),
+                Box::new(WhitespaceMatcher::with_predicate(char::is_whitespace, 0)),
+                Box::new(SymbolMatcher::with_max_len(0, 0)),
+            ],
+        );
+        let tokens: Vec<Token> = lexx.collect();
+
+        let colored = format_stream_colored(&tokens);
+        assert!(colored.contains("\x1b[33m12\x1b[0m"));
+        assert!(colored.contains("\x1b[36m,\x1b[0m"));
+        assert_eq!(strip_escapes(&colored), src);
+    }
+
+    #[test]
+    fn token_remap_types() {
+        use crate::token::remap_types;
+        use std::collections::HashMap;
+
+        let make = |token_type: u16| token("x", token_type);
+        let mut tokens = vec![make(100), make(5), make(100), make(200)];
+
+        remap_types(&mut tokens, &HashMap::from([(100, 200)]));
+        let types: Vec<u16> = tokens.iter().map(|t| t.token_type).collect();
+        assert_eq!(types, vec![200, 5, 200, 200]);
+
+        // not chained, each type is only mapped once
+        remap_types(&mut tokens, &HashMap::from([(200, 300), (300, 400)]));
+        let types: Vec<u16> = tokens.iter().map(|t| t.token_type).collect();
+        assert_eq!(types, vec![300, 5, 300, 300]);
+    }
+
+    #[test]
+    fn token_split_suffix() {
+        use crate::token::{split_suffix, TOKEN_TYPE_WORD};
+
+        let make = |value: &str, line: usize, column: usize, start_byte: usize| Token {
+            line,
+            column,
+            prev_gap: 1,
+            start_byte,
+            end_byte: start_byte + value.len(),
+            leading_trivia: String::from(" "),
+            ..token(value, TOKEN_TYPE_WORD)
+        };
+
+        let (head, tail) = split_suffix(make("archive.tar", 3, 5, 20), &[".tar"]);
+        assert_eq!(head, make("archive", 3, 5, 20));
+        let tail = tail.unwrap();
+        assert_eq!(tail.value, ".tar");
+        assert_eq!(tail.len, 4);
+        assert_eq!((tail.line, tail.column), (3, 12));
+        assert_eq!((tail.start_byte, tail.end_byte), (27, 31));
+        assert_eq!((tail.prev_gap, tail.leading_trivia.as_str()), (0, ""));
+
+        // the longest suffix wins
+        let (head, tail) = split_suffix(make("archive.tar.gz", 1, 1, 0), &[".gz", ".tar.gz"]);
+        assert_eq!(head.value, "archive");
+        assert_eq!(tail.unwrap().value, ".tar.gz");
+
+        // multi byte chars before the suffix
+        let (head, tail) = split_suffix(make("ünï.tar", 1, 1, 0), &[".tar"]);
+        assert_eq!((head.len, head.end_byte), (3, 5));
+        let tail = tail.unwrap();
+        assert_eq!((tail.column, tail.start_byte, tail.end_byte), (4, 5, 9));
+
+        // line breaks before the suffix, \r\n and a lone \r count as one
+        for value in ["a\nb.tar", "a\r\nb.tar", "a\rb.tar"] {
+            let (_, tail) = split_suffix(make(value, 3, 5, 0), &[".tar"]);
+            let tail = tail.unwrap();
+            assert_eq!((tail.line, tail.column), (4, 2));
+        }
+
+        // no suffix, or the suffix is the whole value
+        let token = make("archive.zip", 1, 1, 0);
+        assert_eq!(split_suffix(token.clone(), &[".tar"]), (token, None));
+        let token = make(".tar", 1, 1, 0);
+        assert_eq!(split_suffix(token.clone(), &[".tar", ""]), (token, None));
+    }
+
+    #[test]
+    fn token_merge_spans() {
+        let lexx = Lexx::<512>::from_spec(
+            "word,whitespace,symbol",
+            Box::new(InputString::new(String::from("x = foo(\n  bar)"))),
+        )
+        .unwrap();
+        let tokens: Vec<Token> = lexx.collect();
+
+        // "foo", "(" and "\n  "
+        assert_eq!(Token::merge_spans(&tokens[4..7]), (1, 5, 2, 3));
+        // "bar" and ")" after the newline
+        assert_eq!(Token::merge_spans(&tokens[7..9]), (2, 3, 2, 7));
+        assert_eq!(Token::merge_spans(&tokens[0..1]), (1, 1, 1, 2));
+        assert_eq!(Token::merge_spans(&tokens), (1, 1, 2, 7));
+
+        // \r\n and a lone \r are line breaks too
+        for text in ["x(\r\n  y)", "x(\r  y)"] {
+            let lexx = Lexx::<512>::from_spec(
+                "word,whitespace,symbol",
+                Box::new(InputString::new(String::from(text))),
+            )
+            .unwrap();
+            let tokens: Vec<Token> = lexx.collect();
+            assert_eq!(Token::merge_spans(&tokens[0..3]), (1, 1, 2, 3));
+        }
+    }
+
+    #[test]
+    fn token_normalize_for_golden() {
+        use crate::token::{GoldenToken, TOKEN_TYPE_SYMBOL, TOKEN_TYPE_WHITESPACE, TOKEN_TYPE_WORD};
+
+        let golden = |token_type: u16, value: &str| GoldenToken {
+            token_type,
+            value: value.to_string(),
+        };
+        let make = |src: &str| -> Vec<GoldenToken> {
+            Lexx::<512>::from_spec(
+                "word,whitespace,symbol",
+                Box::new(InputString::new(String::from(src))),
+            )
+            .unwrap()
+            .map(|t| t.normalize_for_golden())
+            .collect()
+        };
+        let expected = vec![
+            golden(TOKEN_TYPE_WORD, "The"),
+            golden(TOKEN_TYPE_WHITESPACE, " "),
+            golden(TOKEN_TYPE_WORD, "fox"),
+            golden(TOKEN_TYPE_SYMBOL, "."),
+        ];
+
+        assert_eq!(make("The fox."), expected);
+        // moving the input around doesn't matter, but changing a value does
+        let shifted = make("\n\nThe fox.");
+        assert_eq!(shifted[1..], expected[..]);
+        assert_ne!(make("The cat."), expected);
+    }
+
+    #[cfg(feature = "golden")]
+    #[test]
+    fn token_assert_golden() {
+        use crate::token::{assert_golden, format_golden};
+        use std::panic::catch_unwind;
+
+        let lex = |src: &str| -> Vec<Token> {
+            Lexx::<512>::from_spec(
+                "word,whitespace,symbol",
+                Box::new(InputString::new(String::from(src))),
+            )
+            .unwrap()
+            .collect()
+        };
+        let tokens = lex("say \"hi\"\n");
+        assert_eq!(
+            format_golden(&tokens),
+            "[\n  {\"type\": 4, \"value\": \"say\"},\n  {\"type\": 3, \"value\": \" \"},\n  {\"type\": 5, \"value\": \"\\\"\"},\n  {\"type\": 4, \"value\": \"hi\"},\n  {\"type\": 5, \"value\": \"\\\"\"},\n  {\"type\": 3, \"value\": \"\\n\"}\n]\n"
+        );
+        assert_eq!(format_golden(&[]), "[\n]\n");
+
+        let path = std::env::temp_dir().join(format!("lexx_golden_{}.json", std::process::id()));
+        std::fs::write(&path, format_golden(&tokens)).unwrap();
+        assert_golden(&lex("say \"hi\"\n"), &path);
+
+        let changed = lex("say \"ho\"\n");
+        let err = catch_unwind(|| assert_golden(&changed, &path)).unwrap_err();
+        assert!(err.downcast_ref::<String>().unwrap().contains("at line 5"));
+        std::fs::remove_file(&path).unwrap();
+        assert!(catch_unwind(|| assert_golden(&tokens, &path)).is_err());
+    }
+
+    #[cfg(feature = "token-dump")]
+    #[test]
+    fn token_encode_decode_round_trip() {
+        use crate::input::InputReader;
+        use crate::token::{decode_tokens, encode_tokens};
+        use crate::LexxError;
+        use std::io::Cursor;
+
+        let src = "The ünïcode fox, jumps + over;\n".repeat(500);
+        let lexx = Lexx::<512>::new(
+            Box::new(InputReader::new(Cursor::new(src.into_bytes()))),
+            vec![
+                Box::new(WordMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    trailing: &[],
+                }),
+                Box::new(WhitespaceMatcher {
+                    index: 0,
+                    column: 0,
+                    line: 0,
+                    precedence: 0,
+                    running: true,
+                    predicate: char::is_whitespace,
+                    line_continuation: None,
+                    mixed_indent: false,
+                }),
+                Box::new(SymbolMatcher {
+                    index: 0,
+                    precedence: 0,
+                    running: true,
+                    max_len: 0,
+                }),
+            ],
+        );
+        let mut tokens: Vec<Token> = lexx.collect();
+        assert!(tokens.len() > 5000);
+        tokens[2].leading_trivia = String::from(" \t");
+        tokens[2].normalized = Some(String::from("ünïcode"));
+
+        let blob = encode_tokens(&tokens);
+        assert_eq!(decode_tokens(&blob), Ok(tokens));
+
+        assert!(matches!(decode_tokens(&blob[..blob.len() - 1]), Err(LexxError::Error(_))));
+        let mut long = blob.clone();
+        long.push(0);
+        assert!(matches!(decode_tokens(&long), Err(LexxError::Error(_))));
+        assert_eq!(decode_tokens(&encode_tokens(&[])), Ok(vec![]));
     }
 }